@@ -165,7 +165,7 @@ impl CommonWidget<GState> for CustomWidget {
 }
 
 impl Event<GState> for CustomWidget {
-    fn handle_mouse_event(&mut self, event: &MouseEvent, consumed: &bool, global_state: &mut GState) {
+    fn handle_mouse_event(&mut self, event: &MouseEvent, global_state: &mut GState) {
         ()
     }
 
@@ -198,12 +198,12 @@ impl Layout<GState> for CustomWidget {
         self.dimension
     }
 
-    fn position_children(&mut self) {
-        let positioning = BasicLayouter::Center.position();
+    fn position_children(&mut self, env: &mut Environment<GS>) {
+        let positioning = BasicLayouter::Center.position(env);
         let position = self.position;
         let dimension = self.dimension;
         positioning(position, dimension, &mut self.child);
-        self.child.position_children();
+        self.child.position_children(env);
     }
 }
 
@@ -3,6 +3,8 @@ extern crate glium;
 extern crate image;
 
 use std;
+use std::io;
+use std::path::Path;
 
 use glium::backend::glutin::Display;
 use glium::glutin::WindowBuilder;
@@ -87,6 +89,13 @@ impl<S: 'static + Clone> Window<S> {
         self.ui.widgets = w
     }
 
+    /// Persist the persistent local states to the file at the path. The states are restored
+    /// immediately, and saved when the window is closed. Returns an error if the file exists but
+    /// the states could not be restored from it.
+    pub fn set_persistent_state_path<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        self.ui.set_persistent_state_path(path)
+    }
+
     pub fn draw(&mut self) {
         let mut events = Vec::new();
 
@@ -155,6 +164,10 @@ impl<S: 'static + Clone> Window<S> {
                 target.finish().unwrap();
             }
         }
+
+        if let Err(err) = self.ui.save_persistent_state() {
+            eprintln!("Could not save the persistent state: {}", err);
+        }
     }
 }
 
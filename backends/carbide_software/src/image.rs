@@ -0,0 +1,50 @@
+use std::path::Path;
+
+use image::RgbaImage;
+
+use carbide_core::mesh;
+use carbide_core::widget::types::image_information::ImageInformation;
+
+/// An image loaded into memory, ready to be sampled by the rasterizer.
+pub struct Image {
+    /// The pixel data of the image in the sRGB color space.
+    pub data: RgbaImage,
+    /// The width of the image.
+    pub width: u32,
+    /// The height of the image.
+    pub height: u32,
+}
+
+impl mesh::mesh::ImageDimensions for Image {
+    fn dimensions(&self) -> [u32; 2] {
+        [self.width, self.height]
+    }
+}
+
+impl Image {
+    pub fn image_information(&self) -> ImageInformation {
+        ImageInformation {
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    pub fn new<P>(path: P) -> Self
+        where P: AsRef<Path> {
+        let data = image::open(path)
+            .expect("Couldn't load image")
+            .to_rgba();
+
+        Self::from_rgba(data)
+    }
+
+    pub fn from_rgba(data: RgbaImage) -> Self {
+        let (width, height) = data.dimensions();
+
+        Image {
+            data,
+            width,
+            height,
+        }
+    }
+}
@@ -0,0 +1,13 @@
+//! A CPU backend for carbide.
+//!
+//! The `Renderer` rasterizes the vertices and commands produced by the `Mesh` into an
+//! `image::RgbaImage`, following the same rules as the shaders used by `carbide_wgpu`. This makes
+//! it possible to take screenshots of a `Ui` on machines without a GPU.
+
+mod image;
+mod rasterizer;
+pub mod renderer;
+mod texture;
+
+pub use crate::image::Image;
+pub use crate::renderer::Renderer;
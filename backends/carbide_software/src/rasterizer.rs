@@ -0,0 +1,220 @@
+use image::{Rgba, RgbaImage};
+
+use carbide_core::mesh::mesh::Scizzor;
+use carbide_core::mesh::vertex::Vertex;
+
+use crate::texture::linear_to_srgb;
+
+/// The interpolated vertex attributes for a single pixel.
+pub(crate) struct Fragment {
+    pub rgba: [f32; 4],
+    pub tex_coords: [f32; 2],
+    pub mode: u32,
+}
+
+/// A render target storing linear colors, that is converted to sRGB when turned into an image,
+/// like the `Bgra8UnormSrgb` swap chain used by the wgpu backend.
+pub(crate) struct Target {
+    width: u32,
+    height: u32,
+    pixels: Vec<[f32; 4]>,
+}
+
+impl Target {
+    pub fn new(width: u32, height: u32, clear_color: [f32; 4]) -> Self {
+        Target {
+            width,
+            height,
+            pixels: vec![clear_color; width as usize * height as usize],
+        }
+    }
+
+    /// Draw the vertices as a list of triangles. Only pixels with their centers inside the
+    /// triangles and the scizzor are shaded. Pixel centers exactly on an edge are handled using
+    /// the top-left rule, such that pixels on edges shared by two triangles are only drawn once.
+    pub fn draw_triangles<F>(&mut self, vertices: &[Vertex], scizzor: &Scizzor, shader: F)
+        where F: Fn(&Fragment) -> [f32; 4] {
+        let min_x = scizzor.top_left[0].max(0) as i64;
+        let min_y = scizzor.top_left[1].max(0) as i64;
+        let max_x = (scizzor.top_left[0] as i64 + scizzor.dimensions[0] as i64).min(self.width as i64);
+        let max_y = (scizzor.top_left[1] as i64 + scizzor.dimensions[1] as i64).min(self.height as i64);
+
+        for triangle in vertices.chunks_exact(3) {
+            self.draw_triangle(triangle, [min_x, min_y, max_x, max_y], &shader);
+        }
+    }
+
+    fn draw_triangle<F>(&mut self, triangle: &[Vertex], bounds: [i64; 4], shader: &F)
+        where F: Fn(&Fragment) -> [f32; 4] {
+        // The mode is flat, so it is taken from the provoking vertex.
+        let mode = triangle[0].mode;
+
+        let mut v0 = &triangle[0];
+        let mut v1 = &triangle[1];
+        let mut v2 = &triangle[2];
+
+        let mut p0 = self.to_pixel(v0);
+        let mut p1 = self.to_pixel(v1);
+        let mut p2 = self.to_pixel(v2);
+
+        let mut area = edge(p0, p1, p2);
+
+        if area == 0.0 {
+            return;
+        }
+
+        // Make sure the triangles are always wound the same way.
+        if area < 0.0 {
+            std::mem::swap(&mut v1, &mut v2);
+            std::mem::swap(&mut p1, &mut p2);
+            area = -area;
+        }
+
+        let tri_min_x = p0[0].min(p1[0]).min(p2[0]).floor() as i64;
+        let tri_min_y = p0[1].min(p1[1]).min(p2[1]).floor() as i64;
+        let tri_max_x = p0[0].max(p1[0]).max(p2[0]).ceil() as i64;
+        let tri_max_y = p0[1].max(p1[1]).max(p2[1]).ceil() as i64;
+
+        let start_x = tri_min_x.max(bounds[0]);
+        let start_y = tri_min_y.max(bounds[1]);
+        let end_x = tri_max_x.min(bounds[2]);
+        let end_y = tri_max_y.min(bounds[3]);
+
+        let top_left_0 = is_top_left(p1, p2);
+        let top_left_1 = is_top_left(p2, p0);
+        let top_left_2 = is_top_left(p0, p1);
+
+        for y in start_y..end_y {
+            for x in start_x..end_x {
+                let p = [x as f32 + 0.5, y as f32 + 0.5];
+
+                let w0 = edge(p1, p2, p);
+                let w1 = edge(p2, p0, p);
+                let w2 = edge(p0, p1, p);
+
+                if !inside(w0, top_left_0) || !inside(w1, top_left_1) || !inside(w2, top_left_2) {
+                    continue;
+                }
+
+                let l0 = w0 / area;
+                let l1 = w1 / area;
+                let l2 = w2 / area;
+
+                let interpolate = |a: f32, b: f32, c: f32| a * l0 + b * l1 + c * l2;
+
+                let fragment = Fragment {
+                    rgba: [
+                        interpolate(v0.rgba[0], v1.rgba[0], v2.rgba[0]),
+                        interpolate(v0.rgba[1], v1.rgba[1], v2.rgba[1]),
+                        interpolate(v0.rgba[2], v1.rgba[2], v2.rgba[2]),
+                        interpolate(v0.rgba[3], v1.rgba[3], v2.rgba[3]),
+                    ],
+                    tex_coords: [
+                        interpolate(v0.tex_coords[0], v1.tex_coords[0], v2.tex_coords[0]),
+                        interpolate(v0.tex_coords[1], v1.tex_coords[1], v2.tex_coords[1]),
+                    ],
+                    mode,
+                };
+
+                let color = shader(&fragment);
+
+                self.blend(x as usize, y as usize, color);
+            }
+        }
+    }
+
+    /// Convert from normalized device coordinates to pixel coordinates with the origin in the
+    /// top left corner.
+    fn to_pixel(&self, vertex: &Vertex) -> [f32; 2] {
+        [
+            (vertex.position[0] + 1.0) / 2.0 * self.width as f32,
+            (1.0 - vertex.position[1]) / 2.0 * self.height as f32,
+        ]
+    }
+
+    /// Blend using the same blend state as the wgpu render pipeline.
+    fn blend(&mut self, x: usize, y: usize, src: [f32; 4]) {
+        let index = y * self.width as usize + x;
+        let dst = self.pixels[index];
+        let alpha = src[3].max(0.0).min(1.0);
+
+        self.pixels[index] = [
+            src[0] * alpha + dst[0] * (1.0 - alpha),
+            src[1] * alpha + dst[1] * (1.0 - alpha),
+            src[2] * alpha + dst[2] * (1.0 - alpha),
+            alpha + dst[3] * (1.0 - alpha),
+        ];
+    }
+
+    pub fn into_image(self) -> RgbaImage {
+        let Target { width, height, pixels } = self;
+
+        let to_byte = |f: f32| (f.max(0.0).min(1.0) * 255.0).round() as u8;
+
+        RgbaImage::from_fn(width, height, |x, y| {
+            let pixel = pixels[(y * width + x) as usize];
+            Rgba([
+                to_byte(linear_to_srgb(pixel[0])),
+                to_byte(linear_to_srgb(pixel[1])),
+                to_byte(linear_to_srgb(pixel[2])),
+                to_byte(pixel[3]),
+            ])
+        })
+    }
+}
+
+/// Twice the signed area of the triangle (a, b, p).
+fn edge(a: [f32; 2], b: [f32; 2], p: [f32; 2]) -> f32 {
+    (p[0] - a[0]) * (b[1] - a[1]) - (p[1] - a[1]) * (b[0] - a[0])
+}
+
+/// With the winding used by the rasterizer and the y axis pointing down, left edges point
+/// downwards and top edges point to the left.
+fn is_top_left(a: [f32; 2], b: [f32; 2]) -> bool {
+    let dx = b[0] - a[0];
+    let dy = b[1] - a[1];
+
+    dy > 0.0 || (dy == 0.0 && dx < 0.0)
+}
+
+fn inside(w: f32, top_left: bool) -> bool {
+    w > 0.0 || (w == 0.0 && top_left)
+}
+
+#[test]
+fn quad_covers_every_pixel_once() {
+    let vertex = |x: f32, y: f32| Vertex::new_from_2d(x, y, [1.0, 1.0, 1.0, 0.5], [0.0, 0.0], 2);
+
+    let vertices = vec![
+        vertex(-1.0, 1.0), vertex(1.0, 1.0), vertex(-1.0, -1.0),
+        vertex(1.0, 1.0), vertex(1.0, -1.0), vertex(-1.0, -1.0),
+    ];
+
+    let scizzor = Scizzor { top_left: [0, 0], dimensions: [8, 8] };
+
+    let mut target = Target::new(8, 8, [0.0, 0.0, 0.0, 1.0]);
+    target.draw_triangles(&vertices, &scizzor, |fragment| fragment.rgba);
+
+    for pixel in &target.pixels {
+        assert_eq!(*pixel, [0.5, 0.5, 0.5, 1.0]);
+    }
+}
+
+#[test]
+fn scizzor_limits_drawing() {
+    let vertex = |x: f32, y: f32| Vertex::new_from_2d(x, y, [1.0, 1.0, 1.0, 1.0], [0.0, 0.0], 2);
+
+    let vertices = vec![
+        vertex(-1.0, 1.0), vertex(1.0, 1.0), vertex(-1.0, -1.0),
+        vertex(1.0, 1.0), vertex(1.0, -1.0), vertex(-1.0, -1.0),
+    ];
+
+    let scizzor = Scizzor { top_left: [2, 2], dimensions: [4, 4] };
+
+    let mut target = Target::new(8, 8, [0.0, 0.0, 0.0, 1.0]);
+    target.draw_triangles(&vertices, &scizzor, |fragment| fragment.rgba);
+
+    let drawn = target.pixels.iter().filter(|pixel| pixel[0] == 1.0).count();
+
+    assert_eq!(drawn, 16);
+}
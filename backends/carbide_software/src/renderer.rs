@@ -0,0 +1,194 @@
+use std::path::Path;
+
+use image::RgbaImage;
+
+use carbide_core::{color, Color, OldRect};
+use carbide_core::image_map::{Id, ImageMap};
+use carbide_core::mesh::{DEFAULT_GLYPH_CACHE_DIMS, MODE_ATLAS, MODE_GEOMETRY, MODE_IMAGE, MODE_TEXT};
+use carbide_core::mesh::mesh::{Command, Draw, Mesh, Scizzor};
+use carbide_core::prelude::Environment;
+use carbide_core::render::cprimitives::CPrimitives;
+use carbide_core::state::global_state::GlobalState;
+
+use crate::image::Image;
+use crate::rasterizer::{Fragment, Target};
+use crate::texture::{srgb_to_linear, Texture, TextureFormat};
+
+/// Renders the primitives of a `Ui` to an image using the CPU.
+///
+/// The renderer keeps its own `Mesh` between frames, such that the glyph cache and texture
+/// atlas are reused like in the windowed backends.
+pub struct Renderer {
+    mesh: Mesh,
+    image_map: ImageMap<Image>,
+    clear_color: Color,
+}
+
+impl Renderer {
+    pub fn new() -> Self {
+        Renderer {
+            mesh: Mesh::with_glyph_cache_dimensions(DEFAULT_GLYPH_CACHE_DIMS),
+            image_map: ImageMap::new(),
+            clear_color: color::BLACK,
+        }
+    }
+
+    /// The color the image is cleared with before drawing. Defaults to black like the wgpu backend.
+    pub fn clear_color(mut self, color: Color) -> Self {
+        self.clear_color = color;
+        self
+    }
+
+    /// Load the image at the path and register its dimensions in the environment, such that it
+    /// can be used by `Image` widgets.
+    pub fn add_image<P: AsRef<Path>, GS: GlobalState>(&mut self, path: P, env: &mut Environment<GS>) -> Id {
+        let image = Image::new(path);
+
+        let information = image.image_information();
+
+        let id = self.image_map.insert(image);
+
+        env.insert_image(id, information);
+
+        id
+    }
+
+    pub fn image_map(&self) -> &ImageMap<Image> {
+        &self.image_map
+    }
+
+    /// Rasterize the primitives into an image with the pixel dimensions of the environment.
+    pub fn render<GS: GlobalState>(&mut self, primitives: CPrimitives, env: &Environment<GS>) -> RgbaImage {
+        let [width, height] = env.get_pixel_dimensions();
+
+        // The buffers are read directly after every fill, so there is no need to check if an
+        // upload is required.
+        self.mesh.fill(OldRect::new([0.0, 0.0], [width, height]), env, &self.image_map, primitives).unwrap();
+
+        let (glyph_cache_width, glyph_cache_height) = self.mesh.glyph_cache().dimensions();
+        let glyph_cache = Texture::new(self.mesh.glyph_cache_pixel_buffer(), glyph_cache_width, glyph_cache_height, TextureFormat::R8Unorm);
+
+        let atlas_width = self.mesh.texture_atlas().width();
+        let atlas_height = self.mesh.texture_atlas().height();
+        let atlas = Texture::new(self.mesh.texture_atlas_image_as_bytes(), atlas_width, atlas_height, TextureFormat::Rgba8UnormSrgb);
+
+        let [r, g, b, a] = self.clear_color.to_fsa();
+        let clear_color = [srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b), a];
+
+        let mut target = Target::new(width as u32, height as u32, clear_color);
+
+        let mut scizzor = Scizzor {
+            top_left: [0, 0],
+            dimensions: [width as u32, height as u32],
+        };
+
+        let vertices = self.mesh.vertices();
+
+        for command in self.mesh.commands() {
+            match command {
+                Command::Scizzor(s) => {
+                    scizzor = s;
+                }
+                Command::Draw(Draw::Plain(vertex_range)) => {
+                    target.draw_triangles(&vertices[vertex_range], &scizzor, |fragment| {
+                        shade(fragment, None, &glyph_cache, &atlas)
+                    });
+                }
+                Command::Draw(Draw::Image(image_id, vertex_range)) => {
+                    let image = match self.image_map.get(&image_id) {
+                        Some(image) => image,
+                        None => continue,
+                    };
+
+                    let texture = Texture::new(&image.data, image.width, image.height, TextureFormat::Rgba8UnormSrgb);
+
+                    target.draw_triangles(&vertices[vertex_range], &scizzor, |fragment| {
+                        shade(fragment, Some(&texture), &glyph_cache, &atlas)
+                    });
+                }
+            }
+        }
+
+        target.into_image()
+    }
+}
+
+/// The equivalent of the fragment shader of the wgpu backend.
+fn shade(fragment: &Fragment, image: Option<&Texture>, glyph_cache: &Texture, atlas: &Texture) -> [f32; 4] {
+    match fragment.mode {
+        MODE_TEXT => {
+            let a = glyph_cache.sample(fragment.tex_coords)[0];
+            [fragment.rgba[0], fragment.rgba[1], fragment.rgba[2], a]
+        }
+        MODE_IMAGE => {
+            match image {
+                Some(image) => image.sample(fragment.tex_coords),
+                None => [0.0, 0.0, 0.0, 0.0],
+            }
+        }
+        MODE_GEOMETRY => fragment.rgba,
+        MODE_ATLAS => atlas.sample(fragment.tex_coords),
+        _ => [0.0, 0.0, 0.0, 0.0],
+    }
+}
+
+#[test]
+fn renders_a_ui_with_a_rectangle_an_image_and_text() {
+    use carbide_core::headless::HeadlessUi;
+    use carbide_core::prelude::*;
+    use carbide_core::text::{FontFamily, FontStyle, FontWeight};
+    use carbide_core::widget::{HStack, Image as ImageWidget, Text};
+    use carbide_core::window::TWindow;
+    use image::Rgba;
+
+    let close = |pixel: &Rgba<u8>, expected: [u8; 4]| {
+        pixel.0.iter().zip(expected.iter()).all(|(a, b)| (*a as i32 - *b as i32).abs() <= 2)
+    };
+
+    let mut ui = HeadlessUi::new([200.0, 100.0], 1.0, ());
+    let mut renderer = Renderer::new().clear_color(color::GREEN);
+
+    let mut family = FontFamily::new("NotoSans");
+    family.add_font("fonts/NotoSans/NotoSans-Regular.ttf", FontWeight::Normal, FontStyle::Normal);
+    ui.add_font_family(family);
+
+    let image_path = std::env::temp_dir().join("carbide_software_blue.png");
+    RgbaImage::from_pixel(4, 4, Rgba([0, 0, 255, 255])).save(&image_path).unwrap();
+    let image_id = renderer.add_image(&image_path, ui.environment_mut());
+
+    let root: Box<dyn Widget<()>> = HStack::initialize(vec![
+        Rectangle::initialize(vec![]).fill(color::RED).frame(50.0, 50.0),
+        ImageWidget::new(image_id).resizeable().frame(50.0, 50.0),
+        Text::new("Hi"),
+    ]).spacing(0.0);
+    *ui.widgets_mut() = root;
+
+    let primitives = ui.draw();
+    let image = renderer.render(primitives, ui.environment());
+
+    assert_eq!(image.dimensions(), (200, 100));
+
+    let bounds: Vec<(Point, Dimensions)> = ui.widgets().get_children()
+        .map(|child| (child.get_position(), child.get_dimension()))
+        .collect();
+
+    let center = |(position, dimension): (Point, Dimensions)| {
+        ((position[0] + dimension[0] / 2.0) as u32, (position[1] + dimension[1] / 2.0) as u32)
+    };
+
+    let (x, y) = center(bounds[0]);
+    assert!(close(image.get_pixel(x, y), color::RED.to_byte_fsa()));
+
+    let (x, y) = center(bounds[1]);
+    assert!(close(image.get_pixel(x, y), [0, 0, 255, 255]));
+
+    // Some of the pixels within the text are covered by glyphs.
+    let (position, dimension) = bounds[2];
+    let covered = (position[0] as u32..(position[0] + dimension[0]) as u32)
+        .flat_map(|x| (position[1] as u32..(position[1] + dimension[1]) as u32).map(move |y| (x, y)))
+        .any(|(x, y)| !close(image.get_pixel(x, y), color::GREEN.to_byte_fsa()));
+    assert!(covered);
+
+    // The background is left untouched.
+    assert!(close(image.get_pixel(2, 2), color::GREEN.to_byte_fsa()));
+}
@@ -0,0 +1,100 @@
+/// The pixel formats of the textures sampled by the rasterizer. These match the formats of the
+/// textures created by the wgpu backend.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) enum TextureFormat {
+    /// A single linear channel, used for the glyph cache.
+    R8Unorm,
+    /// Four channels with the color stored in the sRGB color space.
+    Rgba8UnormSrgb,
+}
+
+/// A view into a buffer of pixels that can be sampled with linear filtering and clamped edges,
+/// like the sampler used by the wgpu backend.
+pub(crate) struct Texture<'a> {
+    data: &'a [u8],
+    width: u32,
+    height: u32,
+    format: TextureFormat,
+}
+
+impl<'a> Texture<'a> {
+    pub fn new(data: &'a [u8], width: u32, height: u32, format: TextureFormat) -> Self {
+        Texture {
+            data,
+            width,
+            height,
+            format,
+        }
+    }
+
+    /// Sample the texture at the normalized texture coordinates. The returned color is linear.
+    pub fn sample(&self, tex_coords: [f32; 2]) -> [f32; 4] {
+        if self.width == 0 || self.height == 0 {
+            return [0.0, 0.0, 0.0, 0.0];
+        }
+
+        // Texel centers are at half coordinates.
+        let x = tex_coords[0] * self.width as f32 - 0.5;
+        let y = tex_coords[1] * self.height as f32 - 0.5;
+
+        let x0 = x.floor();
+        let y0 = y.floor();
+        let fx = x - x0;
+        let fy = y - y0;
+
+        let x0 = x0 as i64;
+        let y0 = y0 as i64;
+
+        let top_left = self.texel(x0, y0);
+        let top_right = self.texel(x0 + 1, y0);
+        let bottom_left = self.texel(x0, y0 + 1);
+        let bottom_right = self.texel(x0 + 1, y0 + 1);
+
+        let mut res = [0.0; 4];
+
+        for i in 0..4 {
+            let top = top_left[i] + (top_right[i] - top_left[i]) * fx;
+            let bottom = bottom_left[i] + (bottom_right[i] - bottom_left[i]) * fx;
+            res[i] = top + (bottom - top) * fy;
+        }
+
+        res
+    }
+
+    fn texel(&self, x: i64, y: i64) -> [f32; 4] {
+        let x = x.max(0).min(self.width as i64 - 1) as usize;
+        let y = y.max(0).min(self.height as i64 - 1) as usize;
+
+        match self.format {
+            TextureFormat::R8Unorm => {
+                let index = y * self.width as usize + x;
+                [self.data[index] as f32 / 255.0, 0.0, 0.0, 1.0]
+            }
+            TextureFormat::Rgba8UnormSrgb => {
+                let index = (y * self.width as usize + x) * 4;
+                [
+                    srgb_to_linear(self.data[index] as f32 / 255.0),
+                    srgb_to_linear(self.data[index + 1] as f32 / 255.0),
+                    srgb_to_linear(self.data[index + 2] as f32 / 255.0),
+                    self.data[index + 3] as f32 / 255.0,
+                ]
+            }
+        }
+    }
+}
+
+pub(crate) fn srgb_to_linear(f: f32) -> f32 {
+    if f <= 0.04045 {
+        f / 12.92
+    } else {
+        ((f + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+pub(crate) fn linear_to_srgb(f: f32) -> f32 {
+    if f <= 0.0031308 {
+        f * 12.92
+    } else {
+        1.055 * f.powf(1.0 / 2.4) - 0.055
+    }
+}
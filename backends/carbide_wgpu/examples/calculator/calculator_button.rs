@@ -33,7 +33,7 @@ impl CalculatorButton {
         Box::new(self)
     }
 
-    fn handle_mouse_event(&mut self, event: &MouseEvent, _consumed: &bool, _: &mut Environment<CalculatorState>, global_state: &mut CalculatorState) {
+    fn handle_mouse_event(&mut self, event: &MouseEvent, _: &mut Environment<CalculatorState>, global_state: &mut CalculatorState) {
         if !self.is_inside(event.get_current_mouse_position()) {return}
         match event {
             MouseEvent::Release(_, _, _) => {
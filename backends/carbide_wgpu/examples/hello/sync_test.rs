@@ -154,12 +154,12 @@ impl<S: GlobalState> Layout<S> for SyncTest<S> {
         self.dimension
     }
 
-    fn position_children(&mut self) {
-        let positioning = BasicLayouter::Center.position();
+    fn position_children(&mut self, env: &mut Environment<S>) {
+        let positioning = BasicLayouter::Center.position(env);
         let position = self.position;
         let dimension = self.dimension;
         positioning(position, dimension, &mut self.child);
-        self.child.position_children();
+        self.child.position_children(env);
     }
 }
 
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::io;
 use std::path::{Path, PathBuf};
 use std::thread::sleep;
 use std::time::{Duration, Instant};
@@ -485,6 +486,13 @@ impl<T: GlobalState> Window<T> {
         Ok(())
     }
 
+    /// Persist the persistent local states to the file at the path. The states are restored
+    /// immediately, and saved when the event loop exits. Returns an error if the file exists but
+    /// the states could not be restored from it.
+    pub fn set_persistent_state_path<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        self.ui.set_persistent_state_path(path)
+    }
+
     pub fn run_event_loop(mut self) {
 
         // Make the state sync on event loop run
@@ -546,6 +554,11 @@ impl<T: GlobalState> Window<T> {
                         // request it.
                         self.inner_window.request_redraw();
                     }
+                    Event::LoopDestroyed => {
+                        if let Err(err) = self.ui.save_persistent_state() {
+                            eprintln!("Could not save the persistent state: {}", err);
+                        }
+                    }
                     _ => {}
                 }
             });
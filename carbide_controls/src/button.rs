@@ -184,19 +184,19 @@ impl<T: 'static + Serialize + Clone + Debug + Default + DeserializeOwned, GS: Gl
     fn calculate_size(&mut self, requested_size: Dimensions, env: &mut Environment<GS>) -> Dimensions {
         self.set_width(requested_size[0]);
 
-        self.child.calculate_size(self.dimension, env);
+        self.child.calculate_size_cached(self.dimension, env);
 
         self.dimension
     }
 
-    fn position_children(&mut self) {
-        let positioning = BasicLayouter::Center.position();
+    fn position_children(&mut self, env: &mut Environment<GS>) {
+        let positioning = BasicLayouter::Center.position(env);
         let position = self.get_position();
         let dimension = self.get_dimension();
 
 
         positioning(position, dimension, &mut self.child);
-        self.child.position_children();
+        self.child.position_children(env);
     }
 }
 
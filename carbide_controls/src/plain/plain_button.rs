@@ -1,4 +1,5 @@
 use carbide_core::event_handler::{KeyboardEvent, MouseEvent};
+use carbide_core::gesture::TapGesture;
 use carbide_core::input::Key;
 use carbide_core::input::MouseButton;
 use carbide_core::prelude::Uuid;
@@ -16,6 +17,7 @@ pub struct PlainButton<T, GS> where GS: GlobalState, T: StateContract + 'static
     dimension: Dimensions,
     on_click: Option<fn(myself: &mut Self, env: &mut Environment<GS>, global_state: &mut GS)>,
     on_click_outside: Option<fn(myself: &mut Self, env: &mut Environment<GS>, global_state: &mut GS)>,
+    tap: TapGesture<GS>,
     #[state] is_hovered: BoolState<GS>,
     #[state] is_pressed: BoolState<GS>,
     #[state] local_state: TState<T, GS>,
@@ -65,13 +67,22 @@ impl<T: StateContract + 'static, GS: GlobalState> PlainButton<T, GS> {
             dimension: [0.0, 0.0],
             on_click: None,
             on_click_outside: None,
+            tap: TapGesture::new(),
             is_hovered: false.into(),
             is_pressed: false.into(),
             local_state: CommonState::new(&T::default()).into(),
         })
     }
 
-    fn handle_mouse_event(&mut self, event: &MouseEvent, _: &bool, env: &mut Environment<GS>, global_state: &mut GS) {
+    fn handle_mouse_event(&mut self, event: &MouseEvent, env: &mut Environment<GS>, global_state: &mut GS) {
+        let inside = env.is_hovered(self.id);
+
+        if self.tap.recognize(event, inside).is_some() {
+            if let Some(action) = self.on_click {
+                action(self, env, global_state);
+            }
+        }
+
         match event {
             MouseEvent::Press(MouseButton::Left, mouse_position, _) => {
                 if self.is_inside(*mouse_position) {
@@ -97,11 +108,7 @@ impl<T: StateContract + 'static, GS: GlobalState> PlainButton<T, GS> {
             }
             MouseEvent::Click(MouseButton::Left, mouse_position, _) |
             MouseEvent::NClick(MouseButton::Left, mouse_position, _, _) => {
-                if self.is_inside(*mouse_position) {
-                    if let Some(action) = self.on_click {
-                        action(self, env, global_state);
-                    }
-                } else {
+                if !self.is_inside(*mouse_position) {
                     if let Some(action) = self.on_click_outside {
                         action(self, env, global_state);
                     }
@@ -189,7 +196,7 @@ impl<T: StateContract, GS: GlobalState> Layout<GS> for PlainButton<T, GS> {
 
     fn calculate_size(&mut self, requested_size: Dimensions, env: &mut Environment<GS>) -> Dimensions {
         if let Some(child) = self.get_children_mut().next() {
-            child.calculate_size(requested_size, env);
+            child.calculate_size_cached(requested_size, env);
         }
 
         self.set_dimension(requested_size);
@@ -197,14 +204,14 @@ impl<T: StateContract, GS: GlobalState> Layout<GS> for PlainButton<T, GS> {
         requested_size
     }
 
-    fn position_children(&mut self) {
-        let positioning = BasicLayouter::Center.position();
+    fn position_children(&mut self, env: &mut Environment<GS>) {
+        let positioning = BasicLayouter::Center.position(env);
         let position = self.get_position();
         let dimension = self.get_dimension();
 
         if let Some(child) = self.get_children_mut().next() {
             positioning(position, dimension, child);
-            child.position_children();
+            child.position_children(env);
         }
     }
 }
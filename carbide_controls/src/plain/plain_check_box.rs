@@ -163,7 +163,7 @@ impl<GS: GlobalState> Layout<GS> for PlainCheckBox<GS> {
 
     fn calculate_size(&mut self, requested_size: Dimensions, env: &mut Environment<GS>) -> Dimensions {
         if let Some(child) = self.get_children_mut().next() {
-            child.calculate_size(requested_size, env);
+            child.calculate_size_cached(requested_size, env);
         }
 
         self.set_dimension(requested_size);
@@ -171,14 +171,14 @@ impl<GS: GlobalState> Layout<GS> for PlainCheckBox<GS> {
         requested_size
     }
 
-    fn position_children(&mut self) {
-        let positioning = BasicLayouter::Center.position();
+    fn position_children(&mut self, env: &mut Environment<GS>) {
+        let positioning = BasicLayouter::Center.position(env);
         let position = self.get_position();
         let dimension = self.get_dimension();
 
         if let Some(child) = self.get_children_mut().next() {
             positioning(position, dimension, child);
-            child.position_children();
+            child.position_children(env);
         }
     }
 }
@@ -65,7 +65,7 @@ impl<T: StateContract + 'static, GS: GlobalState> PlainPopUpButton<T, GS> {
         })
     }
 
-    fn handle_mouse_event(&mut self, event: &MouseEvent, _: &bool, env: &mut Environment<GS>, _: &mut GS) {
+    fn handle_mouse_event(&mut self, event: &MouseEvent, env: &mut Environment<GS>, _: &mut GS) {
         if !self.is_inside(event.get_current_mouse_position()) {
             match event {
                 MouseEvent::Press(_, _, _) => {
@@ -158,7 +158,7 @@ impl<T: StateContract + 'static, GS: GlobalState> PlainPopUpButton<T, GS> {
                 self.dimension,
                 env.get_corrected_dimensions());
 
-            overlay.calculate_size([5000.0, 5000.0], env);
+            overlay.calculate_size_cached([5000.0, 5000.0], env);
 
             let popup_x = self.get_x() - 1.0;
 
@@ -241,7 +241,7 @@ impl<T: StateContract + 'static, GS: GlobalState> Layout<GS> for PlainPopUpButto
 
     fn calculate_size(&mut self, requested_size: Dimensions, env: &mut Environment<GS>) -> Dimensions {
         if let Some(child) = self.get_children_mut().next() {
-            child.calculate_size(requested_size, env);
+            child.calculate_size_cached(requested_size, env);
         }
 
         self.set_dimension(requested_size);
@@ -249,14 +249,14 @@ impl<T: StateContract + 'static, GS: GlobalState> Layout<GS> for PlainPopUpButto
         requested_size
     }
 
-    fn position_children(&mut self) {
-        let positioning = BasicLayouter::Center.position();
+    fn position_children(&mut self, env: &mut Environment<GS>) {
+        let positioning = BasicLayouter::Center.position(env);
         let position = self.get_position();
         let dimension = self.get_dimension();
 
         if let Some(child) = self.get_children_mut().next() {
             positioning(position, dimension, child);
-            child.position_children();
+            child.position_children(env);
         }
     }
 }
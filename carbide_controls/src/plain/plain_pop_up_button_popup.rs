@@ -61,9 +61,9 @@ impl<T: Serialize + Clone + Debug + Default + DeserializeOwned + 'static, GS: Gl
         let length: UsizeState<GS> = foreach_state.get_latest_value().len().into();
 
         let max_height_state: Box<dyn State<f64, GS>> = Box::new(CommonState::<f64, GS>::EnvironmentState {
-            function: |e: &Environment<GS>| {
+            function: Box::new(|e: &Environment<GS>| {
                 e.get_corrected_height()
-            },
+            }),
             function_mut: None,
             latest_value: window_size[1],
         });
@@ -244,21 +244,21 @@ impl<T: Serialize + Clone + Debug + Default + DeserializeOwned + 'static, GS: Gl
     }
 
     fn calculate_size(&mut self, requested_size: Dimensions, env: &mut Environment<GS>) -> Dimensions {
-        let size = self.child.calculate_size(requested_size, env);
+        let size = self.child.calculate_size_cached(requested_size, env);
 
         self.set_dimension(size);
 
         size
     }
 
-    fn position_children(&mut self) {
-        let positioning = BasicLayouter::Center.position();
+    fn position_children(&mut self, env: &mut Environment<GS>) {
+        let positioning = BasicLayouter::Center.position(env);
         let position = self.get_position();
         let dimension = self.get_dimension();
 
         if let Some(child) = self.get_children_mut().next() {
             positioning(position, dimension, child);
-            child.position_children();
+            child.position_children(env);
         }
     }
 }
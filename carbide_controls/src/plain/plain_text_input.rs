@@ -262,7 +262,7 @@ impl<GS: GlobalState> PlainTextInput<GS> {
         }
     }
 
-    fn handle_mouse_event(&mut self, event: &MouseEvent, _: &bool, env: &mut Environment<GS>, global_state: &mut GS) {
+    fn handle_mouse_event(&mut self, event: &MouseEvent, env: &mut Environment<GS>, global_state: &mut GS) {
         if !self.is_inside(event.get_current_mouse_position()) {
             match event {
                 MouseEvent::Press(_, _, _) => {
@@ -419,7 +419,7 @@ impl<GS: GlobalState> PlainTextInput<GS> {
                     }
                 };
 
-                match (key, modifier).into() {
+                match TextInputKeyCommand::triggered_by(*key, *modifier, env) {
                     TextInputKeyCommand::MoveLeft => {
                         let current_char = current_movable_cursor_index.char;
                         let moved_char = if current_char == 0 { 0 } else { current_char - 1 };
@@ -874,9 +874,17 @@ impl<GS: GlobalState> Layout<GS> for PlainTextInput<GS> {
     }
 
     fn calculate_size(&mut self, requested_size: Dimensions, env: &mut Environment<GS>) -> Dimensions {
+        // The commands are declared together, and are only forgotten together when the text
+        // input is no longer shown, so checking the first is enough.
+        if !env.commands().is_declared_by(TextInputKeyCommand::ALL[0].id(), self.id) {
+            for command in TextInputKeyCommand::ALL.iter() {
+                env.commands_mut().declare(self.id, &command.command());
+            }
+        }
+
         let mut dimensions = [0.0, 0.0];
         if let Some(child) = self.get_children_mut().next() {
-            dimensions = child.calculate_size(requested_size, env);
+            dimensions = child.calculate_size_cached(requested_size, env);
         }
 
         self.set_dimension([requested_size[0], dimensions[1]]);
@@ -884,16 +892,53 @@ impl<GS: GlobalState> Layout<GS> for PlainTextInput<GS> {
         self.get_dimension()
     }
 
-    fn position_children(&mut self) {
-        let positioning = BasicLayouter::Center.position();
+    fn position_children(&mut self, env: &mut Environment<GS>) {
+        let positioning = BasicLayouter::Center.position(env);
         let position = self.get_position();
         let dimension = self.get_dimension();
 
         if let Some(child) = self.get_children_mut().next() {
             positioning(position, dimension, child);
-            child.position_children();
+            child.position_children(env);
         }
     }
 }
 
-impl<GS: GlobalState> WidgetExt<GS> for PlainTextInput<GS> {}
\ No newline at end of file
+impl<GS: GlobalState> WidgetExt<GS> for PlainTextInput<GS> {}
+#[test]
+fn typed_text_is_inserted_in_the_focused_input() {
+    use carbide_core::headless::HeadlessUi;
+    use carbide_core::text::{FontFamily, FontStyle, FontWeight};
+    use carbide_core::window::TWindow;
+
+    fn text(global_state: &String) -> &String {
+        global_state
+    }
+
+    fn text_mut(global_state: &mut String) -> &mut String {
+        global_state
+    }
+
+    let mut ui = HeadlessUi::new([400.0, 200.0], 1.0, String::new());
+
+    let mut family = FontFamily::new("NotoSans");
+    family.add_font("fonts/NotoSans/NotoSans-Regular.ttf", FontWeight::Normal, FontStyle::Normal);
+    ui.add_font_family(family);
+
+    let text_state = CommonState::GlobalState {
+        function: Box::new(text),
+        function_mut: Box::new(text_mut),
+        latest_value: String::new(),
+    };
+
+    ui.set_widgets(PlainTextInput::new(text_state).frame(200.0, 30.0));
+    ui.draw();
+
+    // Typing before the input is focused does nothing.
+    ui.type_text("ignored");
+    assert_eq!(ui.global_state(), "");
+
+    ui.click_at([200.0, 100.0]);
+    ui.type_text("carbide");
+    assert_eq!(ui.global_state(), "carbide");
+}
@@ -1,6 +1,10 @@
+use carbide_core::environment::command_registry::{Command, PRIMARY_MODIFIER};
 use carbide_core::input::Key;
 use carbide_core::input::ModifierKey;
+use carbide_core::prelude::Environment;
+use carbide_core::state::global_state::GlobalState;
 
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum TextInputKeyCommand {
     MoveLeft,
     MoveRight,
@@ -29,74 +33,136 @@ pub enum TextInputKeyCommand {
     Undefined,
 }
 
-#[cfg(target_os = "windows")]
-impl From<(&Key, &ModifierKey)> for TextInputKeyCommand {
-    fn from((key, modifier): (&Key, &ModifierKey)) -> Self {
-        match (key, modifier) {
-            (Key::Left, &ModifierKey::NO_MODIFIER) => TextInputKeyCommand::MoveLeft,
-            (Key::Left, &ModifierKey::SHIFT) => TextInputKeyCommand::SelectLeft,
-            (Key::Left, &ModifierKey::CTRL) => TextInputKeyCommand::JumpWordLeft,
-            (Key::Left, &ModifierKey::CTRL_SHIFT) => TextInputKeyCommand::JumpSelectWordLeft,
-            (Key::Right, &ModifierKey::NO_MODIFIER) => TextInputKeyCommand::MoveRight,
-            (Key::Right, &ModifierKey::SHIFT) => TextInputKeyCommand::SelectRight,
-            (Key::Right, &ModifierKey::CTRL) => TextInputKeyCommand::JumpWordRight,
-            (Key::Right, &ModifierKey::CTRL_SHIFT) => TextInputKeyCommand::JumpSelectWordRight,
-            (Key::Backspace, &ModifierKey::NO_MODIFIER) => TextInputKeyCommand::RemoveLeft,
-            (Key::Backspace, &ModifierKey::SHIFT) => TextInputKeyCommand::RemoveLeft,
-            (Key::Backspace, &ModifierKey::CTRL) => TextInputKeyCommand::RemoveWordLeft,
-            (Key::Delete, &ModifierKey::NO_MODIFIER) => TextInputKeyCommand::RemoveRight,
-            (Key::Delete, &ModifierKey::SHIFT) => TextInputKeyCommand::RemoveAll,
-            (Key::Delete, &ModifierKey::CTRL) => TextInputKeyCommand::RemoveWordRight,
-            (Key::C, &ModifierKey::CTRL) => TextInputKeyCommand::Copy,
-            (Key::V, &ModifierKey::CTRL) => TextInputKeyCommand::Paste,
-            (Key::X, &ModifierKey::CTRL) => TextInputKeyCommand::Clip,
-            (Key::A, &ModifierKey::CTRL) => TextInputKeyCommand::SelectAll,
-            (Key::D, &ModifierKey::CTRL) => TextInputKeyCommand::DuplicateRight,
-            (Key::D, &ModifierKey::CTRL_SHIFT) => TextInputKeyCommand::DuplicateLeft,
-            (Key::Home, &ModifierKey::NO_MODIFIER) => TextInputKeyCommand::JumpToLeft,
-            (Key::Home, &ModifierKey::SHIFT) => TextInputKeyCommand::JumpSelectToLeft,
-            (Key::End, &ModifierKey::NO_MODIFIER) => TextInputKeyCommand::JumpToRight,
-            (Key::End, &ModifierKey::SHIFT) => TextInputKeyCommand::JumpSelectToRight,
-            (Key::Return, &ModifierKey::NO_MODIFIER) => TextInputKeyCommand::Enter,
-            _ => TextInputKeyCommand::Undefined
+impl TextInputKeyCommand {
+    /// The commands declared by text inputs, in the order they are declared.
+    pub const ALL: [TextInputKeyCommand; 24] = [
+        TextInputKeyCommand::MoveLeft,
+        TextInputKeyCommand::MoveRight,
+        TextInputKeyCommand::SelectLeft,
+        TextInputKeyCommand::SelectRight,
+        TextInputKeyCommand::RemoveLeft,
+        TextInputKeyCommand::RemoveRight,
+        TextInputKeyCommand::JumpWordLeft,
+        TextInputKeyCommand::JumpWordRight,
+        TextInputKeyCommand::JumpSelectWordLeft,
+        TextInputKeyCommand::JumpSelectWordRight,
+        TextInputKeyCommand::RemoveWordLeft,
+        TextInputKeyCommand::RemoveWordRight,
+        TextInputKeyCommand::DuplicateLeft,
+        TextInputKeyCommand::DuplicateRight,
+        TextInputKeyCommand::Copy,
+        TextInputKeyCommand::Paste,
+        TextInputKeyCommand::Clip,
+        TextInputKeyCommand::SelectAll,
+        TextInputKeyCommand::RemoveAll,
+        TextInputKeyCommand::JumpToLeft,
+        TextInputKeyCommand::JumpToRight,
+        TextInputKeyCommand::JumpSelectToLeft,
+        TextInputKeyCommand::JumpSelectToRight,
+        TextInputKeyCommand::Enter,
+    ];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            TextInputKeyCommand::MoveLeft => "text_input.move_left",
+            TextInputKeyCommand::MoveRight => "text_input.move_right",
+            TextInputKeyCommand::SelectLeft => "text_input.select_left",
+            TextInputKeyCommand::SelectRight => "text_input.select_right",
+            TextInputKeyCommand::RemoveLeft => "text_input.remove_left",
+            TextInputKeyCommand::RemoveRight => "text_input.remove_right",
+            TextInputKeyCommand::JumpWordLeft => "text_input.jump_word_left",
+            TextInputKeyCommand::JumpWordRight => "text_input.jump_word_right",
+            TextInputKeyCommand::JumpSelectWordLeft => "text_input.jump_select_word_left",
+            TextInputKeyCommand::JumpSelectWordRight => "text_input.jump_select_word_right",
+            TextInputKeyCommand::RemoveWordLeft => "text_input.remove_word_left",
+            TextInputKeyCommand::RemoveWordRight => "text_input.remove_word_right",
+            TextInputKeyCommand::DuplicateLeft => "text_input.duplicate_left",
+            TextInputKeyCommand::DuplicateRight => "text_input.duplicate_right",
+            TextInputKeyCommand::Copy => "text_input.copy",
+            TextInputKeyCommand::Paste => "text_input.paste",
+            TextInputKeyCommand::Clip => "text_input.clip",
+            TextInputKeyCommand::SelectAll => "text_input.select_all",
+            TextInputKeyCommand::RemoveAll => "text_input.remove_all",
+            TextInputKeyCommand::JumpToLeft => "text_input.jump_to_left",
+            TextInputKeyCommand::JumpToRight => "text_input.jump_to_right",
+            TextInputKeyCommand::JumpSelectToLeft => "text_input.jump_select_to_left",
+            TextInputKeyCommand::JumpSelectToRight => "text_input.jump_select_to_right",
+            TextInputKeyCommand::Enter => "text_input.enter",
+            TextInputKeyCommand::Undefined => "text_input.undefined",
         }
     }
-}
-
-#[cfg(target_os = "macos")]
-impl From<(&Key, &ModifierKey)> for TextInputKeyCommand {
-    fn from((key, modifier): (&Key, &ModifierKey)) -> Self {
-        match (key, modifier) {
-            (Key::Left, &ModifierKey::NO_MODIFIER) => TextInputKeyCommand::MoveLeft,
-            (Key::Left, &ModifierKey::SHIFT) => TextInputKeyCommand::SelectLeft,
-            (Key::Left, &ModifierKey::GUI) => TextInputKeyCommand::JumpWordLeft,
-            (Key::Left, &ModifierKey::CTRL_SHIFT) => TextInputKeyCommand::JumpSelectWordLeft,
 
-            (Key::Right, &ModifierKey::NO_MODIFIER) => TextInputKeyCommand::MoveRight,
-            (Key::Right, &ModifierKey::SHIFT) => TextInputKeyCommand::SelectRight,
-            (Key::Right, &ModifierKey::GUI) => TextInputKeyCommand::JumpWordRight,
-            (Key::Right, &ModifierKey::CTRL_SHIFT) => TextInputKeyCommand::JumpSelectWordRight,
-
-            (Key::Backspace, &ModifierKey::NO_MODIFIER) => TextInputKeyCommand::RemoveLeft,
-            (Key::Backspace, &ModifierKey::SHIFT) => TextInputKeyCommand::RemoveLeft,
-            (Key::Backspace, &ModifierKey::CTRL) => TextInputKeyCommand::RemoveWordLeft,
-            (Key::Delete, &ModifierKey::NO_MODIFIER) => TextInputKeyCommand::RemoveRight,
-            (Key::Delete, &ModifierKey::SHIFT) => TextInputKeyCommand::RemoveAll,
-            (Key::Delete, &ModifierKey::CTRL) => TextInputKeyCommand::RemoveWordRight,
+    pub fn name(&self) -> &'static str {
+        match self {
+            TextInputKeyCommand::MoveLeft => "Move left",
+            TextInputKeyCommand::MoveRight => "Move right",
+            TextInputKeyCommand::SelectLeft => "Select left",
+            TextInputKeyCommand::SelectRight => "Select right",
+            TextInputKeyCommand::RemoveLeft => "Remove left",
+            TextInputKeyCommand::RemoveRight => "Remove right",
+            TextInputKeyCommand::JumpWordLeft => "Jump word left",
+            TextInputKeyCommand::JumpWordRight => "Jump word right",
+            TextInputKeyCommand::JumpSelectWordLeft => "Select word left",
+            TextInputKeyCommand::JumpSelectWordRight => "Select word right",
+            TextInputKeyCommand::RemoveWordLeft => "Remove word left",
+            TextInputKeyCommand::RemoveWordRight => "Remove word right",
+            TextInputKeyCommand::DuplicateLeft => "Duplicate left",
+            TextInputKeyCommand::DuplicateRight => "Duplicate right",
+            TextInputKeyCommand::Copy => "Copy",
+            TextInputKeyCommand::Paste => "Paste",
+            TextInputKeyCommand::Clip => "Cut",
+            TextInputKeyCommand::SelectAll => "Select all",
+            TextInputKeyCommand::RemoveAll => "Remove all",
+            TextInputKeyCommand::JumpToLeft => "Jump to start",
+            TextInputKeyCommand::JumpToRight => "Jump to end",
+            TextInputKeyCommand::JumpSelectToLeft => "Select to start",
+            TextInputKeyCommand::JumpSelectToRight => "Select to end",
+            TextInputKeyCommand::Enter => "Enter",
+            TextInputKeyCommand::Undefined => "Undefined",
+        }
+    }
 
-            (Key::C, &ModifierKey::GUI) => TextInputKeyCommand::Copy,
-            (Key::V, &ModifierKey::GUI) => TextInputKeyCommand::Paste,
-            (Key::X, &ModifierKey::GUI) => TextInputKeyCommand::Clip,
-            (Key::A, &ModifierKey::GUI) => TextInputKeyCommand::SelectAll,
-            (Key::D, &ModifierKey::GUI) => TextInputKeyCommand::DuplicateRight,
-            (Key::D, &ModifierKey::SHIFT_GUI) => TextInputKeyCommand::DuplicateLeft,
+    /// The command without a handler, bound to the default keys of the current platform. It is
+    /// declared by the text inputs, which handle the command themselves.
+    pub fn command<GS: GlobalState>(&self) -> Command<GS> {
+        let command = Command::new(self.id(), self.name());
 
-            (Key::Home, &ModifierKey::NO_MODIFIER) => TextInputKeyCommand::JumpToLeft,
-            (Key::Home, &ModifierKey::SHIFT) => TextInputKeyCommand::JumpSelectToLeft,
-            (Key::End, &ModifierKey::NO_MODIFIER) => TextInputKeyCommand::JumpToRight,
-            (Key::End, &ModifierKey::SHIFT) => TextInputKeyCommand::JumpSelectToRight,
-            (Key::Return, &ModifierKey::NO_MODIFIER) => TextInputKeyCommand::Enter,
-            _ => TextInputKeyCommand::Undefined
+        match self {
+            TextInputKeyCommand::MoveLeft => command.binding(Key::Left, ModifierKey::NO_MODIFIER),
+            TextInputKeyCommand::MoveRight => command.binding(Key::Right, ModifierKey::NO_MODIFIER),
+            TextInputKeyCommand::SelectLeft => command.binding(Key::Left, ModifierKey::SHIFT),
+            TextInputKeyCommand::SelectRight => command.binding(Key::Right, ModifierKey::SHIFT),
+            TextInputKeyCommand::RemoveLeft => command.binding(Key::Backspace, ModifierKey::NO_MODIFIER)
+                .alternative_binding(Key::Backspace, ModifierKey::SHIFT),
+            TextInputKeyCommand::RemoveRight => command.binding(Key::Delete, ModifierKey::NO_MODIFIER),
+            TextInputKeyCommand::JumpWordLeft => command.binding(Key::Left, PRIMARY_MODIFIER),
+            TextInputKeyCommand::JumpWordRight => command.binding(Key::Right, PRIMARY_MODIFIER),
+            TextInputKeyCommand::JumpSelectWordLeft => command.binding(Key::Left, ModifierKey::CTRL_SHIFT),
+            TextInputKeyCommand::JumpSelectWordRight => command.binding(Key::Right, ModifierKey::CTRL_SHIFT),
+            TextInputKeyCommand::RemoveWordLeft => command.binding(Key::Backspace, ModifierKey::CTRL),
+            TextInputKeyCommand::RemoveWordRight => command.binding(Key::Delete, ModifierKey::CTRL),
+            TextInputKeyCommand::DuplicateLeft => command.binding(Key::D, PRIMARY_MODIFIER | ModifierKey::SHIFT),
+            TextInputKeyCommand::DuplicateRight => command.binding(Key::D, PRIMARY_MODIFIER),
+            TextInputKeyCommand::Copy => command.binding(Key::C, PRIMARY_MODIFIER),
+            TextInputKeyCommand::Paste => command.binding(Key::V, PRIMARY_MODIFIER),
+            TextInputKeyCommand::Clip => command.binding(Key::X, PRIMARY_MODIFIER),
+            TextInputKeyCommand::SelectAll => command.binding(Key::A, PRIMARY_MODIFIER),
+            TextInputKeyCommand::RemoveAll => command.binding(Key::Delete, ModifierKey::SHIFT),
+            TextInputKeyCommand::JumpToLeft => command.binding(Key::Home, ModifierKey::NO_MODIFIER),
+            TextInputKeyCommand::JumpToRight => command.binding(Key::End, ModifierKey::NO_MODIFIER),
+            TextInputKeyCommand::JumpSelectToLeft => command.binding(Key::Home, ModifierKey::SHIFT),
+            TextInputKeyCommand::JumpSelectToRight => command.binding(Key::End, ModifierKey::SHIFT),
+            TextInputKeyCommand::Enter => command.binding(Key::Return, ModifierKey::NO_MODIFIER),
+            TextInputKeyCommand::Undefined => command,
         }
     }
-}
\ No newline at end of file
+
+    /// The command triggered by the keys, taking the bindings in the command registry of the
+    /// environment into account.
+    pub fn triggered_by<GS: GlobalState>(key: Key, modifiers: ModifierKey, env: &Environment<GS>) -> TextInputKeyCommand {
+        TextInputKeyCommand::ALL.iter()
+            .find(|command| env.commands().is_triggered_by(command.id(), key, modifiers))
+            .cloned()
+            .unwrap_or(TextInputKeyCommand::Undefined)
+    }
+}
@@ -0,0 +1,386 @@
+use std::fmt;
+use std::fmt::{Debug, Display, Formatter};
+
+use dyn_clone::DynClone;
+use fxhash::FxHashMap;
+use uuid::Uuid;
+
+use crate::input::{Key, ModifierKey};
+use crate::prelude::Environment;
+use crate::state::global_state::GlobalState;
+
+/// A key pressed together with a combination of modifier keys.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct KeyBinding {
+    pub key: Key,
+    pub modifiers: ModifierKey,
+}
+
+impl KeyBinding {
+    pub fn new(key: Key, modifiers: ModifierKey) -> Self {
+        KeyBinding { key, modifiers }
+    }
+
+    /// The key together with the modifier used for application shortcuts on the current
+    /// platform: command on macOS and control elsewhere.
+    pub fn primary(key: Key) -> Self {
+        KeyBinding::new(key, PRIMARY_MODIFIER)
+    }
+}
+
+/// The modifier used for application shortcuts on the current platform.
+#[cfg(not(target_os = "macos"))]
+pub const PRIMARY_MODIFIER: ModifierKey = ModifierKey::CTRL;
+
+/// The modifier used for application shortcuts on the current platform.
+#[cfg(target_os = "macos")]
+pub const PRIMARY_MODIFIER: ModifierKey = ModifierKey::GUI;
+
+/// Formats the binding like `Ctrl+Shift+S`, for showing it to the user.
+impl Display for KeyBinding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let names = [
+            (ModifierKey::CTRL, "Ctrl"),
+            (ModifierKey::ALT, "Alt"),
+            (ModifierKey::SHIFT, "Shift"),
+            (ModifierKey::GUI, if cfg!(target_os = "macos") { "Cmd" } else { "Super" }),
+        ];
+
+        for (modifier, name) in names.iter() {
+            if self.modifiers.contains(*modifier) {
+                write!(f, "{}+", name)?;
+            }
+        }
+
+        write!(f, "{:?}", self.key)
+    }
+}
+
+pub trait CommandFn<GS: GlobalState>: Fn(&mut Environment<GS>, &mut GS) + DynClone {}
+
+impl<GS: GlobalState, F> CommandFn<GS> for F where F: Fn(&mut Environment<GS>, &mut GS) + DynClone {}
+
+dyn_clone::clone_trait_object!(<GS: GlobalState> CommandFn<GS>);
+
+impl<GS: GlobalState> Debug for dyn CommandFn<GS> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "CommandFn")
+    }
+}
+
+pub trait CommandCondition<GS: GlobalState>: Fn(&Environment<GS>, &GS) -> bool + DynClone {}
+
+impl<GS: GlobalState, F> CommandCondition<GS> for F where F: Fn(&Environment<GS>, &GS) -> bool + DynClone {}
+
+dyn_clone::clone_trait_object!(<GS: GlobalState> CommandCondition<GS>);
+
+impl<GS: GlobalState> Debug for dyn CommandCondition<GS> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "CommandCondition")
+    }
+}
+
+/// An action of the application that can be triggered by a keyboard shortcut. The id is used to
+/// refer to the command when remapping or disabling it, and the name is shown to the user.
+///
+/// Commands without a handler are handled by the widgets declaring them, like `KeyboardShortcut`.
+#[derive(Clone, Debug)]
+pub struct Command<GS> where GS: GlobalState {
+    id: String,
+    name: String,
+    default_binding: Option<KeyBinding>,
+    alternative_bindings: Vec<KeyBinding>,
+    enabled: bool,
+    handler: Option<Box<dyn CommandFn<GS>>>,
+    /// Checked before the handler is executed. The keys reach the widgets when it returns false.
+    condition: Option<Box<dyn CommandCondition<GS>>>,
+    /// The ids of the widgets that declared the command. Empty for registered commands.
+    declared_by: Vec<Uuid>,
+}
+
+impl<GS: GlobalState> Command<GS> {
+    pub fn new(id: &str, name: &str) -> Self {
+        Command {
+            id: id.to_string(),
+            name: name.to_string(),
+            default_binding: None,
+            alternative_bindings: vec![],
+            enabled: true,
+            handler: None,
+            condition: None,
+            declared_by: vec![],
+        }
+    }
+
+    /// The binding used unless the user remaps the command.
+    pub fn binding(mut self, key: Key, modifiers: ModifierKey) -> Self {
+        self.default_binding = Some(KeyBinding::new(key, modifiers));
+        self
+    }
+
+    /// Another binding triggering the command, like `Ctrl+Y` for redo next to `Ctrl+Shift+Z`.
+    /// It is removed when the user remaps the command.
+    pub fn alternative_binding(mut self, key: Key, modifiers: ModifierKey) -> Self {
+        self.alternative_bindings.push(KeyBinding::new(key, modifiers));
+        self
+    }
+
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    pub fn on_execute<F: Fn(&mut Environment<GS>, &mut GS) + Clone + 'static>(mut self, handler: F) -> Self {
+        self.handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Only execute the handler when the condition holds, like undo when there is something to
+    /// undo. Otherwise the keys are given to the widgets, as if the command was not bound to them.
+    pub fn can_execute<F: Fn(&Environment<GS>, &GS) -> bool + Clone + 'static>(mut self, condition: F) -> Self {
+        self.condition = Some(Box::new(condition));
+        self
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn default_binding(&self) -> Option<KeyBinding> {
+        self.default_binding
+    }
+
+    pub(crate) fn handler(&self) -> Option<&Box<dyn CommandFn<GS>>> {
+        self.handler.as_ref()
+    }
+
+    pub(crate) fn condition(&self) -> Option<&Box<dyn CommandCondition<GS>>> {
+        self.condition.as_ref()
+    }
+}
+
+/// The commands of the application and the keys bound to them. It is scoped to the environment.
+///
+/// Bindings remapped by the user are kept apart from the default bindings of the commands, such
+/// that they can be saved and restored using `remapped_bindings` and `remap`, and reset again.
+/// When more than one enabled command is bound to the same keys, the first registered wins, and
+/// the commands are listed by `conflicts`.
+#[derive(Clone, Debug)]
+pub struct CommandRegistry<GS> where GS: GlobalState {
+    commands: Vec<Command<GS>>,
+    /// The bindings replacing the default bindings. A `None` removes the binding of the command.
+    remapped: FxHashMap<String, Option<KeyBinding>>,
+}
+
+impl<GS: GlobalState> CommandRegistry<GS> {
+    pub fn new() -> Self {
+        CommandRegistry {
+            commands: vec![],
+            remapped: FxHashMap::default(),
+        }
+    }
+
+    /// Add the command, replacing a command with the same id. A remapped binding for the id is
+    /// kept.
+    pub fn register(&mut self, command: Command<GS>) {
+        match self.commands.iter_mut().find(|existing| existing.id == command.id) {
+            Some(existing) => *existing = command,
+            None => self.commands.push(command),
+        }
+    }
+
+    /// Add the command without its handler, unless a command with the same id is registered
+    /// already. Used by widgets handling the command themselves. The command is removed again
+    /// when none of the widgets declaring it are shown anymore.
+    pub fn declare(&mut self, widget: Uuid, command: &Command<GS>) {
+        match self.commands.iter_mut().find(|existing| existing.id == command.id) {
+            Some(existing) => {
+                if !existing.declared_by.is_empty() && !existing.declared_by.contains(&widget) {
+                    existing.declared_by.push(widget);
+                }
+            }
+            None => self.commands.push(Command {
+                handler: None,
+                declared_by: vec![widget],
+                ..command.clone()
+            }),
+        }
+    }
+
+    /// Check if the command is declared by the widget, or registered. Widgets declaring many
+    /// commands use this to declare them only when they are not declared already.
+    pub fn is_declared_by(&self, id: &str, widget: Uuid) -> bool {
+        self.commands.iter()
+            .find(|command| command.id == id)
+            .map_or(false, |command| command.declared_by.is_empty() || command.declared_by.contains(&widget))
+    }
+
+    /// Forget the widgets that are not shown anymore, removing the commands only declared by
+    /// them.
+    pub(crate) fn retain_declared<F: Fn(Uuid) -> bool>(&mut self, is_shown: F) {
+        self.commands.retain(|command| {
+            command.declared_by.is_empty() || command.declared_by.iter().any(|widget| is_shown(*widget))
+        });
+
+        for command in &mut self.commands {
+            command.declared_by.retain(|widget| is_shown(*widget));
+        }
+    }
+
+    pub fn unregister(&mut self, id: &str) {
+        self.commands.retain(|command| command.id != id);
+    }
+
+    pub fn command(&self, id: &str) -> Option<&Command<GS>> {
+        self.commands.iter().find(|command| command.id == id)
+    }
+
+    pub fn set_enabled(&mut self, id: &str, enabled: bool) {
+        if let Some(command) = self.commands.iter_mut().find(|command| command.id == id) {
+            command.enabled = enabled;
+        }
+    }
+
+    pub fn is_enabled(&self, id: &str) -> bool {
+        self.command(id).map_or(false, |command| command.enabled)
+    }
+
+    /// The binding of the command, taking the remapping by the user into account.
+    pub fn binding(&self, id: &str) -> Option<KeyBinding> {
+        match self.remapped.get(id) {
+            Some(binding) => *binding,
+            None => self.command(id).and_then(|command| command.default_binding),
+        }
+    }
+
+    /// The binding of the command along with its alternative bindings, unless it is remapped.
+    fn all_bindings(&self, id: &str) -> Vec<KeyBinding> {
+        match (self.remapped.get(id), self.command(id)) {
+            (Some(binding), _) => binding.iter().cloned().collect(),
+            (None, Some(command)) => command.default_binding.iter()
+                .chain(command.alternative_bindings.iter())
+                .cloned()
+                .collect(),
+            (None, None) => vec![],
+        }
+    }
+
+    /// Bind the command to other keys, or remove its binding with `None`. Returns the ids of the
+    /// other commands bound to the same keys, such that the conflict can be shown to the user.
+    pub fn remap(&mut self, id: &str, binding: Option<KeyBinding>) -> Vec<String> {
+        self.remapped.insert(id.to_string(), binding);
+
+        match binding {
+            Some(binding) => self.commands_bound_to(binding)
+                .filter(|command| command.id != id)
+                .map(|command| command.id.clone())
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    /// Restore the default binding of the command.
+    pub fn reset_binding(&mut self, id: &str) {
+        self.remapped.remove(id);
+    }
+
+    pub fn reset_all_bindings(&mut self) {
+        self.remapped.clear();
+    }
+
+    /// The bindings changed by the user, as pairs of a command id and its binding.
+    pub fn remapped_bindings(&self) -> impl Iterator<Item=(&str, Option<KeyBinding>)> {
+        self.remapped.iter().map(|(id, binding)| (id.as_str(), *binding))
+    }
+
+    /// All commands with their current bindings, in the order they were registered. Useful for
+    /// showing the shortcuts of the application to the user.
+    pub fn bindings(&self) -> Vec<(&Command<GS>, Option<KeyBinding>)> {
+        self.commands.iter()
+            .map(|command| (command, self.binding(&command.id)))
+            .collect()
+    }
+
+    /// The keys bound to more than one command, with the ids of those commands.
+    pub fn conflicts(&self) -> Vec<(KeyBinding, Vec<String>)> {
+        let mut conflicts: Vec<(KeyBinding, Vec<String>)> = vec![];
+
+        for command in &self.commands {
+            for binding in self.all_bindings(&command.id) {
+                match conflicts.iter_mut().find(|(existing, _)| *existing == binding) {
+                    Some((_, ids)) => ids.push(command.id.clone()),
+                    None => conflicts.push((binding, vec![command.id.clone()])),
+                }
+            }
+        }
+
+        conflicts.retain(|(_, ids)| ids.len() > 1);
+        conflicts
+    }
+
+    /// The enabled command bound to the keys.
+    pub fn command_for(&self, key: Key, modifiers: ModifierKey) -> Option<&Command<GS>> {
+        self.commands_bound_to(KeyBinding::new(key, modifiers))
+            .find(|command| command.enabled)
+    }
+
+    /// True if pressing the keys triggers the command with the id.
+    pub fn is_triggered_by(&self, id: &str, key: Key, modifiers: ModifierKey) -> bool {
+        self.command_for(key, modifiers).map_or(false, |command| command.id == id)
+    }
+
+    fn commands_bound_to(&self, binding: KeyBinding) -> impl Iterator<Item=&Command<GS>> {
+        self.commands.iter()
+            .filter(move |command| self.all_bindings(&command.id).contains(&binding))
+    }
+}
+
+#[test]
+fn remapped_bindings_replace_the_defaults() {
+    let mut registry: CommandRegistry<()> = CommandRegistry::new();
+    registry.register(Command::new("save", "Save").binding(Key::S, ModifierKey::CTRL));
+    registry.register(Command::new("save_as", "Save as").binding(Key::S, ModifierKey::CTRL_SHIFT));
+    registry.register(Command::new("open", "Open").binding(Key::O, ModifierKey::CTRL));
+
+    assert!(registry.conflicts().is_empty());
+    assert_eq!(registry.remap("open", Some(KeyBinding::new(Key::S, ModifierKey::CTRL))), vec!["save".to_string()]);
+    assert_eq!(registry.conflicts(), vec![(KeyBinding::new(Key::S, ModifierKey::CTRL), vec!["save".to_string(), "open".to_string()])]);
+
+    // The first registered command wins, unless it is disabled.
+    assert!(registry.is_triggered_by("save", Key::S, ModifierKey::CTRL));
+    registry.set_enabled("save", false);
+    assert!(registry.is_triggered_by("open", Key::S, ModifierKey::CTRL));
+
+    registry.reset_binding("open");
+    assert_eq!(registry.binding("open"), Some(KeyBinding::new(Key::O, ModifierKey::CTRL)));
+    assert_eq!(KeyBinding::new(Key::S, ModifierKey::CTRL_SHIFT).to_string(), "Ctrl+Shift+S");
+}
+
+#[test]
+fn declared_commands_are_removed_with_their_widgets() {
+    let mut registry: CommandRegistry<()> = CommandRegistry::new();
+    let first = Uuid::new_v4();
+    let second = Uuid::new_v4();
+
+    let copy = Command::new("copy", "Copy").binding(Key::C, ModifierKey::CTRL);
+    registry.declare(first, &copy);
+    registry.declare(second, &copy);
+    registry.register(Command::new("redo", "Redo")
+        .binding(Key::Z, ModifierKey::CTRL_SHIFT)
+        .alternative_binding(Key::Y, ModifierKey::CTRL));
+
+    assert!(registry.is_triggered_by("redo", Key::Y, ModifierKey::CTRL));
+
+    registry.retain_declared(|widget| widget == second);
+    assert!(registry.is_triggered_by("copy", Key::C, ModifierKey::CTRL));
+    assert!(!registry.is_declared_by("copy", first));
+    assert!(registry.is_declared_by("copy", second));
+
+    registry.retain_declared(|_| false);
+    assert!(registry.command("copy").is_none());
+    assert!(registry.command("redo").is_some());
+}
@@ -1,13 +1,23 @@
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
+use std::io;
+use std::path::Path;
 
 use bitflags::_core::fmt::Formatter;
-use fxhash::{FxBuildHasher, FxHashMap};
+use fxhash::{FxBuildHasher, FxHashMap, FxHashSet};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
+use uuid::Uuid;
 
-use crate::{Color, from_bin, Scalar, to_bin};
+use crate::{Color, from_bin, Point, Scalar, to_bin};
+use crate::cursor::MouseCursor;
+use crate::environment::command_registry::CommandRegistry;
+use crate::environment::undo_manager::{UndoCommand, UndoManager};
 use crate::focus::Refocus;
+use crate::input::{Key, ModifierKey};
+use crate::layout::LayoutDirection;
+use crate::layout::viewport::Viewport;
 use crate::prelude::EnvironmentVariable;
 use crate::state::global_state::GlobalState;
 use crate::state::state::State;
@@ -53,6 +63,76 @@ pub struct Environment<GS> where GS: GlobalState {
     /// the focus change is not instant, but updates after each run event.
     pub(crate) focus_request: Option<Refocus>,
 
+    /// The latest values of the persistent local states, as a map from their key to the value
+    /// serialized using bin-code. Unlike the local state, this is kept across events, and can be
+    /// written to and read from a file, such that the values survive restarts of the application.
+    persistent_state: FxHashMap<String, Vec<u8>>,
+
+    /// Callbacks queued by observed states when their value changed. These are called by the
+    /// `Ui` after the state has been synchronized, with mutable access to the environment and
+    /// the global state. A refcell is used because the states only have immutable access to the
+    /// environment when they are updated.
+    queued_callbacks: RefCell<Vec<(StateKey, Box<dyn FnOnce(&mut Environment<GS>, &mut GS)>)>>,
+
+    /// The history of changes made to undoable states. This is kept across events, unlike the
+    /// local state.
+    undo_manager: UndoManager,
+
+    /// The commands of the application and their keyboard shortcuts.
+    commands: CommandRegistry<GS>,
+
+    /// The viewports of the scrolling widgets currently being sized, with the innermost last.
+    viewports: Vec<Viewport>,
+
+    /// True if all the widgets need to be sized again, for example after the window is resized.
+    layout_invalidated: bool,
+
+    /// The ids of the widgets that need to be sized again, because the values of their states
+    /// have changed, or because they contain such a widget. Other widgets reuse their size from
+    /// the previous layout when they are requested the same size.
+    invalidated_widgets: FxHashSet<Uuid>,
+
+    /// A `ChangeMarker` of the values of the states of each widget, from the last time the widget
+    /// updated its states. Used to tell when the values have changed.
+    state_values: FxHashMap<Uuid, u64>,
+
+    /// The size requested from each widget in the previous layout, the size it returned and the
+    /// dimension it was given.
+    sizes: FxHashMap<Uuid, (Dimensions, Dimensions, Dimensions)>,
+
+    /// The mouse cursor requested by the widgets. This is reset to the arrow each time the mouse
+    /// is moved, such that the widget under the mouse decides the cursor.
+    cursor: MouseCursor,
+
+    /// The ids of the topmost widget under the mouse and of its ancestors, found by hit testing
+    /// the widgets at the position of the latest mouse event.
+    hovered: Vec<Uuid>,
+
+    /// The hovered widgets before the latest mouse event. Used to tell which widgets the mouse
+    /// entered and exited.
+    previously_hovered: Vec<Uuid>,
+
+    /// The position of the mouse at the latest mouse event, if any.
+    mouse_position: Option<Point>,
+
+    /// Set after each layout, because widgets may have moved under the mouse without it moving.
+    hover_outdated: bool,
+
+    /// The ids of the target of the event being dispatched and of its ancestors, innermost first.
+    /// The target of a mouse event is the hovered widget, and of a keyboard event the focused
+    /// widget.
+    event_path: Vec<Uuid>,
+
+    /// True while the event is dispatched along the event path, running the capture and the
+    /// bubble phases, and false while it is broadcast to all the widgets.
+    dispatching_along_path: bool,
+
+    /// Set when a widget stops the propagation of the event being dispatched.
+    propagation_stopped: bool,
+
+    /// Set when a widget requested a tick event.
+    tick_requested: bool,
+
     /// The size of the drawing area in actual pixels.
     pixel_dimensions: Dimensions,
 
@@ -70,6 +150,10 @@ impl<GS: GlobalState> std::fmt::Debug for Environment<GS> {
 
 impl<GS: GlobalState> Environment<GS> {
     pub fn new(env_stack: Vec<EnvironmentVariable>, pixel_dimensions: Dimensions, scale_factor: f64) -> Self {
+        let mut commands = CommandRegistry::new();
+        commands.register(UndoCommand::Undo.command());
+        commands.register(UndoCommand::Redo.command());
+
         Environment {
             stack: env_stack,
             fonts: vec![],
@@ -78,6 +162,24 @@ impl<GS: GlobalState> Environment<GS> {
             overlay_map: HashMap::with_hasher(FxBuildHasher::default()),
             local_state: HashMap::with_hasher(FxBuildHasher::default()),
             focus_request: None,
+            persistent_state: HashMap::with_hasher(FxBuildHasher::default()),
+            queued_callbacks: RefCell::new(vec![]),
+            undo_manager: UndoManager::new(),
+            commands,
+            viewports: vec![],
+            layout_invalidated: true,
+            invalidated_widgets: HashSet::with_hasher(FxBuildHasher::default()),
+            state_values: HashMap::with_hasher(FxBuildHasher::default()),
+            sizes: HashMap::with_hasher(FxBuildHasher::default()),
+            cursor: MouseCursor::Arrow,
+            hovered: vec![],
+            previously_hovered: vec![],
+            mouse_position: None,
+            hover_outdated: false,
+            event_path: vec![],
+            dispatching_along_path: false,
+            propagation_stopped: false,
+            tick_requested: false,
             pixel_dimensions,
             scale_factor,
         }
@@ -85,14 +187,17 @@ impl<GS: GlobalState> Environment<GS> {
 
     pub fn set_pixel_width(&mut self, new_pixel_width: f64) {
         self.pixel_dimensions[0] = new_pixel_width;
+        self.invalidate_layout();
     }
 
     pub fn set_pixel_height(&mut self, new_pixel_height: f64) {
         self.pixel_dimensions[1] = new_pixel_height;
+        self.invalidate_layout();
     }
 
     pub fn set_scale_factor(&mut self, new_scale_factor: f64) {
         self.scale_factor = new_scale_factor;
+        self.invalidate_layout();
     }
 
     pub fn get_corrected_width(&self) -> f64 {
@@ -143,6 +248,261 @@ impl<GS: GlobalState> Environment<GS> {
         self.overlay_map.insert(id.to_string(), overlay);
     }
 
+    /// Queue a callback to be called after the state has been synchronized. Only the first
+    /// callback queued for a key is kept, such that clones of the same observed state only
+    /// notify once.
+    pub fn queue_callback(&self, key: &StateKey, callback: Box<dyn FnOnce(&mut Environment<GS>, &mut GS)>) {
+        let mut queued_callbacks = self.queued_callbacks.borrow_mut();
+
+        if queued_callbacks.iter().all(|(queued_key, _)| queued_key != key) {
+            queued_callbacks.push((key.clone(), callback));
+        }
+    }
+
+    pub(crate) fn take_queued_callbacks(&mut self) -> Vec<Box<dyn FnOnce(&mut Environment<GS>, &mut GS)>> {
+        self.queued_callbacks.get_mut().drain(..).map(|(_, callback)| callback).collect()
+    }
+
+    pub fn undo_manager(&self) -> &UndoManager {
+        &self.undo_manager
+    }
+
+    pub fn undo_manager_mut(&mut self) -> &mut UndoManager {
+        &mut self.undo_manager
+    }
+
+    pub fn commands(&self) -> &CommandRegistry<GS> {
+        &self.commands
+    }
+
+    pub fn commands_mut(&mut self) -> &mut CommandRegistry<GS> {
+        &mut self.commands
+    }
+
+    /// Execute the handler of the enabled command bound to the keys. Returns false if no command
+    /// with a handler is bound to the keys, or if the command cannot execute right now.
+    pub(crate) fn execute_shortcut(&mut self, key: Key, modifiers: ModifierKey, global_state: &mut GS) -> bool {
+        let (handler, condition) = match self.commands.command_for(key, modifiers) {
+            Some(command) => match command.handler() {
+                Some(handler) => (handler.clone(), command.condition().cloned()),
+                None => return false,
+            },
+            None => return false,
+        };
+
+        if let Some(condition) = condition {
+            if !condition(self, global_state) {
+                return false;
+            }
+        }
+
+        handler(self, global_state);
+        true
+    }
+
+    /// Make all the widgets be sized again before they are drawn next. Call this when the global
+    /// state is changed outside of the widgets, or when something other than the states of the
+    /// widgets affects their size.
+    pub fn invalidate_layout(&mut self) {
+        self.layout_invalidated = true;
+    }
+
+    /// Make the widget, and the widgets containing it, be sized again before they are drawn next.
+    /// Widgets should call this when a field affecting their size is changed, as only changes to
+    /// their states are noticed automatically.
+    pub fn invalidate_layout_of(&mut self, id: Uuid) {
+        self.invalidated_widgets.insert(id);
+    }
+
+    /// Make the widget and all the widgets within it be sized again before they are drawn next.
+    pub fn invalidate_layout_of_tree(&mut self, widget: &dyn Widget<GS>) {
+        self.invalidated_widgets.insert(widget.get_id());
+
+        for child in widget.get_children() {
+            self.invalidate_layout_of_tree(child);
+        }
+    }
+
+    pub fn is_layout_invalidated(&self) -> bool {
+        self.layout_invalidated || !self.invalidated_widgets.is_empty()
+    }
+
+    /// Invalidate the layout of the widget if the `ChangeMarker` of the values of its states
+    /// differs from the marker the last time this was called. This is called by the widgets each
+    /// time they update their states.
+    pub fn track_state_values(&mut self, id: Uuid, marker: u64) {
+        match self.state_values.get_mut(&id) {
+            Some(previous) if *previous != marker => {
+                *previous = marker;
+                self.invalidated_widgets.insert(id);
+            }
+            Some(_) => (),
+            None => {
+                self.state_values.insert(id, marker);
+            }
+        }
+    }
+
+    /// The size the widget returned in the previous layout, if it can be reused because the
+    /// widget is not invalidated and is requested the same size.
+    pub(crate) fn previous_size(&self, id: Uuid, requested_size: Dimensions, dimension: Dimensions) -> Option<Dimensions> {
+        if self.layout_invalidated || self.invalidated_widgets.contains(&id) {
+            return None;
+        }
+
+        match self.sizes.get(&id) {
+            Some((requested, size, previous_dimension)) if *requested == requested_size && *previous_dimension == dimension => Some(*size),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn set_previous_size(&mut self, id: Uuid, requested_size: Dimensions, size: Dimensions, dimension: Dimensions) {
+        self.invalidated_widgets.remove(&id);
+        self.sizes.insert(id, (requested_size, size, dimension));
+    }
+
+    /// Prepare laying out the widgets from the root. The widgets containing invalidated widgets
+    /// are invalidated as well, and the sizes and state values of widgets no longer in the tree
+    /// are forgotten, along with the commands declared by them.
+    pub(crate) fn begin_layout(&mut self, root: &dyn Widget<GS>) {
+        let mut widgets = HashSet::with_hasher(FxBuildHasher::default());
+        self.invalidate_ancestors(root, &mut widgets);
+
+        self.sizes.retain(|id, _| widgets.contains(id));
+        self.state_values.retain(|id, _| widgets.contains(id));
+        self.commands.retain_declared(|id| widgets.contains(&id));
+    }
+
+    /// Invalidate the widget if a widget within it is invalidated. Returns true if the widget is
+    /// invalidated.
+    fn invalidate_ancestors(&mut self, widget: &dyn Widget<GS>, widgets: &mut FxHashSet<Uuid>) -> bool {
+        let id = widget.get_id();
+        let mut invalidated = self.invalidated_widgets.contains(&id);
+
+        for child in widget.get_children() {
+            invalidated |= self.invalidate_ancestors(child, widgets);
+        }
+
+        if invalidated {
+            self.invalidated_widgets.insert(id);
+        }
+
+        widgets.insert(id);
+        invalidated
+    }
+
+    /// Mark the layout as up to date. The sizes of invalidated widgets that were not sized, for
+    /// example because they are hidden, are forgotten such that they are sized when shown.
+    pub(crate) fn validate_layout(&mut self) {
+        for id in self.invalidated_widgets.drain() {
+            self.sizes.remove(&id);
+        }
+
+        self.layout_invalidated = false;
+        self.hover_outdated = true;
+    }
+
+    pub fn push_viewport(&mut self, viewport: Viewport) {
+        self.viewports.push(viewport);
+    }
+
+    pub fn pop_viewport(&mut self) {
+        self.viewports.pop();
+    }
+
+    /// The viewport of the innermost scrolling widget currently being sized, if any.
+    pub fn viewport(&self) -> Option<&Viewport> {
+        self.viewports.last()
+    }
+
+    pub fn set_cursor(&mut self, cursor: MouseCursor) {
+        self.cursor = cursor;
+    }
+
+    pub fn cursor(&self) -> MouseCursor {
+        self.cursor
+    }
+
+    pub(crate) fn set_hovered(&mut self, hovered: Vec<Uuid>) {
+        self.previously_hovered = std::mem::replace(&mut self.hovered, hovered);
+    }
+
+    pub(crate) fn set_mouse_position(&mut self, position: Point) {
+        self.mouse_position = Some(position);
+    }
+
+    /// The position of the mouse, if the hovered widgets need to be found again because the
+    /// layout changed since they were last found.
+    pub(crate) fn take_outdated_hover_position(&mut self) -> Option<Point> {
+        if std::mem::replace(&mut self.hover_outdated, false) {
+            self.mouse_position
+        } else {
+            None
+        }
+    }
+
+    /// Check if the widget is the topmost widget under the mouse, or one of its ancestors.
+    pub fn is_hovered(&self, id: Uuid) -> bool {
+        self.hovered.contains(&id)
+    }
+
+    pub(crate) fn was_hovered(&self, id: Uuid) -> bool {
+        self.previously_hovered.contains(&id)
+    }
+
+    pub(crate) fn begin_dispatch_along_path(&mut self, path: Vec<Uuid>) {
+        self.event_path = path;
+        self.dispatching_along_path = true;
+        self.propagation_stopped = false;
+    }
+
+    /// Returns true if the propagation of the event was stopped.
+    pub(crate) fn end_dispatch_along_path(&mut self) -> bool {
+        self.dispatching_along_path = false;
+        self.propagation_stopped
+    }
+
+    pub fn is_dispatching_along_path(&self) -> bool {
+        self.dispatching_along_path
+    }
+
+    /// Check if the widget is the target of the event being dispatched, or one of its ancestors.
+    pub fn is_on_event_path(&self, id: Uuid) -> bool {
+        self.event_path.contains(&id)
+    }
+
+    /// Check if the event being dispatched along the event path needs to visit the widget, either
+    /// to run its phases or to tell it the mouse entered or exited it.
+    pub(crate) fn is_on_dispatch_route(&self, id: Uuid) -> bool {
+        self.is_on_event_path(id) || self.is_hovered(id) || self.was_hovered(id)
+    }
+
+    /// Stop the event from reaching the remaining phases, and from being broadcast to the
+    /// widgets afterwards. Touch events are only broadcast, and still reach all the widgets when
+    /// stopped, such that the widgets tracking the fingers see every touch. Stopping a touch event
+    /// only consumes the touch, such that no mouse events are synthesized from it.
+    pub fn stop_propagation(&mut self) {
+        self.propagation_stopped = true;
+    }
+
+    pub(crate) fn reset_propagation(&mut self) {
+        self.propagation_stopped = false;
+    }
+
+    pub fn is_propagation_stopped(&self) -> bool {
+        self.propagation_stopped
+    }
+
+    /// Request a `WindowEvent::Tick` to be sent to the widgets the next time the events are
+    /// delegated. Request it again when handling the tick to keep receiving ticks.
+    pub fn request_tick(&mut self) {
+        self.tick_requested = true;
+    }
+
+    pub(crate) fn take_tick_request(&mut self) -> bool {
+        std::mem::replace(&mut self.tick_requested, false)
+    }
+
     pub fn clear(&mut self) {
         self.clear_local_state();
         self.overlay_map.clear();
@@ -157,7 +517,13 @@ impl<GS: GlobalState> Environment<GS> {
         if let Some(key) = local_state.get_key() {
             let local_value: &Vec<u8> = match self.local_state.get(key) {
                 Some(n) => n,
-                None => return,
+                None => match key {
+                    StateKey::Persistent(key) => match self.persistent_state.get(key) {
+                        Some(n) => n,
+                        None => return,
+                    },
+                    _ => return,
+                },
             };
             *local_state.get_latest_value_mut() = from_bin::<T>(&local_value).unwrap();
         }
@@ -167,12 +533,41 @@ impl<GS: GlobalState> Environment<GS> {
         local_state.insert_dependent_states(self);
         if let Some(key) = local_state.get_key() {
             let value = local_state.get_latest_value();
-            self.local_state.insert(key.clone(), to_bin(value).unwrap());
+            self.insert_local_state_from_key_value(key, value);
         }
     }
 
     pub fn insert_local_state_from_key_value<T: Serialize + Clone + Debug>(&mut self, key: &StateKey, value: &T) {
-        self.local_state.insert(key.clone(), to_bin(value).unwrap());
+        let value = to_bin(value).unwrap();
+
+        if let StateKey::Persistent(key) = key {
+            self.persistent_state.insert(key.clone(), value.clone());
+        }
+
+        self.local_state.insert(key.clone(), value);
+    }
+
+    /// Read the values of the persistent local states from the file at the path. The values
+    /// are used by the persistent states with matching keys, instead of their initial values.
+    pub fn read_persistent_state<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        let bytes = std::fs::read(path)?;
+
+        let persistent_state: HashMap<String, Vec<u8>> = from_bin(&bytes)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        self.persistent_state.extend(persistent_state);
+
+        Ok(())
+    }
+
+    /// Write the latest values of the persistent local states to the file at the path.
+    pub fn write_persistent_state<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let persistent_state: HashMap<&String, &Vec<u8>> = self.persistent_state.iter().collect();
+
+        let bytes = to_bin(&persistent_state)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        std::fs::write(path, bytes)
     }
 
     pub fn insert_font_from_file<P>(&mut self, path: P) -> FontId
@@ -305,4 +700,191 @@ impl<GS: GlobalState> Environment<GS> {
 
         None
     }
+
+    /// The innermost layout direction set on the stack, left to right if none is set.
+    pub fn get_layout_direction(&self) -> LayoutDirection {
+        for item in self.stack.iter().rev() {
+            if let EnvironmentVariable::LayoutDirection { value } = item {
+                return *value
+            }
+        }
+
+        LayoutDirection::default()
+    }
+}
+
+#[test]
+fn persistent_state_is_restored_from_file() {
+    use crate::state::state::CommonState;
+
+    let path = std::env::temp_dir().join(format!("carbide_persistent_state_{}", uuid::Uuid::new_v4()));
+
+    let mut env = Environment::<()>::new(vec![], [100.0, 100.0], 1.0);
+    let state = CommonState::<u32, ()>::new_persistent("selected_tab", &2);
+    env.insert_local_state(&state);
+    env.clear();
+    env.write_persistent_state(&path).unwrap();
+
+    let mut restored_env = Environment::<()>::new(vec![], [100.0, 100.0], 1.0);
+    restored_env.read_persistent_state(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let mut restored = CommonState::<u32, ()>::new_persistent("selected_tab", &0);
+    restored_env.update_local_state(&mut restored);
+
+    assert_eq!(*restored.get_latest_value(), 2);
+}
+
+#[test]
+fn changed_state_values_invalidate_the_widget() {
+    let mut env = Environment::<()>::new(vec![], [100.0, 100.0], 1.0);
+    let id = Uuid::new_v4();
+
+    env.track_state_values(id, 1);
+    env.set_previous_size(id, [100.0, 100.0], [50.0, 20.0], [50.0, 20.0]);
+    env.validate_layout();
+
+    env.track_state_values(id, 1);
+    assert!(!env.is_layout_invalidated());
+    assert_eq!(env.previous_size(id, [100.0, 100.0], [50.0, 20.0]), Some([50.0, 20.0]));
+    assert_eq!(env.previous_size(id, [80.0, 100.0], [50.0, 20.0]), None);
+
+    env.track_state_values(id, 2);
+    assert!(env.is_layout_invalidated());
+    assert_eq!(env.previous_size(id, [100.0, 100.0], [50.0, 20.0]), None);
+}
+
+#[test]
+fn state_changes_only_size_the_widgets_owning_the_states_again() {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use crate::environment::command_registry::Command;
+    use crate::headless::HeadlessUi;
+    use crate::prelude::*;
+    use crate::state::global_state::GState;
+    use crate::widget::{ChildRender, WidgetExt, ZStack};
+
+    type Widths = (f64, f64);
+
+    /// Counts the times it is sized.
+    #[derive(Debug, Clone, Widget)]
+    #[global_state(Widths)]
+    struct Counter {
+        id: Uuid,
+        position: Point,
+        dimension: Dimensions,
+        count: Rc<Cell<u32>>,
+    }
+
+    impl Layout<Widths> for Counter {
+        fn flexibility(&self) -> u32 {
+            0
+        }
+
+        fn calculate_size(&mut self, _: Dimensions, _: &mut Environment<Widths>) -> Dimensions {
+            self.count.set(self.count.get() + 1);
+            self.dimension = [10.0, 10.0];
+            self.dimension
+        }
+
+        fn position_children(&mut self, _: &mut Environment<Widths>) {}
+    }
+
+    impl CommonWidget<Widths> for Counter {
+        fn get_id(&self) -> Uuid {
+            self.id
+        }
+
+        fn set_id(&mut self, id: Uuid) {
+            self.id = id;
+        }
+
+        fn get_flag(&self) -> Flags {
+            Flags::EMPTY
+        }
+
+        fn get_children(&self) -> WidgetIter<Widths> {
+            WidgetIter::Empty
+        }
+
+        fn get_children_mut(&mut self) -> WidgetIterMut<Widths> {
+            WidgetIterMut::Empty
+        }
+
+        fn get_proxied_children(&mut self) -> WidgetIterMut<Widths> {
+            WidgetIterMut::Empty
+        }
+
+        fn get_proxied_children_rev(&mut self) -> WidgetIterMut<Widths> {
+            WidgetIterMut::Empty
+        }
+
+        fn get_position(&self) -> Point {
+            self.position
+        }
+
+        fn set_position(&mut self, position: Dimensions) {
+            self.position = position;
+        }
+
+        fn get_dimension(&self) -> Dimensions {
+            self.dimension
+        }
+
+        fn set_dimension(&mut self, dimensions: Dimensions) {
+            self.dimension = dimensions
+        }
+    }
+
+    impl ChildRender for Counter {}
+
+    let count = Rc::new(Cell::new(0));
+    let mut ui = HeadlessUi::new([200.0, 100.0], 1.0, (0.0, 0.0));
+
+    let first = GState::new(|widths: &Widths| &widths.0, |widths: &mut Widths| &mut widths.0);
+    let second = GState::new(|widths: &Widths| &widths.1, |widths: &mut Widths| &mut widths.1);
+
+    let root: Box<dyn Widget<Widths>> = ZStack::initialize(vec![
+        Rectangle::initialize(vec![]).frame(first, 10.0),
+        Rectangle::initialize(vec![]).frame(second, 10.0),
+        Box::new(Counter { id: Uuid::new_v4(), position: [0.0, 0.0], dimension: [0.0, 0.0], count: count.clone() }),
+    ]);
+    *ui.widgets_mut() = root;
+    ui.environment_mut().commands_mut().register(Command::new("widen", "Widen")
+        .binding(Key::W, ModifierKey::NO_MODIFIER)
+        .on_execute(|_: &mut Environment<Widths>, widths: &mut Widths| widths.0 = 50.0));
+
+    ui.draw();
+    assert_eq!(count.get(), 1);
+
+    // An event changing no states does not make anything be sized again.
+    ui.move_mouse_to([10.0, 10.0]);
+    ui.update();
+    assert!(!ui.environment().is_layout_invalidated());
+    ui.draw();
+    assert_eq!(count.get(), 1);
+
+    // Only the frame using the changed state is invalidated.
+    ui.press_key(Key::W, ModifierKey::NO_MODIFIER);
+    let ids: Vec<Uuid> = ui.widgets().get_children().map(|child| child.get_id()).collect();
+    assert_eq!(ui.environment().invalidated_widgets.iter().cloned().collect::<Vec<Uuid>>(), vec![ids[0]]);
+
+    ui.draw();
+    assert_eq!(count.get(), 1);
+    assert_eq!(ui.widgets().get_children().next().unwrap().get_width(), 50.0);
+}
+
+#[test]
+fn innermost_layout_direction_is_used() {
+    let mut env = Environment::<()>::new(vec![], [100.0, 100.0], 1.0);
+    assert_eq!(env.get_layout_direction(), LayoutDirection::LeftToRight);
+
+    env.push(EnvironmentVariable::LayoutDirection { value: LayoutDirection::RightToLeft });
+    env.push(EnvironmentVariable::F64 { key: "spacing".to_string(), value: 10.0 });
+    assert_eq!(env.get_layout_direction(), LayoutDirection::RightToLeft);
+
+    env.pop();
+    env.push(EnvironmentVariable::LayoutDirection { value: LayoutDirection::LeftToRight });
+    assert_eq!(env.get_layout_direction(), LayoutDirection::LeftToRight);
 }
@@ -1,4 +1,5 @@
 use crate::Color;
+use crate::layout::LayoutDirection;
 use crate::prelude::EnvironmentColor;
 use crate::prelude::EnvironmentFontSize;
 
@@ -10,4 +11,5 @@ pub enum EnvironmentVariable {
     Color { key: EnvironmentColor, value: Color },
     FontSize { key: EnvironmentFontSize, value: u32 },
     I32 { key: String, value: i32 },
+    LayoutDirection { value: LayoutDirection },
 }
\ No newline at end of file
@@ -1,6 +1,8 @@
+pub mod command_registry;
 pub mod environment;
 pub mod environment_color;
 pub mod environment_font_size;
 pub mod environment_variable;
 pub mod environment_color_state;
-pub mod environment_font_size_state;
\ No newline at end of file
+pub mod environment_font_size_state;
+pub mod undo_manager;
\ No newline at end of file
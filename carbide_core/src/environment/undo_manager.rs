@@ -0,0 +1,279 @@
+use fxhash::FxHashMap;
+
+use crate::environment::command_registry::{Command, PRIMARY_MODIFIER};
+use crate::input::{Key, ModifierKey};
+use crate::prelude::Environment;
+use crate::state::global_state::GlobalState;
+use crate::state::state_key::StateKey;
+
+/// A single change to an undoable state. The values are stored serialized, such that states of
+/// different types can be kept in the same history.
+#[derive(Clone, Debug)]
+struct UndoChange {
+    key: StateKey,
+    before: Vec<u8>,
+    after: Vec<u8>,
+}
+
+/// A group of changes that are undone and redone together.
+#[derive(Clone, Debug)]
+struct UndoGroup {
+    name: Option<String>,
+    changes: Vec<UndoChange>,
+}
+
+impl UndoGroup {
+    fn new(name: Option<String>) -> Self {
+        UndoGroup {
+            name,
+            changes: vec![],
+        }
+    }
+
+    fn push(&mut self, change: UndoChange) {
+        // Only keep the first value before and the last value after, if a state is changed more
+        // than once within the same group.
+        if let Some(existing) = self.changes.iter_mut().find(|existing| existing.key == change.key) {
+            existing.after = change.after;
+        } else {
+            self.changes.push(change);
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum UndoCommand {
+    Undo,
+    Redo,
+}
+
+impl UndoCommand {
+    /// The command executing the undo or redo, bound to the keys of the current platform. It is
+    /// registered in the command registry of every environment.
+    pub fn command<GS: GlobalState>(self) -> Command<GS> {
+        let command = match self {
+            UndoCommand::Undo => Command::new("undo", "Undo")
+                .binding(Key::Z, PRIMARY_MODIFIER),
+            UndoCommand::Redo => Command::new("redo", "Redo")
+                .binding(Key::Z, PRIMARY_MODIFIER | ModifierKey::SHIFT),
+        };
+
+        let command = if self == UndoCommand::Redo && cfg!(not(target_os = "macos")) {
+            command.alternative_binding(Key::Y, ModifierKey::CTRL)
+        } else {
+            command
+        };
+
+        command
+            .can_execute(move |env: &Environment<GS>, _: &GS| match self {
+                UndoCommand::Undo => env.undo_manager().can_undo(),
+                UndoCommand::Redo => env.undo_manager().can_redo(),
+            })
+            .on_execute(move |env: &mut Environment<GS>, _: &mut GS| {
+                env.undo_manager_mut().execute(self);
+            })
+    }
+}
+
+/// The undo manager keeps the history of changes made to undoable states. It is scoped to the
+/// environment, and is shared by all `UndoableState`s within a window.
+///
+/// Changes registered while a transaction is open are grouped, and undone in a single step.
+/// The `Ui` opens a transaction around each event, such that all changes made as a result of a
+/// single event are grouped. Transactions can be nested to group changes across several events.
+#[derive(Clone, Debug)]
+pub struct UndoManager {
+    undo_stack: Vec<UndoGroup>,
+    redo_stack: Vec<UndoGroup>,
+    transaction: Option<UndoGroup>,
+    transaction_depth: usize,
+    /// The values to restore, staged by an undo or redo. The undoable states apply these the
+    /// next time they are updated.
+    pending: FxHashMap<StateKey, Vec<u8>>,
+    /// The maximum number of undo steps kept in the history.
+    limit: usize,
+}
+
+impl UndoManager {
+    pub fn new() -> Self {
+        UndoManager {
+            undo_stack: vec![],
+            redo_stack: vec![],
+            transaction: None,
+            transaction_depth: 0,
+            pending: FxHashMap::default(),
+            limit: 100,
+        }
+    }
+
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+        self.truncate();
+    }
+
+    /// Start grouping changes. Every call needs to be matched by a call to `end_transaction`.
+    pub fn begin_transaction(&mut self, name: Option<&str>) {
+        if self.transaction_depth == 0 {
+            self.transaction = Some(UndoGroup::new(name.map(|name| name.to_string())));
+        }
+
+        self.transaction_depth += 1;
+    }
+
+    pub fn end_transaction(&mut self) {
+        if self.transaction_depth == 0 {
+            return;
+        }
+
+        self.transaction_depth -= 1;
+
+        if self.transaction_depth == 0 {
+            if let Some(group) = self.transaction.take() {
+                self.push_group(group);
+            }
+        }
+    }
+
+    /// Register a change of the state with the given key. This clears the redo history.
+    pub fn register_change(&mut self, key: StateKey, before: Vec<u8>, after: Vec<u8>) {
+        let change = UndoChange { key, before, after };
+
+        match &mut self.transaction {
+            Some(group) => group.push(change),
+            None => {
+                let mut group = UndoGroup::new(None);
+                group.push(change);
+                self.push_group(group);
+            }
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    /// The name of the transaction that will be undone next, if it was given one.
+    pub fn undo_name(&self) -> Option<&str> {
+        self.undo_stack.last().and_then(|group| group.name.as_deref())
+    }
+
+    /// The name of the transaction that will be redone next, if it was given one.
+    pub fn redo_name(&self) -> Option<&str> {
+        self.redo_stack.last().and_then(|group| group.name.as_deref())
+    }
+
+    /// Stage the values from before the latest group of changes. Returns false if there is
+    /// nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        match self.undo_stack.pop() {
+            Some(group) => {
+                for change in group.changes.iter().rev() {
+                    self.pending.insert(change.key.clone(), change.before.clone());
+                }
+                self.redo_stack.push(group);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Stage the values from after the latest undone group of changes. Returns false if there is
+    /// nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        match self.redo_stack.pop() {
+            Some(group) => {
+                for change in &group.changes {
+                    self.pending.insert(change.key.clone(), change.after.clone());
+                }
+                self.undo_stack.push(group);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn execute(&mut self, command: UndoCommand) -> bool {
+        match command {
+            UndoCommand::Undo => self.undo(),
+            UndoCommand::Redo => self.redo(),
+        }
+    }
+
+    /// Remove the whole history.
+    pub fn clear_history(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+
+    pub(crate) fn pending_value(&self, key: &StateKey) -> Option<&Vec<u8>> {
+        self.pending.get(key)
+    }
+
+    pub(crate) fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    pub(crate) fn clear_pending(&mut self) {
+        self.pending.clear();
+    }
+
+    fn push_group(&mut self, group: UndoGroup) {
+        if group.changes.is_empty() {
+            return;
+        }
+
+        self.undo_stack.push(group);
+        self.redo_stack.clear();
+        self.truncate();
+    }
+
+    fn truncate(&mut self) {
+        if self.undo_stack.len() > self.limit {
+            let overflow = self.undo_stack.len() - self.limit;
+            self.undo_stack.drain(0..overflow);
+        }
+    }
+}
+
+#[test]
+fn changes_within_a_transaction_are_undone_together() {
+    let mut manager = UndoManager::new();
+    let fst = StateKey::String("fst".to_string());
+    let snd = StateKey::String("snd".to_string());
+
+    manager.begin_transaction(Some("Edit"));
+    manager.register_change(fst.clone(), vec![0], vec![1]);
+    manager.register_change(fst.clone(), vec![1], vec![2]);
+    manager.register_change(snd.clone(), vec![0], vec![3]);
+    manager.end_transaction();
+
+    assert_eq!(manager.undo_name(), Some("Edit"));
+    assert!(manager.undo());
+    assert_eq!(manager.pending_value(&fst), Some(&vec![0]));
+    assert_eq!(manager.pending_value(&snd), Some(&vec![0]));
+    assert!(!manager.can_undo());
+
+    manager.clear_pending();
+
+    assert!(manager.redo());
+    assert_eq!(manager.pending_value(&fst), Some(&vec![2]));
+    assert_eq!(manager.pending_value(&snd), Some(&vec![3]));
+}
+
+#[test]
+fn undo_keys_are_not_consumed_with_nothing_to_undo() {
+    let mut env = Environment::<()>::new(vec![], [100.0, 100.0], 1.0);
+    let key = StateKey::String("key".to_string());
+
+    assert!(!env.execute_shortcut(Key::Z, PRIMARY_MODIFIER, &mut ()));
+    assert!(!env.execute_shortcut(Key::Z, PRIMARY_MODIFIER | ModifierKey::SHIFT, &mut ()));
+
+    env.undo_manager_mut().register_change(key, vec![0], vec![1]);
+
+    assert!(env.execute_shortcut(Key::Z, PRIMARY_MODIFIER, &mut ()));
+    assert!(env.execute_shortcut(Key::Z, PRIMARY_MODIFIER | ModifierKey::SHIFT, &mut ()));
+}
@@ -1,16 +1,35 @@
+use uuid::Uuid;
+
 use crate::event_handler::{KeyboardEvent, MouseEvent, WidgetEvent};
+use crate::flags::Flags;
 use crate::focus::Focusable;
+use crate::Point;
 use crate::prelude::Environment;
 use crate::state::global_state::GlobalState;
 use crate::state::state_sync::StateSync;
 use crate::widget::common_widget::CommonWidget;
+use crate::widget::primitive::Widget;
+
+/// The decision of a widget handling an event in the capture or the bubble phase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventPropagation {
+    /// Let the event continue to the next widget.
+    Continue,
+    /// Stop the event. The remaining widgets on the event path will not get the event, and it
+    /// is not broadcast to the widgets afterwards.
+    Stop,
+}
 
+/// Events are dispatched in two passes. The mouse and keyboard events are first dispatched
+/// along the event path: the target of the event and its ancestors. Each widget on the path gets
+/// the event in the capture phase, from the root to the target, followed by the bubble phase,
+/// from the target to the root. Unless a widget stopped the propagation, the event is then
+/// broadcast to all the widgets through the `handle_*_event` functions.
 pub trait Event<GS>: CommonWidget<GS> + StateSync<GS> + Focusable<GS> where GS: GlobalState {
-    /// A function that will be called when a mouse event occurs.
-    /// It will only get called on the events where the cursor is inside.
-    /// Return true if the event is consumed, and will thus not be delegated to other
-    /// widgets.
-    fn handle_mouse_event(&mut self, event: &MouseEvent, consumed: &bool, env: &mut Environment<GS>, global_state: &mut GS);
+    /// A function that will be called when a mouse event is broadcast to the widgets.
+    /// To keep the event from other widgets, stop its propagation in `capture_mouse_event` or
+    /// `bubble_mouse_event` instead.
+    fn handle_mouse_event(&mut self, event: &MouseEvent, env: &mut Environment<GS>, global_state: &mut GS);
 
     /// A function that will get called when a keyboard event occurs.
     /// This event will be given to all widgets, no matter if they are in focus or not.
@@ -20,26 +39,28 @@ pub trait Event<GS>: CommonWidget<GS> + StateSync<GS> + Focusable<GS> where GS:
     /// This will get called if there are event that are not covered by the other functions.
     /// This will get delegated to all widgets.
     /// It will never get called with mouse or keyboard events.
-    /// TODO: Separate touch events.
+    /// Touch events are handled here. Stop the propagation of a touch event to keep mouse events
+    /// from being synthesized from it. The touch event is still given to the other widgets.
     fn handle_other_event(&mut self, event: &WidgetEvent, env: &mut Environment<GS>, global_state: &mut GS);
 
-    fn process_mouse_event(&mut self, event: &MouseEvent, consumed: &bool, env: &mut Environment<GS>, global_state: &mut GS);
+    fn process_mouse_event(&mut self, event: &MouseEvent, env: &mut Environment<GS>, global_state: &mut GS);
 
-    fn process_mouse_event_default(&mut self, event: &MouseEvent, consumed: &bool, env: &mut Environment<GS>, global_state: &mut GS) {
+    fn process_mouse_event_default(&mut self, event: &MouseEvent, env: &mut Environment<GS>, global_state: &mut GS) {
         self.update_all_widget_state(env, global_state);
 
-        if !*consumed {
-            self.handle_mouse_event(event, consumed, env, global_state);
-        }
+        self.handle_mouse_event_before_children(event, env, global_state);
 
         self.insert_local_state(env);
 
+        let along_path = env.is_dispatching_along_path();
+
         for child in self.get_proxied_children() {
-            child.process_mouse_event(event, &consumed, env, global_state);
-            if *consumed { return () }
-        }
+            if along_path && !is_on_dispatch_route(child, env) { continue }
 
+            child.process_mouse_event(event, env, global_state);
+        }
 
+        self.handle_mouse_event_after_children(event, env, global_state);
 
         self.update_local_widget_state(env)
     }
@@ -49,14 +70,20 @@ pub trait Event<GS>: CommonWidget<GS> + StateSync<GS> + Focusable<GS> where GS:
     fn process_keyboard_event_default(&mut self, event: &KeyboardEvent, env: &mut Environment<GS>, global_state: &mut GS) {
         self.update_all_widget_state(env, global_state);
 
-        self.handle_keyboard_event(event, env, global_state);
+        self.handle_keyboard_event_before_children(event, env, global_state);
 
         self.insert_local_state(env);
 
+        let along_path = env.is_dispatching_along_path();
+
         for child in self.get_proxied_children() {
+            if along_path && !is_on_dispatch_route(child, env) { continue }
+
             child.process_keyboard_event(event, env, global_state);
         }
 
+        self.handle_keyboard_event_after_children(event, env, global_state);
+
         self.update_local_widget_state(env)
     }
 
@@ -75,4 +102,254 @@ pub trait Event<GS>: CommonWidget<GS> + StateSync<GS> + Focusable<GS> where GS:
 
         self.update_local_widget_state(env)
     }
-}
\ No newline at end of file
+
+    /// Called with the mouse events targeting this widget or one of its descendants, before the
+    /// descendants get the event.
+    fn capture_mouse_event(&mut self, _event: &MouseEvent, _env: &mut Environment<GS>, _global_state: &mut GS) -> EventPropagation {
+        EventPropagation::Continue
+    }
+
+    /// Called with the mouse events targeting this widget or one of its descendants, after the
+    /// descendants got the event.
+    fn bubble_mouse_event(&mut self, _event: &MouseEvent, _env: &mut Environment<GS>, _global_state: &mut GS) -> EventPropagation {
+        EventPropagation::Continue
+    }
+
+    /// Called with the keyboard events while this widget or one of its descendants is focused,
+    /// before the descendants get the event. This lets a container intercept keys before the
+    /// focused widget.
+    fn capture_keyboard_event(&mut self, _event: &KeyboardEvent, _env: &mut Environment<GS>, _global_state: &mut GS) -> EventPropagation {
+        EventPropagation::Continue
+    }
+
+    /// Called with the keyboard events while this widget or one of its descendants is focused,
+    /// after the descendants got the event.
+    fn bubble_keyboard_event(&mut self, _event: &KeyboardEvent, _env: &mut Environment<GS>, _global_state: &mut GS) -> EventPropagation {
+        EventPropagation::Continue
+    }
+
+    /// Handle the mouse event in this widget before its children. Along the event path this
+    /// tells the widget about the mouse entering or exiting it and runs the capture phase, and
+    /// otherwise the event is handled by `handle_mouse_event`.
+    fn handle_mouse_event_before_children(&mut self, event: &MouseEvent, env: &mut Environment<GS>, global_state: &mut GS) {
+        if env.is_dispatching_along_path() {
+            self.handle_hover_change(event, env, global_state);
+
+            if env.is_on_event_path(self.get_id()) && !env.is_propagation_stopped() {
+                if self.capture_mouse_event(event, env, global_state) == EventPropagation::Stop {
+                    env.stop_propagation();
+                }
+            }
+        } else {
+            self.handle_mouse_event(event, env, global_state);
+        }
+    }
+
+    /// Run the bubble phase for this widget, if the event is dispatched along the event path.
+    fn handle_mouse_event_after_children(&mut self, event: &MouseEvent, env: &mut Environment<GS>, global_state: &mut GS) {
+        if env.is_dispatching_along_path() && env.is_on_event_path(self.get_id()) && !env.is_propagation_stopped() {
+            if self.bubble_mouse_event(event, env, global_state) == EventPropagation::Stop {
+                env.stop_propagation();
+            }
+        }
+    }
+
+    fn handle_keyboard_event_before_children(&mut self, event: &KeyboardEvent, env: &mut Environment<GS>, global_state: &mut GS) {
+        if env.is_dispatching_along_path() {
+            if env.is_on_event_path(self.get_id()) && !env.is_propagation_stopped() {
+                if self.capture_keyboard_event(event, env, global_state) == EventPropagation::Stop {
+                    env.stop_propagation();
+                }
+            }
+        } else {
+            self.handle_keyboard_event(event, env, global_state);
+        }
+    }
+
+    fn handle_keyboard_event_after_children(&mut self, event: &KeyboardEvent, env: &mut Environment<GS>, global_state: &mut GS) {
+        if env.is_dispatching_along_path() && env.is_on_event_path(self.get_id()) && !env.is_propagation_stopped() {
+            if self.bubble_keyboard_event(event, env, global_state) == EventPropagation::Stop {
+                env.stop_propagation();
+            }
+        }
+    }
+
+    /// Let the widget handle the mouse entering or exiting it, if the event changed whether the
+    /// widget is hovered.
+    fn handle_hover_change(&mut self, event: &MouseEvent, env: &mut Environment<GS>, global_state: &mut GS) {
+        let id = self.get_id();
+        let position = event.get_current_mouse_position();
+
+        match (env.was_hovered(id), env.is_hovered(id)) {
+            (false, true) => self.handle_mouse_event(&MouseEvent::Enter(position), env, global_state),
+            (true, false) => self.handle_mouse_event(&MouseEvent::Exit(position), env, global_state),
+            _ => {}
+        }
+    }
+
+    /// Find the topmost widget containing the point, among this widget and its descendants. The
+    /// id of the widget found and the ids of its ancestors are pushed to the path, innermost
+    /// first. Returns true if a widget was found.
+    fn hit_test(&self, point: Point, path: &mut Vec<Uuid>) -> bool {
+        self.hit_test_default(point, path)
+    }
+
+    fn hit_test_default(&self, point: Point, path: &mut Vec<Uuid>) -> bool {
+        // The children drawn last are on top, so they are tested first.
+        let children: Vec<&dyn Widget<GS>> = self.get_children().collect();
+
+        let hit = children.iter().rev().any(|child| child.hit_test(point, path)) || self.is_inside(point);
+
+        if hit {
+            path.push(self.get_id());
+        }
+
+        hit
+    }
+}
+
+/// Check if the child needs to be visited while dispatching along the event path. Proxies are
+/// always visited, as their children might be on the path.
+pub(crate) fn is_on_dispatch_route<GS: GlobalState>(child: &dyn Widget<GS>, env: &Environment<GS>) -> bool {
+    child.get_flag() == Flags::PROXY || env.is_on_dispatch_route(child.get_id())
+}
+
+#[test]
+fn mouse_events_are_captured_and_bubbled_along_the_event_path() {
+    use crate::headless::HeadlessUi;
+    use crate::prelude::*;
+    use crate::widget::{ChildRender, SingleChildLayout};
+
+    type Log = Vec<String>;
+
+    /// Logs the mouse presses it gets in each phase, and stops them in the capture phase if told.
+    #[derive(Debug, Clone, Widget)]
+    #[global_state(Log)]
+    #[event(handle_mouse_event, capture_mouse_event, bubble_mouse_event)]
+    struct Recorder {
+        id: Uuid,
+        child: Box<dyn Widget<Log>>,
+        position: Point,
+        dimension: Dimensions,
+        name: &'static str,
+        stop_capture: bool,
+    }
+
+    impl Recorder {
+        fn new(name: &'static str, stop_capture: bool, child: Box<dyn Widget<Log>>) -> Box<Recorder> {
+            Box::new(Recorder {
+                id: Uuid::new_v4(),
+                child,
+                position: [0.0, 0.0],
+                dimension: [0.0, 0.0],
+                name,
+                stop_capture,
+            })
+        }
+
+        fn record(&self, phase: &str, event: &MouseEvent, log: &mut Log) {
+            if let MouseEvent::Press(..) = event {
+                log.push(format!("{} {}", phase, self.name));
+            }
+        }
+
+        fn handle_mouse_event(&mut self, event: &MouseEvent, _: &mut Environment<Log>, log: &mut Log) {
+            self.record("broadcast", event, log);
+        }
+
+        fn capture_mouse_event(&mut self, event: &MouseEvent, _: &mut Environment<Log>, log: &mut Log) -> EventPropagation {
+            self.record("capture", event, log);
+
+            if self.stop_capture {
+                EventPropagation::Stop
+            } else {
+                EventPropagation::Continue
+            }
+        }
+
+        fn bubble_mouse_event(&mut self, event: &MouseEvent, _: &mut Environment<Log>, log: &mut Log) -> EventPropagation {
+            self.record("bubble", event, log);
+            EventPropagation::Continue
+        }
+    }
+
+    impl CommonWidget<Log> for Recorder {
+        fn get_id(&self) -> Uuid {
+            self.id
+        }
+
+        fn set_id(&mut self, id: Uuid) {
+            self.id = id;
+        }
+
+        fn get_flag(&self) -> Flags {
+            Flags::EMPTY
+        }
+
+        fn get_children(&self) -> WidgetIter<Log> {
+            WidgetIter::single(self.child.deref())
+        }
+
+        fn get_children_mut(&mut self) -> WidgetIterMut<Log> {
+            WidgetIterMut::single(self.child.deref_mut())
+        }
+
+        fn get_proxied_children(&mut self) -> WidgetIterMut<Log> {
+            WidgetIterMut::single(self.child.deref_mut())
+        }
+
+        fn get_proxied_children_rev(&mut self) -> WidgetIterMut<Log> {
+            WidgetIterMut::single(self.child.deref_mut())
+        }
+
+        fn get_position(&self) -> Point {
+            self.position
+        }
+
+        fn set_position(&mut self, position: Dimensions) {
+            self.position = position;
+        }
+
+        fn get_dimension(&self) -> Dimensions {
+            self.dimension
+        }
+
+        fn set_dimension(&mut self, dimensions: Dimensions) {
+            self.dimension = dimensions
+        }
+    }
+
+    impl ChildRender for Recorder {}
+
+    impl SingleChildLayout for Recorder {
+        fn flexibility(&self) -> u32 {
+            1
+        }
+    }
+
+    fn press(stop_capture_at: Option<&'static str>) -> Log {
+        let mut ui = HeadlessUi::new([100.0, 100.0], 1.0, vec![]);
+
+        let inner = Recorder::new("inner", stop_capture_at == Some("inner"), Rectangle::initialize(vec![]).frame(50.0, 50.0));
+        let outer = Recorder::new("outer", stop_capture_at == Some("outer"), inner);
+
+        let root: Box<dyn Widget<Log>> = outer;
+        *ui.widgets_mut() = root;
+        ui.draw();
+
+        ui.click_at([50.0, 50.0]);
+        ui.global_state().clone()
+    }
+
+    // The capture phase runs from the parent to the child, and the bubble phase from the child
+    // to the parent, before the event is broadcast.
+    assert_eq!(press(None), vec![
+        "capture outer", "capture inner",
+        "bubble inner", "bubble outer",
+        "broadcast outer", "broadcast inner",
+    ]);
+
+    // Stopping the event while capturing keeps it from the target and from the broadcast.
+    assert_eq!(press(Some("outer")), vec!["capture outer"]);
+    assert_eq!(press(Some("inner")), vec!["capture outer", "capture inner"]);
+}
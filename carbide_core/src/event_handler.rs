@@ -5,6 +5,7 @@ use instant::Instant;
 
 use crate::event::input::Input;
 use crate::event::Motion;
+use crate::event::touch;
 use crate::piston_input::{Button, Key, MouseButton};
 use crate::piston_input::keyboard::ModifierKey;
 use crate::Point;
@@ -31,6 +32,11 @@ impl EventHandler {
     pub fn clear_events(&mut self) {
         self.events.clear()
     }
+
+    /// Add a tick event, letting the widgets act on the passage of time.
+    pub fn tick(&mut self) {
+        self.add_event(WidgetEvent::Window(WindowEvent::Tick))
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -63,6 +69,11 @@ pub enum MouseEvent {
         total_delta_xy: Point,
         modifiers: ModifierKey
     },
+    /// The mouse moved onto the widget, or the widget became the topmost widget under the mouse.
+    /// Sent to each widget that became hovered, including the ancestors of the topmost widget.
+    Enter(Point),
+    /// The mouse left the widget, or the widget is no longer the topmost widget under the mouse.
+    Exit(Point),
 }
 
 impl MouseEvent {
@@ -75,6 +86,8 @@ impl MouseEvent {
             MouseEvent::NClick(_, n, _, _) => {*n}
             MouseEvent::Scroll { mouse_position, .. } => {*mouse_position}
             MouseEvent::Drag {to, .. } => {*to}
+            MouseEvent::Enter(n) => {*n}
+            MouseEvent::Exit(n) => {*n}
         }
     }
 }
@@ -92,12 +105,155 @@ pub enum WindowEvent {
     Resize(Dimensions),
     Focus,
     UnFocus,
-    Redraw
+    Redraw,
+    /// Sent once after a widget requested it with `Environment::request_tick`, such that it can
+    /// act on the passage of time, like recognizing a long press while the mouse is still.
+    Tick,
+    /// Sent after an undo or a redo, such that the undoable states in all the widgets apply the
+    /// restored values.
+    StatesRestored,
 }
 
+/// A finger touching the screen, moving, or being lifted. Each finger gets an id when it
+/// touches the screen, which it keeps until it is lifted. The locations are in the same
+/// coordinates as the locations of the mouse events.
 #[derive(Clone, Debug)]
 pub enum TouchEvent {
-    // Todo: Handle touch events
+    Start(touch::Id, Point),
+    Move(touch::Id, Point),
+    End(touch::Id, Point),
+    /// The touch was interrupted, for example by the system taking over the touch.
+    Cancel(touch::Id, Point),
+}
+
+impl TouchEvent {
+    pub fn get_id(&self) -> touch::Id {
+        match self {
+            TouchEvent::Start(id, _) => *id,
+            TouchEvent::Move(id, _) => *id,
+            TouchEvent::End(id, _) => *id,
+            TouchEvent::Cancel(id, _) => *id,
+        }
+    }
+
+    pub fn get_location(&self) -> Point {
+        match self {
+            TouchEvent::Start(_, location) => *location,
+            TouchEvent::Move(_, location) => *location,
+            TouchEvent::End(_, location) => *location,
+            TouchEvent::Cancel(_, location) => *location,
+        }
+    }
+}
+
+/// The distance a finger can move and still click, larger than for the mouse as fingers are less
+/// precise.
+const TOUCH_CLICK_DISTANCE: Scalar = 10.0;
+
+/// Synthesizes mouse events from the touches of a single finger, such that the widgets only
+/// handling the mouse can be used on a touch screen. A second finger touching the screen cancels
+/// the synthesized press, as the fingers are likely making a gesture.
+#[derive(Debug, Default)]
+pub(crate) struct MouseFromTouch {
+    /// The number of fingers touching the screen.
+    fingers: usize,
+    /// The id of the finger moving the synthesized mouse, along with the location it touched and
+    /// its latest location.
+    finger: Option<(touch::Id, Point, Point)>,
+}
+
+impl MouseFromTouch {
+    /// The mouse events to synthesize for the touch event. When a widget consumed the touch
+    /// event, the synthesized press is released, and no more events are synthesized until the
+    /// finger is lifted.
+    pub fn mouse_events(&mut self, event: &TouchEvent, consumed: bool) -> Vec<MouseEvent> {
+        let modifiers = ModifierKey::default();
+
+        match *event {
+            TouchEvent::Start(id, location) => {
+                self.fingers += 1;
+
+                if self.fingers == 1 && !consumed {
+                    self.finger = Some((id, location, location));
+
+                    // The move lets the widgets under the finger know they are hovered.
+                    return vec![
+                        MouseEvent::Move { from: location, to: location, delta_xy: [0.0, 0.0], modifiers },
+                        MouseEvent::Press(MouseButton::Left, location, modifiers),
+                    ]
+                }
+
+                self.cancel()
+            }
+            TouchEvent::Move(id, location) => {
+                match self.finger {
+                    Some((finger, origin, from)) if finger == id => {
+                        if consumed {
+                            return self.cancel()
+                        }
+
+                        self.finger = Some((finger, origin, location));
+
+                        let delta_xy = utils::vec2_sub(location, from);
+
+                        vec![
+                            MouseEvent::Move { from, to: location, delta_xy, modifiers },
+                            MouseEvent::Drag {
+                                button: MouseButton::Left,
+                                origin,
+                                from,
+                                to: location,
+                                delta_xy,
+                                total_delta_xy: utils::vec2_sub(location, origin),
+                                modifiers,
+                            },
+                        ]
+                    }
+                    _ => vec![],
+                }
+            }
+            TouchEvent::End(id, location) => {
+                self.fingers = self.fingers.saturating_sub(1);
+
+                match self.finger {
+                    Some((finger, origin, _)) if finger == id => {
+                        if consumed {
+                            return self.cancel()
+                        }
+
+                        self.finger = None;
+
+                        let mut events = vec![MouseEvent::Release(MouseButton::Left, location, modifiers)];
+
+                        let total_delta_xy = utils::vec2_sub(location, origin);
+
+                        if total_delta_xy[0].hypot(total_delta_xy[1]) < TOUCH_CLICK_DISTANCE {
+                            events.push(MouseEvent::Click(MouseButton::Left, location, modifiers));
+                        }
+
+                        events
+                    }
+                    _ => vec![],
+                }
+            }
+            TouchEvent::Cancel(id, _) => {
+                self.fingers = self.fingers.saturating_sub(1);
+
+                match self.finger {
+                    Some((finger, _, _)) if finger == id => self.cancel(),
+                    _ => vec![],
+                }
+            }
+        }
+    }
+
+    /// Stop synthesizing mouse events, releasing the synthesized press without clicking.
+    fn cancel(&mut self) -> Vec<MouseEvent> {
+        match self.finger.take() {
+            Some((_, _, location)) => vec![MouseEvent::Release(MouseButton::Left, location, ModifierKey::default())],
+            None => vec![],
+        }
+    }
 }
 
 
@@ -391,8 +547,20 @@ impl EventHandler {
                 None
             },
 
-            Input::Touch(touch) => match touch.phase {
-                _ => {None}
+            Input::Touch(touch) => {
+                // Translate the location the same way as the mouse position.
+                let location = [touch.xy[0] + window_dimensions[0] / 2.0, window_dimensions[1] - (touch.xy[1] + window_dimensions[1] / 2.0)];
+
+                let event = match touch.phase {
+                    touch::Phase::Start => TouchEvent::Start(touch.id, location),
+                    touch::Phase::Move => TouchEvent::Move(touch.id, location),
+                    touch::Phase::End => TouchEvent::End(touch.id, location),
+                    touch::Phase::Cancel => TouchEvent::Cancel(touch.id, location),
+                };
+
+                self.add_event(WidgetEvent::Touch(event));
+
+                None
             },
 
             Input::Focus(focused) if focused == true => {
@@ -411,4 +579,64 @@ impl EventHandler {
             },
         }
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn single_finger_synthesizes_a_click() {
+    let mut mouse = MouseFromTouch::default();
+    let finger = touch::Id::new(1);
+
+    assert_eq!(mouse.mouse_events(&TouchEvent::Start(finger, [10.0, 10.0]), false).len(), 2);
+    assert_eq!(mouse.mouse_events(&TouchEvent::Move(finger, [12.0, 10.0]), false).len(), 2);
+
+    let events = mouse.mouse_events(&TouchEvent::End(finger, [12.0, 10.0]), false);
+    assert!(matches!(events.as_slice(), [MouseEvent::Release(..), MouseEvent::Click(..)]));
+}
+
+#[test]
+fn second_finger_cancels_the_press() {
+    let mut mouse = MouseFromTouch::default();
+
+    mouse.mouse_events(&TouchEvent::Start(touch::Id::new(1), [10.0, 10.0]), false);
+
+    let events = mouse.mouse_events(&TouchEvent::Start(touch::Id::new(2), [50.0, 10.0]), false);
+    assert!(matches!(events.as_slice(), [MouseEvent::Release(..)]));
+
+    assert!(mouse.mouse_events(&TouchEvent::End(touch::Id::new(1), [10.0, 10.0]), false).is_empty());
+}
+
+#[test]
+fn moving_finger_synthesizes_a_drag_without_a_click() {
+    let mut mouse = MouseFromTouch::default();
+    let finger = touch::Id::new(1);
+
+    let events = mouse.mouse_events(&TouchEvent::Start(finger, [10.0, 10.0]), false);
+    assert!(matches!(events.as_slice(), [MouseEvent::Move { .. }, MouseEvent::Press(MouseButton::Left, _, _)]));
+
+    let events = mouse.mouse_events(&TouchEvent::Move(finger, [40.0, 10.0]), false);
+    match events.as_slice() {
+        [MouseEvent::Move { .. }, MouseEvent::Drag { origin, to, total_delta_xy, .. }] => {
+            assert_eq!((*origin, *to, *total_delta_xy), ([10.0, 10.0], [40.0, 10.0], [30.0, 0.0]));
+        }
+        events => panic!("Expected a move and a drag, got {:?}", events),
+    }
+
+    let events = mouse.mouse_events(&TouchEvent::End(finger, [40.0, 10.0]), false);
+    assert!(matches!(events.as_slice(), [MouseEvent::Release(..)]));
+}
+
+#[test]
+fn consumed_touches_synthesize_no_mouse_events() {
+    let mut mouse = MouseFromTouch::default();
+    let finger = touch::Id::new(1);
+
+    assert!(mouse.mouse_events(&TouchEvent::Start(finger, [10.0, 10.0]), true).is_empty());
+    assert!(mouse.mouse_events(&TouchEvent::Move(finger, [40.0, 10.0]), false).is_empty());
+    assert!(mouse.mouse_events(&TouchEvent::End(finger, [40.0, 10.0]), false).is_empty());
+
+    // Consuming a touch after it started releases the synthesized press without a click.
+    mouse.mouse_events(&TouchEvent::Start(finger, [10.0, 10.0]), false);
+    let events = mouse.mouse_events(&TouchEvent::Move(finger, [40.0, 10.0]), true);
+    assert!(matches!(events.as_slice(), [MouseEvent::Release(..)]));
+    assert!(mouse.mouse_events(&TouchEvent::End(finger, [40.0, 10.0]), false).is_empty());
+}
@@ -0,0 +1,109 @@
+use crate::event::touch::Id;
+use crate::gesture::distance;
+use crate::Point;
+use crate::Scalar;
+
+/// The two fingers of a pinch or a rotation, along with their locations when the second finger
+/// touched. Any further fingers are ignored.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct FingerPair {
+    fingers: Vec<(Id, Point)>,
+    start: Option<(Point, Point)>,
+}
+
+impl FingerPair {
+    /// Add a finger touching the screen, if the pair is not complete. Returns true if the finger
+    /// was added.
+    pub fn add(&mut self, id: Id, location: Point) -> bool {
+        if self.fingers.len() == 2 {
+            return false
+        }
+
+        self.fingers.push((id, location));
+
+        if self.fingers.len() == 2 {
+            self.start = Some((self.fingers[0].1, self.fingers[1].1));
+        }
+
+        true
+    }
+
+    /// Update the location of a finger. Returns true if the finger is one of the pair.
+    pub fn update(&mut self, id: Id, location: Point) -> bool {
+        match self.fingers.iter_mut().find(|(finger_id, _)| *finger_id == id) {
+            Some(finger) => {
+                finger.1 = location;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove the finger from the pair. Returns true if the pair was complete.
+    pub fn remove(&mut self, id: Id) -> bool {
+        if !self.fingers.iter().any(|(finger_id, _)| *finger_id == id) {
+            return false
+        }
+
+        let was_complete = self.is_complete();
+
+        self.fingers.retain(|(finger_id, _)| *finger_id != id);
+        self.start = None;
+
+        was_complete
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.start.is_some()
+    }
+
+    /// The distance between the fingers relative to the distance when the pair was completed.
+    pub fn scale(&self) -> Scalar {
+        match self.start {
+            Some((a, b)) if distance(a, b) > 0.0 => {
+                distance(self.fingers[0].1, self.fingers[1].1) / distance(a, b)
+            }
+            _ => 1.0,
+        }
+    }
+
+    /// The angle in radians the line between the fingers has turned since the pair was completed.
+    pub fn angle(&self) -> Scalar {
+        match self.start {
+            Some((a, b)) => {
+                let (c, d) = (self.fingers[0].1, self.fingers[1].1);
+                let angle = (d[1] - c[1]).atan2(d[0] - c[0]) - (b[1] - a[1]).atan2(b[0] - a[0]);
+
+                // Keep the angle within a half turn in either direction.
+                (angle + std::f64::consts::PI).rem_euclid(2.0 * std::f64::consts::PI) - std::f64::consts::PI
+            }
+            None => 0.0,
+        }
+    }
+
+    pub fn center(&self) -> Point {
+        match self.fingers.as_slice() {
+            [(_, a), (_, b)] => [(a[0] + b[0]) / 2.0, (a[1] + b[1]) / 2.0],
+            [(_, a)] => *a,
+            _ => [0.0, 0.0],
+        }
+    }
+}
+
+#[test]
+fn pair_tracks_scale_and_angle() {
+    let mut pair = FingerPair::default();
+
+    assert!(pair.add(Id::new(1), [0.0, 0.0]));
+    assert!(pair.add(Id::new(2), [10.0, 0.0]));
+    assert!(!pair.add(Id::new(3), [5.0, 5.0]));
+
+    assert!(pair.update(Id::new(2), [0.0, 20.0]));
+
+    assert_eq!(pair.scale(), 2.0);
+    assert!((pair.angle() - std::f64::consts::FRAC_PI_2).abs() < 1e-9);
+    assert_eq!(pair.center(), [0.0, 10.0]);
+
+    assert!(pair.remove(Id::new(1)));
+    assert!(!pair.is_complete());
+}
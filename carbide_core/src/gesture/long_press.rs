@@ -0,0 +1,105 @@
+use std::time::Duration;
+
+use instant::Instant;
+
+use crate::event_handler::{MouseEvent, WidgetEvent, WindowEvent};
+use crate::gesture::{distance, Gesture, GestureFn, TAP_DISTANCE};
+use crate::input::MouseButton;
+use crate::Point;
+use crate::prelude::Environment;
+use crate::state::global_state::GlobalState;
+
+/// Recognizes the left mouse button being held down without moving the mouse. The callback is
+/// called with the location of the press once the button has been held for the duration, even if
+/// the mouse is not moved in the meantime.
+#[derive(Debug, Clone)]
+pub struct LongPressGesture<GS> where GS: GlobalState {
+    duration: Duration,
+    on_end: Option<Box<dyn GestureFn<Point, GS>>>,
+    /// The time and the location of the press, while the button is held down.
+    pressed: Option<(Instant, Point)>,
+}
+
+impl<GS: GlobalState> LongPressGesture<GS> {
+    pub fn new() -> Self {
+        LongPressGesture {
+            duration: Duration::from_millis(500),
+            on_end: None,
+            pressed: None,
+        }
+    }
+
+    /// The time the button needs to be held down.
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    pub fn on_end<C>(mut self, callback: C) -> Self
+        where C: Fn(&Point, &mut Environment<GS>, &mut GS) + Clone + 'static {
+        self.on_end = Some(Box::new(callback));
+        self
+    }
+}
+
+impl<GS: GlobalState> Gesture<GS> for LongPressGesture<GS> {
+    fn handle_mouse_event(&mut self, event: &MouseEvent, inside: bool, env: &mut Environment<GS>, _: &mut GS) {
+        match event {
+            MouseEvent::Press(MouseButton::Left, location, _) if inside => {
+                self.pressed = Some((Instant::now(), *location));
+                env.request_tick();
+            }
+            MouseEvent::Drag { button: MouseButton::Left, to, .. } => {
+                if let Some((_, location)) = self.pressed {
+                    if distance(location, *to) >= TAP_DISTANCE {
+                        self.pressed = None;
+                    }
+                }
+            }
+            MouseEvent::Release(MouseButton::Left, _, _) => {
+                self.pressed = None;
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_other_event(&mut self, event: &WidgetEvent, env: &mut Environment<GS>, global_state: &mut GS) {
+        if let WidgetEvent::Window(WindowEvent::Tick) = event {
+            if let Some((time, location)) = self.pressed {
+                if time.elapsed() < self.duration {
+                    env.request_tick();
+                    return
+                }
+
+                self.pressed = None;
+
+                if let Some(on_end) = &self.on_end {
+                    on_end(&location, env, global_state);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn long_press_ends_on_the_first_tick_after_the_duration() {
+    use crate::input::ModifierKey;
+
+    let mut env = Environment::<Vec<Point>>::new(vec![], [100.0, 100.0], 1.0);
+    let mut ended_at = vec![];
+    let mut gesture = LongPressGesture::new()
+        .on_end(|location: &Point, _: &mut Environment<Vec<Point>>, ended_at: &mut Vec<Point>| ended_at.push(*location));
+
+    gesture.handle_mouse_event(&MouseEvent::Press(MouseButton::Left, [10.0, 10.0], ModifierKey::NO_MODIFIER), true, &mut env, &mut ended_at);
+    assert!(env.take_tick_request());
+
+    // Ticks before the duration has passed keep requesting ticks.
+    gesture.handle_other_event(&WidgetEvent::Window(WindowEvent::Tick), &mut env, &mut ended_at);
+    assert!(ended_at.is_empty());
+    assert!(env.take_tick_request());
+
+    gesture.pressed = Some((Instant::now() - Duration::from_millis(600), [10.0, 10.0]));
+    gesture.handle_other_event(&WidgetEvent::Window(WindowEvent::Tick), &mut env, &mut ended_at);
+    assert_eq!(ended_at, vec![[10.0, 10.0]]);
+    assert!(!env.take_tick_request());
+}
@@ -0,0 +1,68 @@
+//! Gesture recognizers, turning the raw events received by a widget into taps, long presses,
+//! pans, pinches and rotations. Recognizers are attached to widgets with `WidgetExt::gesture`.
+
+use std::fmt;
+use std::fmt::Debug;
+use std::time::Duration;
+
+use dyn_clone::DynClone;
+
+use crate::event_handler::{MouseEvent, TouchEvent, WidgetEvent};
+use crate::Point;
+use crate::prelude::Environment;
+use crate::Scalar;
+use crate::state::global_state::GlobalState;
+
+pub use self::long_press::LongPressGesture;
+pub use self::pan::{PanGesture, PanValue};
+pub use self::pinch::{PinchGesture, PinchValue};
+pub use self::rotate::{RotateGesture, RotateValue};
+pub use self::tap::TapGesture;
+
+mod finger_pair;
+pub mod long_press;
+pub mod pan;
+pub mod pinch;
+pub mod rotate;
+pub mod tap;
+
+/// The distance the mouse can move while pressed and still be a tap or a long press.
+const TAP_DISTANCE: Scalar = 10.0;
+
+/// The longest time between two taps counting as repeated taps.
+const TAP_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A recognizer of a gesture, fed with the events received by the widget it is attached to.
+pub trait Gesture<GS>: Debug + DynClone where GS: GlobalState {
+    /// Handle a mouse event. The event is inside if the widget, or one of its descendants, is the
+    /// topmost widget under the mouse.
+    fn handle_mouse_event(&mut self, event: &MouseEvent, inside: bool, env: &mut Environment<GS>, global_state: &mut GS);
+
+    /// Handle a touch event. The event is inside if the finger is within the bounds of the
+    /// widget. Stop the propagation of the touch events used by the gesture, such that no mouse
+    /// events are synthesized from them.
+    fn handle_touch_event(&mut self, _event: &TouchEvent, _inside: bool, _env: &mut Environment<GS>, _global_state: &mut GS) {}
+
+    /// Handle the events other than mouse, keyboard and touch events, like the ticks requested with
+    /// `Environment::request_tick`.
+    fn handle_other_event(&mut self, _event: &WidgetEvent, _env: &mut Environment<GS>, _global_state: &mut GS) {}
+}
+
+dyn_clone::clone_trait_object!(<GS: GlobalState> Gesture<GS>);
+
+/// Called with the value of a gesture when it changes or ends.
+pub trait GestureFn<T, GS: GlobalState>: Fn(&T, &mut Environment<GS>, &mut GS) + DynClone {}
+
+impl<T, GS: GlobalState, F> GestureFn<T, GS> for F where F: Fn(&T, &mut Environment<GS>, &mut GS) + DynClone {}
+
+dyn_clone::clone_trait_object!(<T, GS: GlobalState> GestureFn<T, GS>);
+
+impl<T, GS: GlobalState> Debug for dyn GestureFn<T, GS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "GestureFn")
+    }
+}
+
+fn distance(a: Point, b: Point) -> Scalar {
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2)).sqrt()
+}
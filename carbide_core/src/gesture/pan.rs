@@ -0,0 +1,158 @@
+use std::time::Duration;
+
+use instant::Instant;
+
+use crate::event_handler::MouseEvent;
+use crate::gesture::{distance, Gesture, GestureFn};
+use crate::input::MouseButton;
+use crate::Point;
+use crate::prelude::Environment;
+use crate::Scalar;
+use crate::state::global_state::GlobalState;
+
+/// The state of a pan gesture.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PanValue {
+    pub start_location: Point,
+    pub location: Point,
+    /// The distance moved since the start of the pan.
+    pub translation: Point,
+    /// The speed of the pan along each axis, in points per second.
+    pub velocity: Point,
+}
+
+/// Recognizes dragging with the left mouse button pressed. The pan starts when the mouse has
+/// moved the minimum distance from the press.
+#[derive(Debug, Clone)]
+pub struct PanGesture<GS> where GS: GlobalState {
+    minimum_distance: Scalar,
+    on_changed: Option<Box<dyn GestureFn<PanValue, GS>>>,
+    on_end: Option<Box<dyn GestureFn<PanValue, GS>>>,
+    /// The location of the press, while the button is held down.
+    start_location: Option<Point>,
+    active: bool,
+    /// The time and the location of the latest drag, used to calculate the velocity.
+    last_drag: Option<(Instant, Point)>,
+    velocity: Point,
+}
+
+impl<GS: GlobalState> PanGesture<GS> {
+    pub fn new() -> Self {
+        PanGesture {
+            minimum_distance: 10.0,
+            on_changed: None,
+            on_end: None,
+            start_location: None,
+            active: false,
+            last_drag: None,
+            velocity: [0.0, 0.0],
+        }
+    }
+
+    pub fn minimum_distance(mut self, distance: Scalar) -> Self {
+        self.minimum_distance = distance;
+        self
+    }
+
+    pub fn on_changed<C>(mut self, callback: C) -> Self
+        where C: Fn(&PanValue, &mut Environment<GS>, &mut GS) + Clone + 'static {
+        self.on_changed = Some(Box::new(callback));
+        self
+    }
+
+    pub fn on_end<C>(mut self, callback: C) -> Self
+        where C: Fn(&PanValue, &mut Environment<GS>, &mut GS) + Clone + 'static {
+        self.on_end = Some(Box::new(callback));
+        self
+    }
+
+    fn value(&self, start_location: Point, location: Point) -> PanValue {
+        PanValue {
+            start_location,
+            location,
+            translation: [location[0] - start_location[0], location[1] - start_location[1]],
+            velocity: self.velocity,
+        }
+    }
+}
+
+/// The velocity of a move from one location to another. Returns `None` if no time has passed.
+fn velocity(from: Point, to: Point, elapsed: Duration) -> Option<Point> {
+    let seconds = elapsed.as_secs_f64();
+
+    if seconds > 0.0 {
+        Some([(to[0] - from[0]) / seconds, (to[1] - from[1]) / seconds])
+    } else {
+        None
+    }
+}
+
+/// If the mouse is held still for longer than this before it is released, the pan ends without
+/// velocity.
+const VELOCITY_TIMEOUT: Duration = Duration::from_millis(100);
+
+impl<GS: GlobalState> Gesture<GS> for PanGesture<GS> {
+    fn handle_mouse_event(&mut self, event: &MouseEvent, inside: bool, env: &mut Environment<GS>, global_state: &mut GS) {
+        match event {
+            MouseEvent::Press(MouseButton::Left, location, _) if inside => {
+                self.start_location = Some(*location);
+                self.active = false;
+                self.last_drag = Some((Instant::now(), *location));
+                self.velocity = [0.0, 0.0];
+            }
+            MouseEvent::Drag { button: MouseButton::Left, to, .. } => {
+                let start_location = match self.start_location {
+                    Some(start_location) => start_location,
+                    None => return,
+                };
+
+                let now = Instant::now();
+
+                if let Some((time, location)) = self.last_drag {
+                    if let Some(velocity) = velocity(location, *to, now.duration_since(time)) {
+                        self.velocity = velocity;
+                    }
+                }
+
+                self.last_drag = Some((now, *to));
+
+                if !self.active && distance(start_location, *to) >= self.minimum_distance {
+                    self.active = true;
+                }
+
+                if self.active {
+                    let value = self.value(start_location, *to);
+
+                    if let Some(on_changed) = &self.on_changed {
+                        on_changed(&value, env, global_state);
+                    }
+                }
+            }
+            MouseEvent::Release(MouseButton::Left, location, _) => {
+                if let (Some(start_location), true) = (self.start_location.take(), self.active) {
+                    if let Some((time, _)) = self.last_drag {
+                        if time.elapsed() > VELOCITY_TIMEOUT {
+                            self.velocity = [0.0, 0.0];
+                        }
+                    }
+
+                    let value = self.value(start_location, *location);
+
+                    if let Some(on_end) = &self.on_end {
+                        on_end(&value, env, global_state);
+                    }
+                }
+
+                self.active = false;
+                self.last_drag = None;
+            }
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn velocity_is_in_points_per_second() {
+    assert_eq!(velocity([0.0, 0.0], [10.0, -5.0], Duration::from_millis(100)), Some([100.0, -50.0]));
+    assert_eq!(velocity([0.0, 0.0], [10.0, -5.0], Duration::from_millis(0)), None);
+}
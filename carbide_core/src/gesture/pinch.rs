@@ -0,0 +1,151 @@
+use crate::event_handler::{MouseEvent, TouchEvent};
+use crate::gesture::{Gesture, GestureFn};
+use crate::gesture::finger_pair::FingerPair;
+use crate::input::ModifierKey;
+use crate::Point;
+use crate::prelude::Environment;
+use crate::Scalar;
+use crate::state::global_state::GlobalState;
+
+/// The state of a pinch gesture.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PinchValue {
+    /// The scale relative to the start of the pinch.
+    pub scale: Scalar,
+    pub center: Point,
+}
+
+/// Recognizes two fingers pinching on a touch screen, and pinching on a trackpad. Trackpad pinches
+/// are reported as scrolling while the control key is held, the same way mouse users zoom, where
+/// scrolling up zooms in. A trackpad pinch ends with the first other mouse event.
+#[derive(Debug, Clone)]
+pub struct PinchGesture<GS> where GS: GlobalState {
+    on_changed: Option<Box<dyn GestureFn<PinchValue, GS>>>,
+    on_end: Option<Box<dyn GestureFn<PinchValue, GS>>>,
+    /// The value of the pinch in progress, if any.
+    value: Option<PinchValue>,
+    fingers: FingerPair,
+}
+
+/// The change of scale for each scrolled point.
+const SCROLL_SCALE: Scalar = 0.01;
+
+impl<GS: GlobalState> PinchGesture<GS> {
+    pub fn new() -> Self {
+        PinchGesture {
+            on_changed: None,
+            on_end: None,
+            value: None,
+            fingers: FingerPair::default(),
+        }
+    }
+
+    pub fn on_changed<C>(mut self, callback: C) -> Self
+        where C: Fn(&PinchValue, &mut Environment<GS>, &mut GS) + Clone + 'static {
+        self.on_changed = Some(Box::new(callback));
+        self
+    }
+
+    pub fn on_end<C>(mut self, callback: C) -> Self
+        where C: Fn(&PinchValue, &mut Environment<GS>, &mut GS) + Clone + 'static {
+        self.on_end = Some(Box::new(callback));
+        self
+    }
+}
+
+impl<GS: GlobalState> Gesture<GS> for PinchGesture<GS> {
+    fn handle_mouse_event(&mut self, event: &MouseEvent, inside: bool, env: &mut Environment<GS>, global_state: &mut GS) {
+        match event {
+            MouseEvent::Scroll { y, mouse_position, modifiers, .. } if modifiers.contains(ModifierKey::CTRL) => {
+                if !inside && self.value.is_none() {
+                    return
+                }
+
+                let scale = self.value.map_or(1.0, |value| value.scale) * (y * SCROLL_SCALE).exp();
+                let value = PinchValue { scale, center: *mouse_position };
+
+                self.value = Some(value);
+
+                if let Some(on_changed) = &self.on_changed {
+                    on_changed(&value, env, global_state);
+                }
+            }
+            // Entering or exiting widgets does not end the pinch.
+            MouseEvent::Enter(_) | MouseEvent::Exit(_) => {}
+            _ => {
+                if self.fingers.is_complete() {
+                    return
+                }
+
+                if let Some(value) = self.value.take() {
+                    if let Some(on_end) = &self.on_end {
+                        on_end(&value, env, global_state);
+                    }
+                }
+            }
+        }
+    }
+
+    fn handle_touch_event(&mut self, event: &TouchEvent, inside: bool, env: &mut Environment<GS>, global_state: &mut GS) {
+        match *event {
+            TouchEvent::Start(id, location) => {
+                if inside && self.fingers.add(id, location) && self.fingers.is_complete() {
+                    env.stop_propagation();
+                }
+            }
+            TouchEvent::Move(id, location) => {
+                if self.fingers.update(id, location) && self.fingers.is_complete() {
+                    env.stop_propagation();
+
+                    let value = PinchValue { scale: self.fingers.scale(), center: self.fingers.center() };
+                    self.value = Some(value);
+
+                    if let Some(on_changed) = &self.on_changed {
+                        on_changed(&value, env, global_state);
+                    }
+                }
+            }
+            TouchEvent::End(id, _) | TouchEvent::Cancel(id, _) => {
+                if self.fingers.remove(id) {
+                    env.stop_propagation();
+
+                    if let Some(value) = self.value.take() {
+                        if let Some(on_end) = &self.on_end {
+                            on_end(&value, env, global_state);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn scrolling_with_control_pinches() {
+    // The scales reported while pinching, and the scale the pinch ended with.
+    type Scales = (Vec<Scalar>, Option<Scalar>);
+
+    let mut env = Environment::<Scales>::new(vec![], [100.0, 100.0], 1.0);
+    let mut scales = (vec![], None);
+    let mut gesture = PinchGesture::new()
+        .on_changed(|value: &PinchValue, _: &mut Environment<Scales>, scales: &mut Scales| scales.0.push(value.scale))
+        .on_end(|value: &PinchValue, _: &mut Environment<Scales>, scales: &mut Scales| scales.1 = Some(value.scale));
+
+    let scroll = |y: Scalar, modifiers: ModifierKey| MouseEvent::Scroll { x: 0.0, y, mouse_position: [10.0, 10.0], modifiers };
+
+    // Scrolling outside the widget does not start a pinch, and neither does scrolling without
+    // the control key.
+    gesture.handle_mouse_event(&scroll(10.0, ModifierKey::CTRL), false, &mut env, &mut scales);
+    gesture.handle_mouse_event(&scroll(10.0, ModifierKey::NO_MODIFIER), true, &mut env, &mut scales);
+    assert!(scales.0.is_empty());
+
+    gesture.handle_mouse_event(&scroll(10.0, ModifierKey::CTRL), true, &mut env, &mut scales);
+    gesture.handle_mouse_event(&scroll(10.0, ModifierKey::CTRL), true, &mut env, &mut scales);
+    assert_eq!(scales.0.len(), 2);
+    assert!((scales.0[1] - (0.2 as Scalar).exp()).abs() < 1e-9);
+    assert_eq!(scales.1, None);
+
+    // The next other mouse event ends the pinch.
+    gesture.handle_mouse_event(&MouseEvent::Move { from: [10.0, 10.0], to: [11.0, 10.0], delta_xy: [1.0, 0.0], modifiers: ModifierKey::NO_MODIFIER }, true, &mut env, &mut scales);
+    assert_eq!(scales.1, Some(scales.0[1]));
+}
@@ -0,0 +1,85 @@
+use crate::event_handler::{MouseEvent, TouchEvent};
+use crate::gesture::{Gesture, GestureFn};
+use crate::gesture::finger_pair::FingerPair;
+use crate::Point;
+use crate::prelude::Environment;
+use crate::Scalar;
+use crate::state::global_state::GlobalState;
+
+/// The state of a rotate gesture.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RotateValue {
+    /// The angle in radians relative to the start of the rotation, clockwise on screen.
+    pub angle: Scalar,
+    pub center: Point,
+}
+
+/// Recognizes two fingers rotating on a touch screen.
+#[derive(Debug, Clone)]
+pub struct RotateGesture<GS> where GS: GlobalState {
+    on_changed: Option<Box<dyn GestureFn<RotateValue, GS>>>,
+    on_end: Option<Box<dyn GestureFn<RotateValue, GS>>>,
+    /// The value of the rotation in progress, if any.
+    value: Option<RotateValue>,
+    fingers: FingerPair,
+}
+
+impl<GS: GlobalState> RotateGesture<GS> {
+    pub fn new() -> Self {
+        RotateGesture {
+            on_changed: None,
+            on_end: None,
+            value: None,
+            fingers: FingerPair::default(),
+        }
+    }
+
+    pub fn on_changed<C>(mut self, callback: C) -> Self
+        where C: Fn(&RotateValue, &mut Environment<GS>, &mut GS) + Clone + 'static {
+        self.on_changed = Some(Box::new(callback));
+        self
+    }
+
+    pub fn on_end<C>(mut self, callback: C) -> Self
+        where C: Fn(&RotateValue, &mut Environment<GS>, &mut GS) + Clone + 'static {
+        self.on_end = Some(Box::new(callback));
+        self
+    }
+}
+
+impl<GS: GlobalState> Gesture<GS> for RotateGesture<GS> {
+    fn handle_mouse_event(&mut self, _: &MouseEvent, _: bool, _: &mut Environment<GS>, _: &mut GS) {}
+
+    fn handle_touch_event(&mut self, event: &TouchEvent, inside: bool, env: &mut Environment<GS>, global_state: &mut GS) {
+        match *event {
+            TouchEvent::Start(id, location) => {
+                if inside && self.fingers.add(id, location) && self.fingers.is_complete() {
+                    env.stop_propagation();
+                }
+            }
+            TouchEvent::Move(id, location) => {
+                if self.fingers.update(id, location) && self.fingers.is_complete() {
+                    env.stop_propagation();
+
+                    let value = RotateValue { angle: self.fingers.angle(), center: self.fingers.center() };
+                    self.value = Some(value);
+
+                    if let Some(on_changed) = &self.on_changed {
+                        on_changed(&value, env, global_state);
+                    }
+                }
+            }
+            TouchEvent::End(id, _) | TouchEvent::Cancel(id, _) => {
+                if self.fingers.remove(id) {
+                    env.stop_propagation();
+
+                    if let Some(value) = self.value.take() {
+                        if let Some(on_end) = &self.on_end {
+                            on_end(&value, env, global_state);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
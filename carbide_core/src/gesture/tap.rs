@@ -0,0 +1,115 @@
+use instant::Instant;
+
+use crate::event_handler::MouseEvent;
+use crate::gesture::{distance, Gesture, GestureFn, TAP_DISTANCE, TAP_INTERVAL};
+use crate::input::MouseButton;
+use crate::Point;
+use crate::prelude::Environment;
+use crate::state::global_state::GlobalState;
+
+/// Recognizes a number of taps: presses and releases of the left mouse button without moving the
+/// mouse. Taps are counted while they follow each other within half a second, like the clicks of
+/// a double click. The callback is called with the location of the last tap.
+#[derive(Debug, Clone)]
+pub struct TapGesture<GS> where GS: GlobalState {
+    count: u32,
+    on_end: Option<Box<dyn GestureFn<Point, GS>>>,
+    /// The location of the press, while the button is held down.
+    pressed_at: Option<Point>,
+    /// The number of taps counted, and the time and the location of the latest of them.
+    taps: u32,
+    last_tap: Option<(Instant, Point)>,
+}
+
+impl<GS: GlobalState> TapGesture<GS> {
+    pub fn new() -> Self {
+        TapGesture {
+            count: 1,
+            on_end: None,
+            pressed_at: None,
+            taps: 0,
+            last_tap: None,
+        }
+    }
+
+    /// The number of taps needed, like 2 for a double tap.
+    pub fn count(mut self, count: u32) -> Self {
+        self.count = count.max(1);
+        self
+    }
+
+    pub fn on_end<C>(mut self, callback: C) -> Self
+        where C: Fn(&Point, &mut Environment<GS>, &mut GS) + Clone + 'static {
+        self.on_end = Some(Box::new(callback));
+        self
+    }
+}
+
+/// Check if a tap at the location continues the taps counted so far.
+fn is_repeated_tap(last_tap: Option<(Instant, Point)>, now: Instant, location: Point) -> bool {
+    match last_tap {
+        Some((time, last_location)) => {
+            now.duration_since(time) < TAP_INTERVAL && distance(last_location, location) < TAP_DISTANCE
+        }
+        None => false,
+    }
+}
+
+impl<GS: GlobalState> TapGesture<GS> {
+    /// Feed the mouse event to the recognizer, returning the location of the last tap once the
+    /// taps are recognized. Used by widgets reacting to taps without a callback.
+    pub fn recognize(&mut self, event: &MouseEvent, inside: bool) -> Option<Point> {
+        match event {
+            MouseEvent::Press(MouseButton::Left, location, _) if inside => {
+                self.pressed_at = Some(*location);
+                None
+            }
+            MouseEvent::Release(MouseButton::Left, location, _) => {
+                let pressed_at = self.pressed_at.take()?;
+
+                if !inside || distance(pressed_at, *location) >= TAP_DISTANCE {
+                    self.taps = 0;
+                    self.last_tap = None;
+                    return None
+                }
+
+                let now = Instant::now();
+
+                self.taps = if is_repeated_tap(self.last_tap, now, *location) { self.taps + 1 } else { 1 };
+                self.last_tap = Some((now, *location));
+
+                if self.taps == self.count {
+                    self.taps = 0;
+                    self.last_tap = None;
+
+                    Some(*location)
+                } else {
+                    None
+                }
+            }
+            _ => None
+        }
+    }
+}
+
+impl<GS: GlobalState> Gesture<GS> for TapGesture<GS> {
+    fn handle_mouse_event(&mut self, event: &MouseEvent, inside: bool, env: &mut Environment<GS>, global_state: &mut GS) {
+        if let Some(location) = self.recognize(event, inside) {
+            if let Some(on_end) = &self.on_end {
+                on_end(&location, env, global_state);
+            }
+        }
+    }
+}
+
+#[test]
+fn taps_close_in_time_and_space_are_repeated() {
+    let now = Instant::now();
+    let earlier = now - std::time::Duration::from_millis(200);
+    let much_earlier = now - std::time::Duration::from_millis(800);
+
+    assert!(is_repeated_tap(Some((earlier, [10.0, 10.0])), now, [12.0, 11.0]));
+    assert!(!is_repeated_tap(Some((earlier, [10.0, 10.0])), now, [40.0, 10.0]));
+    assert!(!is_repeated_tap(Some((much_earlier, [10.0, 10.0])), now, [10.0, 10.0]));
+    assert!(!is_repeated_tap(None, now, [10.0, 10.0]));
+}
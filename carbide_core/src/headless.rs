@@ -0,0 +1,272 @@
+//! A headless driver for a carbide `Ui`.
+//!
+//! The `HeadlessUi` owns a `Ui` and a global state, but no window or GPU. Inputs are fed through
+//! the same `EventHandler` the window backends use, which makes it possible to drive a tree of
+//! widgets from tests and assert on the widgets and the produced primitives afterwards.
+
+use std::path::Path;
+
+use crate::event::input::Input;
+use crate::event::Motion;
+use crate::image_map::{Id, ImageMap};
+use crate::input::{Button, Key, ModifierKey, MouseButton};
+use crate::position::{Dimensions, Point};
+use crate::prelude::{Environment, EnvironmentColor};
+use crate::render::cprimitives::CPrimitives;
+use crate::state::global_state::GlobalState;
+use crate::text::{FontFamily, FontId};
+use crate::ui::Ui;
+use crate::widget::{OverlaidLayer, Rectangle};
+use crate::widget::primitive::Widget;
+use crate::widget::types::image_information::ImageInformation;
+use crate::window::TWindow;
+
+/// A `Ui` with fixed pixel dimensions and scale factor that is driven by synthetic input.
+pub struct HeadlessUi<GS> where GS: GlobalState {
+    ui: Ui<GS>,
+    global_state: GS,
+    image_map: ImageMap<ImageInformation>,
+}
+
+impl<GS: GlobalState> HeadlessUi<GS> {
+    pub fn new(pixel_dimensions: Dimensions, scale_factor: f64, global_state: GS) -> Self {
+        HeadlessUi {
+            ui: Ui::new(pixel_dimensions, scale_factor),
+            global_state,
+            image_map: ImageMap::new(),
+        }
+    }
+
+    /// Pass a single input to the event handler. The resulting events are not delivered to the
+    /// widgets before `update` is called.
+    pub fn handle_input(&mut self, input: Input) {
+        self.ui.handle_event(input, &mut self.global_state);
+    }
+
+    pub fn handle_inputs<I: IntoIterator<Item=Input>>(&mut self, inputs: I) {
+        for input in inputs {
+            self.handle_input(input);
+        }
+    }
+
+    /// Deliver all the pending events to the widgets.
+    pub fn update(&mut self) {
+        self.ui.delegate_events(&mut self.global_state);
+    }
+
+    /// Layout the widgets and return the primitives that would be drawn to the window.
+    pub fn draw(&mut self) -> CPrimitives {
+        self.ui.draw(&self.global_state)
+    }
+
+    /// Deliver the pending events and draw the resulting frame.
+    pub fn frame(&mut self) -> CPrimitives {
+        self.update();
+        self.draw()
+    }
+
+    /// Move the mouse to the point, given in carbide coordinates with (0, 0) in the top left.
+    pub fn move_mouse_to(&mut self, point: Point) {
+        let dimensions = self.ui.environment.get_corrected_dimensions();
+
+        // The inverse of the transformation done by the event handler.
+        let x = point[0] - dimensions[0] / 2.0;
+        let y = dimensions[1] / 2.0 - point[1];
+
+        self.handle_input(Input::Motion(Motion::MouseCursor { x, y }));
+    }
+
+    /// Move the mouse to the point and press and release the left mouse button.
+    pub fn click_at(&mut self, point: Point) {
+        self.move_mouse_to(point);
+        self.handle_input(Input::Press(Button::Mouse(MouseButton::Left)));
+        self.handle_input(Input::Release(Button::Mouse(MouseButton::Left)));
+        self.update();
+    }
+
+    pub fn type_text(&mut self, text: &str) {
+        self.handle_input(Input::Text(text.to_string()));
+        self.update();
+    }
+
+    /// Press and release the key while holding down the keys for the given modifiers.
+    pub fn press_key(&mut self, key: Key, modifier: ModifierKey) {
+        let modifier_keys = Self::modifier_keys(modifier);
+
+        for modifier_key in &modifier_keys {
+            self.handle_input(Input::Press(Button::Keyboard(*modifier_key)));
+        }
+
+        self.handle_input(Input::Press(Button::Keyboard(key)));
+        self.handle_input(Input::Release(Button::Keyboard(key)));
+
+        for modifier_key in modifier_keys.iter().rev() {
+            self.handle_input(Input::Release(Button::Keyboard(*modifier_key)));
+        }
+
+        self.update();
+    }
+
+    fn modifier_keys(modifier: ModifierKey) -> Vec<Key> {
+        let mut keys = vec![];
+
+        if modifier.contains(ModifierKey::CTRL) {
+            keys.push(Key::LCtrl);
+        }
+        if modifier.contains(ModifierKey::SHIFT) {
+            keys.push(Key::LShift);
+        }
+        if modifier.contains(ModifierKey::ALT) {
+            keys.push(Key::LAlt);
+        }
+        if modifier.contains(ModifierKey::GUI) {
+            keys.push(Key::LGui);
+        }
+
+        keys
+    }
+
+    pub fn widgets(&self) -> &Box<dyn Widget<GS>> {
+        &self.ui.widgets
+    }
+
+    /// The widgets are laid out again before the next draw, since they might be replaced.
+    pub fn widgets_mut(&mut self) -> &mut Box<dyn Widget<GS>> {
+        self.ui.environment.invalidate_layout();
+        &mut self.ui.widgets
+    }
+
+    pub fn environment(&self) -> &Environment<GS> {
+        &self.ui.environment
+    }
+
+    pub fn environment_mut(&mut self) -> &mut Environment<GS> {
+        &mut self.ui.environment
+    }
+
+    pub fn global_state(&self) -> &GS {
+        &self.global_state
+    }
+
+    pub fn global_state_mut(&mut self) -> &mut GS {
+        &mut self.global_state
+    }
+
+    pub fn image_map(&self) -> &ImageMap<ImageInformation> {
+        &self.image_map
+    }
+}
+
+impl<GS: GlobalState> TWindow<GS> for HeadlessUi<GS> {
+    /// Fonts in the family are looked up in the nearest assets folder, like for the windowed
+    /// backends.
+    fn add_font_family(&mut self, family: FontFamily) -> String {
+        let family_name = family.name.clone();
+        self.ui.environment.add_font_family(family);
+        family_name
+    }
+
+    /// Load a font from the given path. Unlike the windowed backends the path is not resolved
+    /// relative to the assets folder.
+    fn add_font<P: AsRef<Path>>(&mut self, path: P) -> FontId {
+        self.ui.environment.insert_font_from_file(path)
+    }
+
+    /// Only the dimensions of the image are loaded, since nothing is uploaded to a GPU.
+    fn add_image(&mut self, path: &str) -> Id {
+        let (width, height) = image::image_dimensions(path)
+            .expect("Couldn't load image dimensions");
+
+        let id = self.image_map.insert(ImageInformation { width, height });
+
+        self.ui.environment.insert_image(id, ImageInformation { width, height });
+
+        id
+    }
+
+    fn set_widgets(&mut self, w: Box<dyn Widget<GS>>) {
+        self.ui.widgets = Rectangle::initialize(vec![
+            OverlaidLayer::new(
+                "controls_popup_layer",
+                w,
+            )
+        ])
+            .fill(EnvironmentColor::SystemBackground);
+    }
+}
+
+#[test]
+fn click_at_reaches_the_widget_under_the_mouse() {
+    use crate::gesture::TapGesture;
+    use crate::widget::{HStack, WidgetExt};
+
+    let mut ui = HeadlessUi::new([200.0, 100.0], 1.0, 0u32);
+
+    let root: Box<dyn Widget<u32>> = HStack::initialize(vec![
+        Rectangle::initialize(vec![]).frame(50.0, 50.0)
+            .gesture(TapGesture::new().on_end(|_: &Point, _: &mut Environment<u32>, taps: &mut u32| *taps += 1)),
+        Rectangle::initialize(vec![]).frame(50.0, 50.0),
+    ]);
+    *ui.widgets_mut() = root;
+    ui.draw();
+
+    // The stack is 110 wide and centered, so the first rectangle spans 45 to 95.
+    ui.click_at([70.0, 50.0]);
+    assert_eq!(*ui.global_state(), 1);
+
+    ui.click_at([130.0, 50.0]);
+    assert_eq!(*ui.global_state(), 1);
+}
+
+#[test]
+fn press_key_triggers_keyboard_shortcuts() {
+    use crate::environment::command_registry::Command;
+    use crate::widget::WidgetExt;
+
+    let mut ui = HeadlessUi::new([200.0, 100.0], 1.0, 0u32);
+
+    let command = Command::new("increment", "Increment")
+        .binding(Key::I, ModifierKey::CTRL)
+        .on_execute(|_: &mut Environment<u32>, count: &mut u32| *count += 1);
+
+    let root: Box<dyn Widget<u32>> = Rectangle::initialize(vec![]).keyboard_shortcut(command);
+    *ui.widgets_mut() = root;
+    ui.draw();
+
+    ui.press_key(Key::I, ModifierKey::CTRL);
+    assert_eq!(*ui.global_state(), 1);
+
+    ui.press_key(Key::I, ModifierKey::NO_MODIFIER);
+    assert_eq!(*ui.global_state(), 1);
+}
+
+#[test]
+fn commands_of_removed_keyboard_shortcuts_are_forgotten() {
+    use crate::environment::command_registry::Command;
+    use crate::widget::WidgetExt;
+
+    let mut ui = HeadlessUi::new([200.0, 100.0], 1.0, 0i32);
+
+    let increment = Command::new("increment", "Increment")
+        .binding(Key::I, ModifierKey::CTRL)
+        .on_execute(|_: &mut Environment<i32>, count: &mut i32| *count += 1);
+
+    let root: Box<dyn Widget<i32>> = Rectangle::initialize(vec![]).keyboard_shortcut(increment);
+    *ui.widgets_mut() = root;
+    ui.draw();
+
+    ui.press_key(Key::I, ModifierKey::CTRL);
+    assert_eq!(*ui.global_state(), 1);
+
+    // Once the shortcut is removed, its command no longer shadows the commands registered later.
+    let root: Box<dyn Widget<i32>> = Rectangle::initialize(vec![]);
+    *ui.widgets_mut() = root;
+    ui.environment_mut().commands_mut().register(Command::new("decrement", "Decrement")
+        .binding(Key::I, ModifierKey::CTRL)
+        .on_execute(|_: &mut Environment<i32>, count: &mut i32| *count -= 1));
+    ui.draw();
+
+    assert!(ui.environment().commands().command("increment").is_none());
+    ui.press_key(Key::I, ModifierKey::CTRL);
+    assert_eq!(*ui.global_state(), 0);
+}
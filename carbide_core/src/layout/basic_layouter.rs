@@ -1,7 +1,9 @@
 use crate::{Point, Scalar};
+use crate::layout::LayoutDirection;
 use crate::layout::layouter::Layouter;
 use crate::position::Dimensions;
-use crate::prelude::Widget;
+use crate::prelude::{Environment, Widget};
+use crate::state::global_state::GlobalState;
 
 #[derive(Clone, Debug)]
 pub enum BasicLayouter {
@@ -17,6 +19,26 @@ pub enum BasicLayouter {
 }
 
 impl BasicLayouter {
+    /// The layouter placing children at the same sides when laid out in the given direction.
+    /// In a right to left layout leading and trailing are swapped.
+    fn for_direction(&self, direction: LayoutDirection) -> BasicLayouter {
+        if direction == LayoutDirection::LeftToRight {
+            return self.clone()
+        }
+
+        match self {
+            BasicLayouter::TopLeading => BasicLayouter::TopTrailing,
+            BasicLayouter::TopTrailing => BasicLayouter::TopLeading,
+            BasicLayouter::Leading => BasicLayouter::Trailing,
+            BasicLayouter::Trailing => BasicLayouter::Leading,
+            BasicLayouter::BottomLeading => BasicLayouter::BottomTrailing,
+            BasicLayouter::BottomTrailing => BasicLayouter::BottomLeading,
+            BasicLayouter::Top => BasicLayouter::Top,
+            BasicLayouter::Center => BasicLayouter::Center,
+            BasicLayouter::Bottom => BasicLayouter::Bottom,
+        }
+    }
+
     fn leading_x<S>(x: Scalar, _: Scalar, child: &mut dyn Widget<S>) {
         child.set_x(x);
     }
@@ -87,9 +109,9 @@ impl BasicLayouter {
     }
 }
 
-impl<S> Layouter<S> for BasicLayouter {
-    fn position(&self) -> fn(Point, Dimensions, &mut dyn Widget<S>) {
-        match self {
+impl<S: GlobalState> Layouter<S> for BasicLayouter {
+    fn position(&self, env: &Environment<S>) -> fn(Point, Dimensions, &mut dyn Widget<S>) {
+        match self.for_direction(env.get_layout_direction()) {
             BasicLayouter::TopLeading => BasicLayouter::top_leading,
             BasicLayouter::Top => BasicLayouter::top,
             BasicLayouter::TopTrailing => BasicLayouter::top_trailing,
@@ -9,7 +9,7 @@ pub trait Layout<GS> where GS: GlobalState {
     /// 0 is the most flexible and the largest number is the least flexible
     fn flexibility(&self) -> u32;
     fn calculate_size(&mut self, requested_size: Dimensions, env: &mut Environment<GS>) -> Dimensions;
-    fn position_children(&mut self);
+    fn position_children(&mut self, env: &mut Environment<GS>);
 }
 
 pub trait SingleChildLayout {
@@ -24,7 +24,7 @@ impl<T, GS: GlobalState> Layout<GS> for T where T: SingleChildLayout + CommonWid
     fn calculate_size(&mut self, requested_size: Dimensions, env: &mut Environment<GS>) -> Dimensions {
         let mut dimentions = [0.0, 0.0];
         if let Some(child) = self.get_children_mut().next() {
-            dimentions = child.calculate_size(requested_size, env);
+            dimentions = child.calculate_size_cached(requested_size, env);
         }
 
         self.set_dimension(dimentions);
@@ -32,14 +32,14 @@ impl<T, GS: GlobalState> Layout<GS> for T where T: SingleChildLayout + CommonWid
         self.get_dimension()
     }
 
-    fn position_children(&mut self) {
-        let positioning = BasicLayouter::Center.position();
+    fn position_children(&mut self, env: &mut Environment<GS>) {
+        let positioning = BasicLayouter::Center.position(env);
         let position = self.get_position();
         let dimension = self.get_dimension();
 
         if let Some(child) = self.get_children_mut().next() {
             positioning(position, dimension, child);
-            child.position_children();
+            child.position_children(env);
         }
     }
 }
\ No newline at end of file
@@ -1,7 +1,11 @@
 use crate::Point;
 use crate::position::Dimensions;
+use crate::prelude::Environment;
+use crate::state::global_state::GlobalState;
 use crate::widget::Widget;
 
-pub trait Layouter<S> {
-    fn position(&self) -> fn(Point, Dimensions, &mut dyn Widget<S>);
+pub trait Layouter<S: GlobalState> {
+    /// The function positioning a child within the given bounds, in the layout direction of the
+    /// environment.
+    fn position(&self, env: &Environment<S>) -> fn(Point, Dimensions, &mut dyn Widget<S>);
 }
\ No newline at end of file
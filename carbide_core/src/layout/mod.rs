@@ -1,10 +1,32 @@
 pub mod basic_layouter;
 pub mod layout;
 pub mod layouter;
+pub mod viewport;
+
+use serde::{Deserialize, Serialize};
 
 pub use self::layout::Layout;
 
 #[derive(Debug, Clone)]
 pub enum CrossAxisAlignment {
     Start, Center, End
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MainAxisAlignment {
+    Start, Center, End, SpaceBetween, SpaceAround, SpaceEvenly
+}
+
+/// The direction in which content flows horizontally. In a right to left layout, leading is the
+/// right edge and trailing is the left edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LayoutDirection {
+    LeftToRight,
+    RightToLeft,
+}
+
+impl Default for LayoutDirection {
+    fn default() -> Self {
+        LayoutDirection::LeftToRight
+    }
+}
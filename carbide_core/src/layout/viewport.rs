@@ -0,0 +1,17 @@
+use crate::Point;
+use crate::position::Dimensions;
+
+/// The visible part of the content of a scrolling widget. Scrolling widgets push their
+/// viewport to the environment while their content is sized, such that lazy widgets can
+/// limit the children they lay out to the ones that are visible.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    /// The position of the visible area relative to the content, for the scroll offset used in
+    /// the current layout.
+    pub offset: Point,
+    /// The size of the visible area.
+    pub dimension: Dimensions,
+    /// The position of the content in the previous layout. Together with the position of a
+    /// descendant in the same layout, it gives the offset of the descendant within the content.
+    pub content_position: Point,
+}
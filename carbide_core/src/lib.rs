@@ -46,6 +46,7 @@ pub use crate::ui::Ui;
 pub mod color;
 pub mod event;
 pub mod guide;
+pub mod headless;
 pub mod image_map;
 pub mod input;
 pub mod mesh;
@@ -64,6 +65,7 @@ pub mod draw;
 pub mod window;
 pub mod prelude;
 pub mod focus;
+pub mod gesture;
 mod environment;
 pub mod text;
 
@@ -12,6 +12,7 @@ pub use crate::environment::environment_variable::EnvironmentVariable;
 pub use crate::flags::Flags;
 pub use crate::layout::basic_layouter::BasicLayouter;
 pub use crate::layout::Layout;
+pub use crate::layout::LayoutDirection;
 pub use crate::layout::layouter::Layouter;
 pub use crate::position::Dimensions;
 pub use crate::position::Scalar;
@@ -13,14 +13,21 @@ pub struct CPrimitives {
 
 impl CPrimitives {
     pub fn new<S: GlobalState>(window_dimensions: Dimensions, root: &mut Box<dyn Widget<S>>, environment: &mut Environment<S>, global_state: &S) -> Self {
-        let now = Instant::now();
-        root.calculate_size(window_dimensions, environment);
+        // The layout from the previous draw is reused until it is invalidated, and then only the
+        // invalidated widgets are sized again.
+        if environment.is_layout_invalidated() {
+            let now = Instant::now();
+            environment.begin_layout(&**root);
+            root.calculate_size_cached(window_dimensions, environment);
 
-        root.set_x(window_dimensions[0] / 2.0 - root.get_width() / 2.0);
-        root.set_y(window_dimensions[1] / 2.0 - root.get_height() / 2.0);
+            root.set_x(window_dimensions[0] / 2.0 - root.get_width() / 2.0);
+            root.set_y(window_dimensions[1] / 2.0 - root.get_height() / 2.0);
+
+            root.position_children(environment);
+            environment.validate_layout();
+            println!("Time for pos and size: {:?}us", now.elapsed().as_micros());
+        }
 
-        root.position_children();
-        println!("Time for pos and size: {:?}us", now.elapsed().as_micros());
         let prims: Vec<Primitive> = root.get_primitives(environment, global_state);
         CPrimitives {
             primitives: prims
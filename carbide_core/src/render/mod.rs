@@ -11,3 +11,4 @@ pub mod primitives;
 pub mod primitive_kind;
 pub mod primitive;
 pub mod positions_strategy;
+pub mod snapshot;
@@ -0,0 +1,336 @@
+//! A stable, serializable form of the primitives produced by `Ui::draw`, used for snapshot tests.
+//!
+//! Scalars are rounded to two decimals and colors are stored as bytes, such that small floating
+//! point differences between platforms do not cause snapshots to change.
+
+use std::fmt::Write;
+use std::path::Path;
+
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+use crate::{Color, OldRect, Scalar};
+use crate::color::Rgba;
+use crate::render::cprimitives::CPrimitives;
+use crate::render::primitive::Primitive;
+use crate::render::primitive_kind::PrimitiveKind;
+use crate::text::Glyph;
+
+/// Set this environment variable to regenerate the snapshot files instead of comparing against them.
+pub const UPDATE_SNAPSHOTS_VAR: &str = "CARBIDE_UPDATE_SNAPSHOTS";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PrimitiveSnapshot {
+    pub kind: PrimitiveKindSnapshot,
+    /// The bounding rectangle as [x, y, width, height].
+    pub rect: [Scalar; 4],
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum PrimitiveKindSnapshot {
+    Clip,
+    UnClip,
+    Rectangle {
+        color: [u8; 4],
+    },
+    TrianglesSingleColor {
+        color: [u8; 4],
+        triangles: Vec<[[Scalar; 2]; 3]>,
+    },
+    TrianglesMultiColor {
+        triangles: Vec<[([Scalar; 2], [u8; 4]); 3]>,
+    },
+    Image {
+        image_id: u32,
+        color: Option<[u8; 4]>,
+        source_rect: Option<[Scalar; 4]>,
+    },
+    Text {
+        color: [u8; 4],
+        glyphs: Vec<GlyphSnapshot>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GlyphSnapshot {
+    pub id: u16,
+    pub font_id: usize,
+    pub font_size: u32,
+    pub position: [Scalar; 2],
+}
+
+impl From<&Primitive> for PrimitiveSnapshot {
+    fn from(primitive: &Primitive) -> Self {
+        let kind = match &primitive.kind {
+            PrimitiveKind::Clip => PrimitiveKindSnapshot::Clip,
+            PrimitiveKind::UnClip => PrimitiveKindSnapshot::UnClip,
+            PrimitiveKind::Rectangle { color } => PrimitiveKindSnapshot::Rectangle {
+                color: color.to_byte_fsa()
+            },
+            PrimitiveKind::TrianglesSingleColor { color, triangles } => PrimitiveKindSnapshot::TrianglesSingleColor {
+                color: rgba_to_bytes(*color),
+                triangles: triangles.iter().map(|triangle| {
+                    [point(triangle[0]), point(triangle[1]), point(triangle[2])]
+                }).collect(),
+            },
+            PrimitiveKind::TrianglesMultiColor { triangles } => PrimitiveKindSnapshot::TrianglesMultiColor {
+                triangles: triangles.iter().map(|triangle| {
+                    let vertex = |(p, color): (crate::Point, Rgba)| (point(p), rgba_to_bytes(color));
+                    [vertex(triangle[0]), vertex(triangle[1]), vertex(triangle[2])]
+                }).collect(),
+            },
+            PrimitiveKind::Image { image_id, color, source_rect } => PrimitiveKindSnapshot::Image {
+                image_id: image_id.0,
+                color: color.map(|color| color.to_byte_fsa()),
+                source_rect: source_rect.map(rect),
+            },
+            PrimitiveKind::Text { color, text } => PrimitiveKindSnapshot::Text {
+                color: color.to_byte_fsa(),
+                glyphs: text.iter().map(GlyphSnapshot::from).collect(),
+            },
+        };
+
+        PrimitiveSnapshot {
+            kind,
+            rect: rect(primitive.rect),
+        }
+    }
+}
+
+impl From<&Glyph> for GlyphSnapshot {
+    fn from(glyph: &Glyph) -> Self {
+        let position = glyph.position();
+
+        GlyphSnapshot {
+            id: glyph.id().0,
+            font_id: glyph.font_id(),
+            font_size: glyph.font_size(),
+            position: [round(position.x), round(position.y)],
+        }
+    }
+}
+
+impl CPrimitives {
+    pub fn snapshot(&self) -> Vec<PrimitiveSnapshot> {
+        self.primitives.iter().map(PrimitiveSnapshot::from).collect()
+    }
+}
+
+/// Compare the primitives against the snapshot stored as RON at the given path. If the
+/// `CARBIDE_UPDATE_SNAPSHOTS` environment variable is set, the snapshot is written instead.
+///
+/// # Panics
+/// If the snapshot is missing, or if it does not match the primitives. A line diff between the
+/// stored and the actual snapshot is included in the panic message.
+pub fn assert_snapshot<P: AsRef<Path>>(primitives: &CPrimitives, path: P) {
+    let path = path.as_ref();
+    let actual = primitives.snapshot();
+    let actual_string = to_pretty_ron(&actual);
+
+    if std::env::var_os(UPDATE_SNAPSHOTS_VAR).is_some() {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).expect("Could not create the snapshot directory");
+        }
+        std::fs::write(path, &actual_string).expect("Could not write the snapshot");
+        return;
+    }
+
+    let expected_string = match std::fs::read_to_string(path) {
+        Ok(expected_string) => expected_string,
+        Err(_) => panic!(
+            "The snapshot {:?} does not exist. Run the test with {}=1 to create it.",
+            path,
+            UPDATE_SNAPSHOTS_VAR
+        ),
+    };
+
+    let expected: Vec<PrimitiveSnapshot> = crate::from_ron(&expected_string)
+        .expect("Could not parse the snapshot");
+
+    if expected != actual {
+        panic!(
+            "The primitives did not match the snapshot {:?}. Run the test with {}=1 to update it.\n{}",
+            path,
+            UPDATE_SNAPSHOTS_VAR,
+            line_diff(&to_pretty_ron(&expected), &actual_string)
+        );
+    }
+}
+
+fn to_pretty_ron(snapshot: &Vec<PrimitiveSnapshot>) -> String {
+    ron::ser::to_string_pretty(snapshot, PrettyConfig::new()).expect("Could not serialize the snapshot")
+}
+
+/// A minimal line diff based on the longest common subsequence. Removed lines are prefixed with
+/// "-" and added lines with "+".
+fn line_diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+
+    let n = expected.len();
+    let m = actual.len();
+
+    // lcs[i][j] is the length of the longest common subsequence of expected[i..] and actual[j..]
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if expected[i] == actual[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut res = String::new();
+    let mut i = 0;
+    let mut j = 0;
+
+    while i < n || j < m {
+        if i < n && j < m && expected[i] == actual[j] {
+            writeln!(res, "  {}", expected[i]).unwrap();
+            i += 1;
+            j += 1;
+        } else if j < m && (i == n || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            writeln!(res, "+ {}", actual[j]).unwrap();
+            j += 1;
+        } else {
+            writeln!(res, "- {}", expected[i]).unwrap();
+            i += 1;
+        }
+    }
+
+    res
+}
+
+fn round(value: Scalar) -> Scalar {
+    // Adding zero turns negative zero into positive zero.
+    (value * 100.0).round() / 100.0 + 0.0
+}
+
+fn point(p: crate::Point) -> [Scalar; 2] {
+    [round(p[0]), round(p[1])]
+}
+
+fn rect(rect: OldRect) -> [Scalar; 4] {
+    [round(rect.x.start), round(rect.y.start), round(rect.w()), round(rect.h())]
+}
+
+fn rgba_to_bytes(rgba: Rgba) -> [u8; 4] {
+    Color::from(rgba).to_byte_fsa()
+}
+
+#[test]
+fn line_diff_marks_changed_lines() {
+    let diff = line_diff("a\nb\nc", "a\nd\nc");
+
+    assert_eq!(diff, "  a\n+ d\n- b\n  c\n");
+}
+
+#[test]
+fn round_removes_negative_zero() {
+    assert_eq!(round(-0.001).to_string(), "0");
+}
+
+#[test]
+fn h_stack_snapshot() {
+    use crate::headless::HeadlessUi;
+    use crate::widget::{HStack, Rectangle, Widget, WidgetExt};
+
+    let mut ui = HeadlessUi::new([200.0, 100.0], 1.0, ());
+
+    let root: Box<dyn Widget<()>> = HStack::initialize(vec![
+        Rectangle::initialize(vec![]).fill(Color::Rgba(1.0, 0.0, 0.0, 1.0)).frame(50.0, 50.0),
+        Rectangle::initialize(vec![]).fill(Color::Rgba(0.0, 0.0, 1.0, 1.0)).frame(30.0, 20.0),
+    ]);
+    *ui.widgets_mut() = root;
+
+    assert_snapshot(&ui.draw(), concat!(env!("CARGO_MANIFEST_DIR"), "/snapshots/h_stack.ron"));
+}
+
+#[test]
+fn v_stack_snapshot() {
+    use crate::headless::HeadlessUi;
+    use crate::widget::{Rectangle, VStack, Widget, WidgetExt};
+
+    let mut ui = HeadlessUi::new([200.0, 100.0], 1.0, ());
+
+    let root: Box<dyn Widget<()>> = VStack::initialize(vec![
+        Rectangle::initialize(vec![]).fill(Color::Rgba(1.0, 0.0, 0.0, 1.0)).frame(50.0, 20.0),
+        Rectangle::initialize(vec![]).fill(Color::Rgba(0.0, 0.0, 1.0, 1.0)).frame(30.0, 30.0),
+    ]);
+    *ui.widgets_mut() = root;
+
+    assert_snapshot(&ui.draw(), concat!(env!("CARGO_MANIFEST_DIR"), "/snapshots/v_stack.ron"));
+}
+
+#[test]
+fn padding_snapshot() {
+    use crate::headless::HeadlessUi;
+    use crate::widget::{EdgeInsets, Rectangle, Widget, WidgetExt};
+
+    let mut ui = HeadlessUi::new([200.0, 100.0], 1.0, ());
+
+    let root: Box<dyn Widget<()>> = Rectangle::initialize(vec![])
+        .fill(Color::Rgba(1.0, 0.0, 0.0, 1.0))
+        .frame(50.0, 30.0)
+        .padding(EdgeInsets::single(5.0, 15.0, 20.0, 0.0));
+    *ui.widgets_mut() = root;
+
+    assert_snapshot(&ui.draw(), concat!(env!("CARGO_MANIFEST_DIR"), "/snapshots/padding.ron"));
+}
+
+#[test]
+fn frame_snapshot() {
+    use crate::headless::HeadlessUi;
+    use crate::widget::{Rectangle, Widget, WidgetExt};
+
+    let mut ui = HeadlessUi::new([200.0, 100.0], 1.0, ());
+
+    let root: Box<dyn Widget<()>> = Rectangle::initialize(vec![
+        Rectangle::initialize(vec![]).fill(Color::Rgba(1.0, 0.0, 0.0, 1.0)).frame(40.0, 20.0),
+    ])
+        .fill(Color::Rgba(0.0, 0.0, 1.0, 1.0))
+        .frame(100.0, 60.0);
+    *ui.widgets_mut() = root;
+
+    assert_snapshot(&ui.draw(), concat!(env!("CARGO_MANIFEST_DIR"), "/snapshots/frame.ron"));
+}
+
+#[test]
+fn background_snapshot() {
+    use crate::headless::HeadlessUi;
+    use crate::widget::{EdgeInsets, Rectangle, Widget, WidgetExt};
+
+    let mut ui = HeadlessUi::new([200.0, 100.0], 1.0, ());
+
+    // The background takes the size of the padded child, and is drawn behind it.
+    let root: Box<dyn Widget<()>> = Rectangle::initialize(vec![])
+        .fill(Color::Rgba(1.0, 0.0, 0.0, 1.0))
+        .frame(60.0, 40.0)
+        .padding(EdgeInsets::all(10.0))
+        .background(Rectangle::initialize(vec![]).fill(Color::Rgba(0.0, 0.0, 1.0, 1.0)));
+    *ui.widgets_mut() = root;
+
+    assert_snapshot(&ui.draw(), concat!(env!("CARGO_MANIFEST_DIR"), "/snapshots/background.ron"));
+}
+
+#[test]
+fn overlay_snapshot() {
+    use crate::headless::HeadlessUi;
+    use crate::layout::basic_layouter::BasicLayouter;
+    use crate::widget::{EdgeInsets, Rectangle, Widget, WidgetExt};
+
+    let mut ui = HeadlessUi::new([200.0, 100.0], 1.0, ());
+
+    // The overlay takes the size of the padded child, and is drawn in front of it.
+    let root: Box<dyn Widget<()>> = Rectangle::initialize(vec![])
+        .fill(Color::Rgba(1.0, 0.0, 0.0, 1.0))
+        .frame(60.0, 40.0)
+        .padding(EdgeInsets::all(10.0))
+        .overlay(Rectangle::initialize(vec![]).fill(Color::Rgba(0.0, 0.0, 1.0, 1.0)), BasicLayouter::TopLeading);
+    *ui.widgets_mut() = root;
+
+    assert_snapshot(&ui.draw(), concat!(env!("CARGO_MANIFEST_DIR"), "/snapshots/overlay.ron"));
+}
@@ -0,0 +1,52 @@
+use std::hash::Hasher;
+use std::io;
+
+use fxhash::FxHasher;
+
+use crate::Serialize;
+
+/// A hash of the values of the states of a widget, used to notice when any of the values change
+/// without keeping a copy of them. The values are streamed into the hasher, so nothing is
+/// allocated.
+pub struct ChangeMarker(FxHasher);
+
+impl ChangeMarker {
+    pub fn new() -> Self {
+        ChangeMarker(FxHasher::default())
+    }
+
+    pub fn add<T: Serialize + ?Sized>(&mut self, value: &T) {
+        bincode::serialize_into(HashWriter(&mut self.0), value).unwrap();
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.0.finish()
+    }
+}
+
+struct HashWriter<'a>(&'a mut FxHasher);
+
+impl<'a> io::Write for HashWriter<'a> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn markers_differ_when_a_value_changes() {
+    let marker = |first: f64, second: &str| {
+        let mut marker = ChangeMarker::new();
+        marker.add(&first);
+        marker.add(second);
+        marker.finish()
+    };
+
+    assert_eq!(marker(1.0, "a"), marker(1.0, "a"));
+    assert_ne!(marker(1.0, "a"), marker(2.0, "a"));
+    assert_ne!(marker(1.0, "a"), marker(1.0, "b"));
+}
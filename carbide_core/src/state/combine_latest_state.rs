@@ -0,0 +1,164 @@
+use crate::prelude::Environment;
+use crate::prelude::GlobalState;
+use crate::state::{StateContract, TState};
+use crate::state::state::State;
+use crate::state::state_key::StateKey;
+use crate::state::widget_state::WidgetState;
+
+/// A state combining the latest values of a list of states of the same type into a vec. Changes
+/// to the elements of the vec are written back to the corresponding states. Elements added or
+/// removed from the vec are ignored.
+#[derive(Clone)]
+pub struct CombineLatestState<T, GS> where T: StateContract, GS: GlobalState {
+    states: Vec<TState<T, GS>>,
+    latest_value: Vec<T>,
+    /// True if a mutable reference to the latest value has been handed out, and the value has
+    /// not yet been written back into the combined states.
+    changed: bool,
+}
+
+impl<T: StateContract, GS: GlobalState> CombineLatestState<T, GS> {
+    pub fn new<I: Into<TState<T, GS>>>(states: Vec<I>) -> Box<CombineLatestState<T, GS>> {
+        let states: Vec<TState<T, GS>> = states.into_iter().map(|state| state.into()).collect();
+
+        let latest_value = states.iter().map(|state| state.get_latest_value().clone()).collect();
+
+        Box::new(CombineLatestState {
+            states,
+            latest_value,
+            changed: false,
+        })
+    }
+
+    fn write_back(&mut self, env: &mut Environment<GS>, global_state: &mut GS) {
+        if self.changed {
+            for (state, value) in self.states.iter_mut().zip(self.latest_value.iter()) {
+                *state.get_value_mut(env, global_state) = value.clone();
+            }
+            self.changed = false;
+        }
+    }
+}
+
+impl<T: StateContract, GS: GlobalState> State<Vec<T>, GS> for CombineLatestState<T, GS> {
+    fn get_value_mut<'a>(&'a mut self, env: &'a mut Environment<GS>, global_state: &'a mut GS) -> &'a mut Vec<T> {
+        self.write_back(env, global_state);
+
+        self.latest_value = self.states.iter_mut()
+            .map(|state| state.get_value_mut(env, global_state).clone())
+            .collect();
+
+        // We can not know what is done with the value, so we assume it has been changed.
+        self.changed = true;
+
+        &mut self.latest_value
+    }
+
+    fn get_value(&mut self, env: &Environment<GS>, global_state: &GS) -> &Vec<T> {
+        if self.changed {
+            // The value is written all the way back when the state is released.
+            for (state, value) in self.states.iter_mut().zip(self.latest_value.iter()) {
+                *state.get_latest_value_mut() = value.clone();
+            }
+            return &self.latest_value;
+        }
+
+        self.latest_value = self.states.iter_mut()
+            .map(|state| state.get_value(env, global_state).clone())
+            .collect();
+
+        &self.latest_value
+    }
+
+    fn get_latest_value(&self) -> &Vec<T> {
+        &self.latest_value
+    }
+
+    fn get_latest_value_mut(&mut self) -> &mut Vec<T> {
+        self.changed = true;
+        &mut self.latest_value
+    }
+
+    fn get_key(&self) -> Option<&StateKey> {
+        None
+    }
+
+    fn update_dependent_states(&mut self, env: &Environment<GS>) {
+        for state in self.states.iter_mut() {
+            env.update_local_state(state);
+        }
+
+        if !self.changed {
+            self.latest_value = self.states.iter().map(|state| state.get_latest_value().clone()).collect();
+        }
+    }
+
+    fn insert_dependent_states(&self, env: &mut Environment<GS>) {
+        if self.changed {
+            for (state, value) in self.states.iter().zip(self.latest_value.iter()) {
+                if let Some(key) = state.get_key() {
+                    env.insert_local_state_from_key_value(key, value);
+                }
+            }
+        } else {
+            for state in &self.states {
+                state.insert_dependent_states(env);
+            }
+        }
+    }
+
+    fn release_state(&mut self, env: &mut Environment<GS>, global_state: &mut GS) {
+        self.write_back(env, global_state);
+
+        for state in self.states.iter_mut() {
+            state.release_state(env, global_state);
+        }
+    }
+}
+
+impl<T: StateContract + 'static, GS: GlobalState> Into<TState<Vec<T>, GS>> for Box<CombineLatestState<T, GS>> {
+    fn into(self) -> TState<Vec<T>, GS> {
+        WidgetState::new(self)
+    }
+}
+
+#[test]
+fn combined_value_follows_the_states() {
+    use crate::state::state::CommonState;
+
+    fn fst(global_state: &(u32, u32)) -> &u32 {
+        &global_state.0
+    }
+
+    fn fst_mut(global_state: &mut (u32, u32)) -> &mut u32 {
+        &mut global_state.0
+    }
+
+    fn snd(global_state: &(u32, u32)) -> &u32 {
+        &global_state.1
+    }
+
+    fn snd_mut(global_state: &mut (u32, u32)) -> &mut u32 {
+        &mut global_state.1
+    }
+
+    let mut env = Environment::<(u32, u32)>::new(vec![], [100.0, 100.0], 1.0);
+    let mut global_state = (1, 2);
+
+    let mut combined = CombineLatestState::new(vec![
+        CommonState::GlobalState { function: Box::new(fst), function_mut: Box::new(fst_mut), latest_value: 1 },
+        CommonState::GlobalState { function: Box::new(snd), function_mut: Box::new(snd_mut), latest_value: 2 },
+    ]);
+
+    assert_eq!(combined.get_value(&env, &global_state), &vec![1, 2]);
+
+    global_state.0 = 3;
+    assert_eq!(combined.get_value(&env, &global_state), &vec![3, 2]);
+
+    global_state.1 = 4;
+    assert_eq!(combined.get_value(&env, &global_state), &vec![3, 4]);
+
+    combined.get_value_mut(&mut env, &mut global_state)[0] = 5;
+    combined.release_state(&mut env, &mut global_state);
+    assert_eq!(global_state, (5, 4));
+}
@@ -0,0 +1,113 @@
+//! Clonable closures used by the states. Every closure that implements the corresponding `Fn`
+//! trait and `Clone` implements these traits, which means both function pointers and closures
+//! capturing clonable values can be used.
+
+use std::fmt;
+use std::fmt::Debug;
+
+use dyn_clone::DynClone;
+
+use crate::prelude::Environment;
+use crate::state::global_state::GlobalState;
+
+/// Map a value of one type to a value of another type.
+pub trait MapFn<FROM, TO>: Fn(&FROM) -> TO + DynClone {}
+
+impl<FROM, TO, F> MapFn<FROM, TO> for F where F: Fn(&FROM) -> TO + DynClone {}
+
+dyn_clone::clone_trait_object!(<FROM, TO> MapFn<FROM, TO>);
+
+/// Map a changed value back into the value it was mapped from. The first argument is the
+/// current value that was mapped from and the second is the changed mapped value.
+pub trait MapBackFn<FROM, TO>: Fn(FROM, &TO) -> FROM + DynClone {}
+
+impl<FROM, TO, F> MapBackFn<FROM, TO> for F where F: Fn(FROM, &TO) -> FROM + DynClone {}
+
+dyn_clone::clone_trait_object!(<FROM, TO> MapBackFn<FROM, TO>);
+
+/// Get a reference to a value within the global state.
+pub trait GlobalFn<T, GS>: Fn(&GS) -> &T + DynClone {}
+
+impl<T, GS, F> GlobalFn<T, GS> for F where F: Fn(&GS) -> &T + DynClone {}
+
+dyn_clone::clone_trait_object!(<T, GS> GlobalFn<T, GS>);
+
+/// Get a mutable reference to a value within the global state.
+pub trait GlobalFnMut<T, GS>: Fn(&mut GS) -> &mut T + DynClone {}
+
+impl<T, GS, F> GlobalFnMut<T, GS> for F where F: Fn(&mut GS) -> &mut T + DynClone {}
+
+dyn_clone::clone_trait_object!(<T, GS> GlobalFnMut<T, GS>);
+
+/// Calculate a value from the environment.
+pub trait EnvFn<T, GS: GlobalState>: Fn(&Environment<GS>) -> T + DynClone {}
+
+impl<T, GS: GlobalState, F> EnvFn<T, GS> for F where F: Fn(&Environment<GS>) -> T + DynClone {}
+
+dyn_clone::clone_trait_object!(<T, GS: GlobalState> EnvFn<T, GS>);
+
+/// Get a mutable reference to a value within the environment.
+pub trait EnvFnMut<T, GS: GlobalState>: Fn(&mut Environment<GS>) -> &mut T + DynClone {}
+
+impl<T, GS: GlobalState, F> EnvFnMut<T, GS> for F where F: Fn(&mut Environment<GS>) -> &mut T + DynClone {}
+
+dyn_clone::clone_trait_object!(<T, GS: GlobalState> EnvFnMut<T, GS>);
+
+/// Called with the old and the new value when an observed state changes.
+pub trait ChangeFn<T, GS: GlobalState>: Fn(&T, &T, &mut Environment<GS>, &mut GS) + DynClone {}
+
+impl<T, GS: GlobalState, F> ChangeFn<T, GS> for F where F: Fn(&T, &T, &mut Environment<GS>, &mut GS) + DynClone {}
+
+dyn_clone::clone_trait_object!(<T, GS: GlobalState> ChangeFn<T, GS>);
+
+impl<FROM, TO> Debug for dyn MapFn<FROM, TO> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MapFn")
+    }
+}
+
+impl<FROM, TO> Debug for dyn MapBackFn<FROM, TO> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MapBackFn")
+    }
+}
+
+impl<T, GS> Debug for dyn GlobalFn<T, GS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "GlobalFn")
+    }
+}
+
+impl<T, GS> Debug for dyn GlobalFnMut<T, GS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "GlobalFnMut")
+    }
+}
+
+impl<T, GS: GlobalState> Debug for dyn EnvFn<T, GS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "EnvFn")
+    }
+}
+
+impl<T, GS: GlobalState> Debug for dyn EnvFnMut<T, GS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "EnvFnMut")
+    }
+}
+
+impl<T, GS: GlobalState> Debug for dyn ChangeFn<T, GS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ChangeFn")
+    }
+}
+
+#[test]
+fn boxed_closures_keep_captured_values_when_cloned() {
+    let factor = 3;
+    let map: Box<dyn MapFn<i32, i32>> = Box::new(move |value: &i32| value * factor);
+    let cloned = map.clone();
+
+    assert_eq!(map(&2), 6);
+    assert_eq!(cloned(&4), 12);
+}
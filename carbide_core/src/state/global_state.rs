@@ -2,6 +2,7 @@ use std::fmt::{Debug, Formatter};
 
 use crate::prelude::Environment;
 use crate::state::{State, StateContract, TState};
+use crate::state::functions::{GlobalFn, GlobalFnMut};
 use crate::state::state_key::StateKey;
 use crate::state::widget_state::WidgetState;
 
@@ -15,19 +16,17 @@ impl<T> GlobalState for T where T: 'static + Clone + std::fmt::Debug {}
 
 #[derive(Clone)]
 pub struct GState<T, GS> where T: StateContract, GS: GlobalState {
-    function: fn(state: &GS) -> &T,
-    function_mut: fn(state: &mut GS) -> &mut T,
+    function: Box<dyn GlobalFn<T, GS>>,
+    function_mut: Box<dyn GlobalFnMut<T, GS>>,
     latest_value: T,
 }
 
 impl<T: StateContract, GS: GlobalState> GState<T, GS> {
-    pub fn new(
-        function: fn(state: &GS) -> &T,
-        function_mut: fn(state: &mut GS) -> &mut T,
-    ) -> Box<Self> {
+    pub fn new<F, FM>(function: F, function_mut: FM) -> Box<Self>
+        where F: Fn(&GS) -> &T + Clone + 'static, FM: Fn(&mut GS) -> &mut T + Clone + 'static {
         Box::new(GState {
-            function,
-            function_mut,
+            function: Box::new(function),
+            function_mut: Box::new(function_mut),
             latest_value: T::default(),
         })
     }
@@ -0,0 +1,132 @@
+use crate::prelude::Environment;
+use crate::prelude::GlobalState;
+use crate::state::{StateContract, TState};
+use crate::state::functions::MapFn;
+use crate::state::state::State;
+use crate::state::state_key::StateKey;
+use crate::state::widget_state::WidgetState;
+use crate::widget::primitive::foreach::ForEachDelegate;
+
+/// A state of the element in a vec with a given id. Unlike `VecState`, which refers to an element
+/// by its position, this state keeps referring to the same element when elements before it are
+/// inserted or removed. This makes it suitable for the states of the rows of a `ForEach`, which
+/// are kept by their id. If no element has the id, the latest value is used as a fallback and
+/// changes to it are not written to the vec.
+#[derive(Clone)]
+pub struct KeyedVecState<T, K, GS> where T: StateContract, K: ForEachDelegate, GS: GlobalState {
+    vec: TState<Vec<T>, GS>,
+    id_state: TState<K, GS>,
+    /// Get the id of an element.
+    id_of: Box<dyn MapFn<T, K>>,
+    /// The index the element was found at the last time. This is checked first when looking up
+    /// the element, which makes the lookup constant time as long as the vec does not change.
+    latest_index: usize,
+    latest_value: T,
+}
+
+impl<T: StateContract, K: ForEachDelegate, GS: GlobalState> KeyedVecState<T, K, GS> {
+    pub fn new<V, I, M>(vec: V, id_state: I, id_of: M) -> Box<KeyedVecState<T, K, GS>>
+        where V: Into<TState<Vec<T>, GS>>, I: Into<TState<K, GS>>, M: Fn(&T) -> K + Clone + 'static {
+        let vec = vec.into();
+        let id_state = id_state.into();
+
+        let latest_value = vec.get_latest_value().iter()
+            .find(|element| &id_of(element) == id_state.get_latest_value())
+            .cloned()
+            .unwrap_or_default();
+
+        Box::new(KeyedVecState {
+            vec,
+            id_state,
+            id_of: Box::new(id_of),
+            latest_index: 0,
+            latest_value,
+        })
+    }
+}
+
+impl<T: StateContract, K: ForEachDelegate, GS: GlobalState> State<T, GS> for KeyedVecState<T, K, GS> {
+    fn get_value_mut<'a>(&'a mut self, env: &'a mut Environment<GS>, global_state: &'a mut GS) -> &'a mut T {
+        let id = self.id_state.get_value(env, global_state).clone();
+        let index = find_index(self.vec.get_value(env, global_state), &id, self.latest_index, &self.id_of);
+
+        match index {
+            Some(index) => {
+                self.latest_index = index;
+                let value = &mut self.vec.get_value_mut(env, global_state)[index];
+                self.latest_value = value.clone();
+                value
+            }
+            None => &mut self.latest_value,
+        }
+    }
+
+    fn get_value(&mut self, env: &Environment<GS>, global_state: &GS) -> &T {
+        let id = self.id_state.get_value(env, global_state).clone();
+        let index = find_index(self.vec.get_value(env, global_state), &id, self.latest_index, &self.id_of);
+
+        if let Some(index) = index {
+            self.latest_index = index;
+            self.latest_value = self.vec.get_latest_value()[index].clone();
+        }
+
+        &self.latest_value
+    }
+
+    fn get_latest_value(&self) -> &T {
+        &self.latest_value
+    }
+
+    fn get_latest_value_mut(&mut self) -> &mut T {
+        &mut self.latest_value
+    }
+
+    fn get_key(&self) -> Option<&StateKey> {
+        None
+    }
+
+    fn update_dependent_states(&mut self, env: &Environment<GS>) {
+        env.update_local_state(&mut self.vec);
+        env.update_local_state(&mut self.id_state);
+    }
+
+    fn insert_dependent_states(&self, env: &mut Environment<GS>) {
+        env.insert_local_state(&self.vec)
+    }
+
+    fn release_state(&mut self, env: &mut Environment<GS>, global_state: &mut GS) {
+        self.vec.release_state(env, global_state);
+        self.id_state.release_state(env, global_state);
+    }
+}
+
+fn find_index<T, K: PartialEq>(vec: &Vec<T>, id: &K, latest_index: usize, id_of: &Box<dyn MapFn<T, K>>) -> Option<usize> {
+    match vec.get(latest_index) {
+        Some(element) if &id_of(element) == id => Some(latest_index),
+        _ => vec.iter().position(|element| &id_of(element) == id),
+    }
+}
+
+impl<T: StateContract + 'static, K: ForEachDelegate + 'static, GS: GlobalState> Into<TState<T, GS>> for Box<KeyedVecState<T, K, GS>> {
+    fn into(self) -> TState<T, GS> {
+        WidgetState::new(self)
+    }
+}
+
+#[test]
+fn element_is_found_after_removing_an_element_before_it() {
+    use crate::state::state::CommonState;
+
+    let env = Environment::<()>::new(vec![], [100.0, 100.0], 1.0);
+
+    let vec: TState<Vec<(u32, String)>, ()> = CommonState::new(&vec![(1, "a".to_string()), (2, "b".to_string())]).into();
+    let id: TState<u32, ()> = CommonState::new(&2).into();
+
+    let mut state = KeyedVecState::new(vec, id, |element: &(u32, String)| element.0);
+
+    assert_eq!(state.get_value(&env, &()).1, "b");
+
+    state.vec.get_latest_value_mut().remove(0);
+
+    assert_eq!(state.get_value(&env, &()).1, "b");
+}
@@ -3,6 +3,7 @@ use uuid::Uuid;
 use crate::prelude::Environment;
 use crate::prelude::GlobalState;
 use crate::state::{StateContract, TState};
+use crate::state::functions::{MapBackFn, MapFn};
 use crate::state::state::State;
 use crate::state::state_key::StateKey;
 use crate::state::widget_state::WidgetState;
@@ -11,47 +12,84 @@ use crate::state::widget_state::WidgetState;
 pub struct MappedState<T, U, GS> where T: StateContract, U: StateContract, GS: GlobalState {
     id: Option<StateKey>,
     mapped_state: Box<dyn State<U, GS>>,
-    map: fn(&U) -> T,
-    map_back: Option<fn(U, &T) -> U>,
+    map: Box<dyn MapFn<U, T>>,
+    map_back: Option<Box<dyn MapBackFn<U, T>>>,
     latest_value: T,
+    /// True if a mutable reference to the latest value has been handed out, and the value has
+    /// not yet been mapped back into the mapped state.
+    changed: bool,
 }
 
 impl<T: StateContract, U: StateContract, GS: GlobalState> MappedState<T, U, GS> {
 
-    pub fn new_local(state: Box<dyn State<U, GS>>, map: fn(&U) -> T, start: T) -> Box<MappedState<T, U, GS>> {
+    pub fn new_local<M: Fn(&U) -> T + Clone + 'static>(state: Box<dyn State<U, GS>>, map: M, start: T) -> Box<MappedState<T, U, GS>> {
         Box::new(MappedState {
             id: Some(StateKey::String(Uuid::new_v4().to_string())),
             mapped_state: state,
-            map,
+            map: Box::new(map),
             map_back: None,
-            latest_value: start
+            latest_value: start,
+            changed: false,
         })
     }
 
-    pub fn new(state: Box<dyn State<U, GS>>, map: fn(&U) -> T, start: T) -> Box<MappedState<T, U, GS>> {
+    pub fn new<M: Fn(&U) -> T + Clone + 'static>(state: Box<dyn State<U, GS>>, map: M, start: T) -> Box<MappedState<T, U, GS>> {
         Box::new(MappedState {
             id: None,
             mapped_state: state,
-            map,
+            map: Box::new(map),
             map_back: None,
-            latest_value: start
+            latest_value: start,
+            changed: false,
         })
     }
 
-    pub fn map_back(mut self, f: fn(U, &T) -> U) -> Box<MappedState<T, U, GS>> {
-        self.map_back = Some(f);
+    pub fn map_back<M: Fn(U, &T) -> U + Clone + 'static>(mut self, f: M) -> Box<MappedState<T, U, GS>> {
+        self.map_back = Some(Box::new(f));
 
         Box::new(self)
     }
+
+    fn mapped_back_value(&self) -> Option<U> {
+        match &self.map_back {
+            Some(map_back) if self.changed => {
+                Some(map_back(self.mapped_state.get_latest_value().clone(), &self.latest_value))
+            }
+            _ => None,
+        }
+    }
+
+    /// Write the changed value back into the mapped state. This is possible for all kinds of
+    /// states, because we have mutable access to both the environment and the global state.
+    fn write_back(&mut self, env: &mut Environment<GS>, global_state: &mut GS) {
+        if let (Some(map_back), true) = (&self.map_back, self.changed) {
+            let value = self.mapped_state.get_value_mut(env, global_state);
+            *value = map_back(value.clone(), &self.latest_value);
+            self.changed = false;
+        }
+    }
 }
 
 impl<T: StateContract, U: StateContract, GS: GlobalState> State<T, GS> for MappedState<T, U, GS> {
-    fn get_value_mut(&mut self, env: &mut Environment<GS>, global_state: &mut GS) -> &mut T {
+    fn get_value_mut<'a>(&'a mut self, env: &'a mut Environment<GS>, global_state: &'a mut GS) -> &'a mut T {
+        self.write_back(env, global_state);
+
         self.latest_value = (self.map)(self.mapped_state.get_value_mut(env, global_state));
+
+        // We can not know what is done with the value, so we assume it has been changed.
+        self.changed = self.map_back.is_some();
+
         &mut self.latest_value
     }
 
     fn get_value(&mut self, env: &Environment<GS>, global_state: &GS) -> &T {
+        if let Some(mapped_back) = self.mapped_back_value() {
+            // Without mutable access to the global state, we can only update the latest value of
+            // the mapped state. The value is written all the way back when the state is released.
+            *self.mapped_state.get_latest_value_mut() = mapped_back;
+            return &self.latest_value;
+        }
+
         self.latest_value = (self.map)(self.mapped_state.get_value(env, global_state));
         &self.latest_value
     }
@@ -61,6 +99,7 @@ impl<T: StateContract, U: StateContract, GS: GlobalState> State<T, GS> for Mappe
     }
 
     fn get_latest_value_mut(&mut self) -> &mut T {
+        self.changed = self.map_back.is_some();
         &mut self.latest_value
     }
 
@@ -76,15 +115,17 @@ impl<T: StateContract, U: StateContract, GS: GlobalState> State<T, GS> for Mappe
         env.update_local_state(&mut self.mapped_state)
     }
 
-    fn insert_dependent_states(&self, _: &mut Environment<GS>) {
-        //Todo: If a map back function is made, we could map the value back and insert that into the environment
-
-        /*if let Some(map_back) = &self.map_back {
+    fn insert_dependent_states(&self, env: &mut Environment<GS>) {
+        if let Some(mapped_back) = self.mapped_back_value() {
             if let Some(key) = self.mapped_state.get_key() {
-                let mapped_back = map_back(self.mapped_state.get_latest_value().clone(), &self.latest_value);
                 env.insert_local_state_from_key_value(key, &mapped_back)
             }
-        }*/
+        }
+    }
+
+    fn release_state(&mut self, env: &mut Environment<GS>, global_state: &mut GS) {
+        self.write_back(env, global_state);
+        self.mapped_state.release_state(env, global_state);
     }
 }
 
@@ -93,3 +134,42 @@ impl<T: StateContract + 'static, U: StateContract + 'static, GS: GlobalState> In
         WidgetState::new(self)
     }
 }
+
+#[test]
+fn changes_are_written_back_through_map_back() {
+    use crate::state::state::CommonState;
+    use crate::state::state_ext::StateExt;
+
+    fn number(global_state: &f64) -> &f64 {
+        global_state
+    }
+
+    fn number_mut(global_state: &mut f64) -> &mut f64 {
+        global_state
+    }
+
+    let mut env = Environment::<f64>::new(vec![], [100.0, 100.0], 1.0);
+    let mut global_state = 1.5;
+
+    let source = CommonState::GlobalState {
+        function: Box::new(number),
+        function_mut: Box::new(number_mut),
+        latest_value: 1.5,
+    };
+
+    let mut text = MappedState::new(Box::new(source.clone()), |number: &f64| number.to_string(), "1.5".to_string())
+        .map_back(|number: f64, text: &String| text.parse().unwrap_or(number));
+
+    *text.get_value_mut(&mut env, &mut global_state) = "2.5".to_string();
+    assert!(text.changed);
+
+    text.release_state(&mut env, &mut global_state);
+    assert_eq!(global_state, 2.5);
+    assert!(!text.changed);
+
+    let mut text = source.mapped_bidirectional(|number: &f64| number.to_string(), |number: f64, text: &String| text.parse().unwrap_or(number));
+
+    *text.get_value_mut(&mut env, &mut global_state) = "4".to_string();
+    text.release_state(&mut env, &mut global_state);
+    assert_eq!(global_state, 4.0);
+}
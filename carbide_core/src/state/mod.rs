@@ -7,7 +7,13 @@ use crate::focus::Focus;
 pub use crate::state::state::State;
 use crate::state::widget_state::WidgetState;
 
+pub use self::change_marker::ChangeMarker;
+pub use self::combine_latest_state::CombineLatestState;
+pub use self::keyed_vec_state::KeyedVecState;
 pub use self::mapped_state::MappedState;
+pub use self::observed_state::ObservedState;
+pub use self::undoable_state::UndoableState;
+pub use self::zip_state::ZipState;
 
 pub mod state;
 pub mod state_sync;
@@ -16,8 +22,15 @@ pub mod mapped_state;
 pub mod state_key;
 pub mod tuple_state;
 pub mod vec_state;
+pub mod keyed_vec_state;
 pub mod widget_state;
 pub mod state_ext;
+pub mod functions;
+pub mod zip_state;
+pub mod combine_latest_state;
+pub mod undoable_state;
+pub mod observed_state;
+pub mod change_marker;
 
 pub type ColorState<GS> = TState<Color, GS>;
 pub type StringState<GS> = TState<String, GS>;
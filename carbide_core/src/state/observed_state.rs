@@ -0,0 +1,135 @@
+use uuid::Uuid;
+
+use crate::to_bin;
+use crate::prelude::Environment;
+use crate::prelude::GlobalState;
+use crate::state::{StateContract, TState};
+use crate::state::functions::ChangeFn;
+use crate::state::state::State;
+use crate::state::state_key::StateKey;
+use crate::state::widget_state::WidgetState;
+
+/// A state calling a callback when the value of the state it wraps changes. The value is compared
+/// each time the state is updated, which happens when the widgets are synchronized after each
+/// event. The callback is called after the synchronization, with mutable access to the
+/// environment and the global state.
+#[derive(Clone)]
+pub struct ObservedState<T, GS> where T: StateContract + 'static, GS: GlobalState {
+    /// Used to make sure the callback is only called once, even if the state is cloned into
+    /// multiple widgets.
+    id: StateKey,
+    state: TState<T, GS>,
+    callback: Box<dyn ChangeFn<T, GS>>,
+    /// The latest observed value, and the same value serialized, used for comparison.
+    observed_value: T,
+    observed_bytes: Vec<u8>,
+}
+
+impl<T: StateContract + 'static, GS: GlobalState> ObservedState<T, GS> {
+    pub fn new<I, C>(state: I, callback: C) -> Box<ObservedState<T, GS>>
+        where I: Into<TState<T, GS>>, C: Fn(&T, &T, &mut Environment<GS>, &mut GS) + Clone + 'static {
+        let state = state.into();
+        let observed_value = state.get_latest_value().clone();
+        let observed_bytes = to_bin(&observed_value).unwrap();
+
+        Box::new(ObservedState {
+            id: StateKey::String(Uuid::new_v4().to_string()),
+            state,
+            callback: Box::new(callback),
+            observed_value,
+            observed_bytes,
+        })
+    }
+
+    fn observe(&mut self, env: &Environment<GS>) {
+        let value = self.state.get_latest_value();
+        let bytes = to_bin(value).unwrap();
+
+        if bytes != self.observed_bytes {
+            let new = value.clone();
+            let old = std::mem::replace(&mut self.observed_value, new.clone());
+            self.observed_bytes = bytes;
+
+            let callback = self.callback.clone();
+
+            env.queue_callback(&self.id, Box::new(move |env: &mut Environment<GS>, global_state: &mut GS| {
+                callback(&old, &new, env, global_state)
+            }));
+        }
+    }
+}
+
+impl<T: StateContract + 'static, GS: GlobalState> State<T, GS> for ObservedState<T, GS> {
+    fn get_value_mut<'a>(&'a mut self, env: &'a mut Environment<GS>, global_state: &'a mut GS) -> &'a mut T {
+        self.state.get_value_mut(env, global_state)
+    }
+
+    fn get_value(&mut self, env: &Environment<GS>, global_state: &GS) -> &T {
+        self.state.get_value(env, global_state);
+        self.observe(env);
+        self.state.get_latest_value()
+    }
+
+    fn get_latest_value(&self) -> &T {
+        self.state.get_latest_value()
+    }
+
+    fn get_latest_value_mut(&mut self) -> &mut T {
+        self.state.get_latest_value_mut()
+    }
+
+    fn get_key(&self) -> Option<&StateKey> {
+        None
+    }
+
+    fn update_dependent_states(&mut self, env: &Environment<GS>) {
+        env.update_local_state(&mut self.state);
+    }
+
+    fn insert_dependent_states(&self, env: &mut Environment<GS>) {
+        env.insert_local_state(&self.state);
+    }
+
+    fn release_state(&mut self, env: &mut Environment<GS>, global_state: &mut GS) {
+        self.state.release_state(env, global_state);
+    }
+}
+
+impl<T: StateContract + 'static, GS: GlobalState> Into<TState<T, GS>> for Box<ObservedState<T, GS>> {
+    fn into(self) -> TState<T, GS> {
+        WidgetState::new(self)
+    }
+}
+
+#[test]
+fn callback_is_queued_once_per_change() {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use crate::state::state::CommonState;
+
+    let mut env = Environment::<()>::new(vec![], [100.0, 100.0], 1.0);
+    let mut global_state = ();
+
+    let calls = Rc::new(Cell::new(0));
+    let counter = calls.clone();
+
+    let mut state = ObservedState::new(CommonState::new(&1u32), move |old: &u32, new: &u32, _: &mut Environment<()>, _: &mut ()| {
+        assert_eq!((*old, *new), (1, 2));
+        counter.set(counter.get() + 1);
+    });
+    let mut clone = state.clone();
+
+    *state.get_latest_value_mut() = 2;
+    *clone.get_latest_value_mut() = 2;
+
+    state.get_value(&env, &global_state);
+    clone.get_value(&env, &global_state);
+    state.get_value(&env, &global_state);
+
+    for callback in env.take_queued_callbacks() {
+        callback(&mut env, &mut global_state);
+    }
+
+    assert_eq!(calls.get(), 1);
+}
@@ -9,6 +9,7 @@ use uuid::Uuid;
 
 use crate::prelude::Environment;
 use crate::state::*;
+use crate::state::functions::{EnvFn, EnvFnMut, GlobalFn, GlobalFnMut};
 use crate::state::global_state::GlobalState;
 use crate::state::state_key::StateKey;
 use crate::widget::widget_state::WidgetState;
@@ -21,6 +22,10 @@ pub trait State<T, GS>: DynClone where T: Serialize + Clone + Debug, GS: GlobalS
     fn get_key(&self) -> Option<&StateKey>;
     fn update_dependent_states(&mut self, env: &Environment<GS>);
     fn insert_dependent_states(&self, env: &mut Environment<GS>);
+
+    /// Called after the widget owning the state has processed an event. States handing out a
+    /// mutable reference to a copy of their value should write the changes back here.
+    fn release_state(&mut self, _env: &mut Environment<GS>, _global_state: &mut GS) {}
 }
 
 
@@ -62,6 +67,10 @@ impl<T: Serialize + Clone + Debug, GS: GlobalState> State<T, GS> for Box<dyn Sta
     fn insert_dependent_states(&self, env: &mut Environment<GS>) {
         self.deref().insert_dependent_states(env)
     }
+
+    fn release_state(&mut self, env: &mut Environment<GS>, global_state: &mut GS) {
+        self.deref_mut().release_state(env, global_state)
+    }
 }
 
 // TODO: Split into different structs.
@@ -70,13 +79,13 @@ pub enum CommonState<T, GS> where T: Serialize + Clone + Debug, GS: GlobalState
     LocalState { id: StateKey, value: T },
     Value { value: T },
     GlobalState {
-        function: fn(state: &GS) -> &T,
-        function_mut: fn(state: &mut GS) -> &mut T,
+        function: Box<dyn GlobalFn<T, GS>>,
+        function_mut: Box<dyn GlobalFnMut<T, GS>>,
         latest_value: T,
     },
     EnvironmentState {
-        function: fn(env: &Environment<GS>) -> T,
-        function_mut: Option<fn(env: &mut Environment<GS>) -> &mut T>,
+        function: Box<dyn EnvFn<T, GS>>,
+        function_mut: Option<Box<dyn EnvFnMut<T, GS>>>,
         latest_value: T,
     },
 }
@@ -212,13 +221,17 @@ impl<T: Clone + Debug + Serialize + 'static, S: GlobalState> CommonState<T, S> {
             value: val.clone(),
         }
     }
-}
-
-/*pub trait EnvFn<T: Serialize + Clone + Debug, GS: GlobalState>: FnOnce(&Environment<GS>) -> T + DynClone {}
-pub trait EnvFnMut<T: Serialize + Clone + Debug, GS: GlobalState>: FnOnce(&mut Environment<GS>) -> T + DynClone {}
 
-impl<T: Serialize + Clone + Debug, U: FnOnce(&Environment<GS>) -> T + DynClone, GS: GlobalState> EnvFn<T, GS> for U {}
-impl<T: Serialize + Clone + Debug, U: FnOnce(&mut Environment<GS>) -> T + DynClone, GS: GlobalState> EnvFnMut<T, GS> for U {}*/
+    /// Create a local state that is persisted across runs of the application, if the `Ui` has
+    /// been given a file to persist the state to. The key should be unique within the application,
+    /// and states with the same key share their value.
+    pub fn new_persistent(key: &str, val: &T) -> Self {
+        CommonState::LocalState {
+            id: StateKey::Persistent(key.to_string()),
+            value: val.clone(),
+        }
+    }
+}
 
 /*pub type LocalStateList = Vec<(String, String)>;
 
@@ -1,11 +1,74 @@
-use crate::state::{State, StateContract};
+use crate::prelude::Environment;
+use crate::state::{State, StateContract, TState};
+use crate::state::combine_latest_state::CombineLatestState;
 use crate::state::global_state::GlobalState;
 use crate::state::mapped_state::MappedState;
+use crate::state::observed_state::ObservedState;
+use crate::state::undoable_state::UndoableState;
+use crate::state::widget_state::WidgetState;
+use crate::state::zip_state::ZipState;
 
 pub trait StateExt<T: StateContract + 'static, GS: GlobalState>: State<T, GS> + Sized + 'static {
-    fn mapped<U: StateContract + 'static>(self, map: fn(&T) -> U) -> Box<dyn State<U, GS>> {
-        let latest_value = self.get_latest_value().clone();
-        MappedState::new(Box::new(self), map, map(&latest_value))
+    fn mapped<U: StateContract + 'static, M: Fn(&T) -> U + Clone + 'static>(self, map: M) -> Box<dyn State<U, GS>> {
+        let start = map(self.get_latest_value());
+        MappedState::new(Box::new(self), map, start)
+    }
+
+    /// Map the state both ways. Changes to the mapped value are written back to this state using
+    /// `map_back`, which receives the current value of this state and the changed mapped value.
+    fn mapped_bidirectional<U, M, B>(self, map: M, map_back: B) -> Box<dyn State<U, GS>>
+        where U: StateContract + 'static, M: Fn(&T) -> U + Clone + 'static, B: Fn(T, &U) -> T + Clone + 'static {
+        let start = map(self.get_latest_value());
+        MappedState::new(Box::new(self), map, start).map_back(map_back)
+    }
+
+    /// Combine this state with another into a state of a tuple. Changes to the tuple are written
+    /// back to both states.
+    fn zip<U: StateContract + 'static, S: Into<TState<U, GS>>>(self, other: S) -> Box<ZipState<T, U, GS>> {
+        ZipState::new(WidgetState::new(Box::new(self)), other)
+    }
+
+    /// Combine this state with a list of other states of the same type into a state of a vec
+    /// containing the latest value of each. Changes to the vec are written back to the states.
+    fn combine_latest<S: Into<TState<T, GS>>>(self, others: Vec<S>) -> Box<CombineLatestState<T, GS>> {
+        let mut states: Vec<TState<T, GS>> = vec![WidgetState::new(Box::new(self))];
+        states.extend(others.into_iter().map(|state| state.into()));
+
+        CombineLatestState::new(states)
+    }
+
+    /// Record the changes made to this state, such that they can be undone and redone using the
+    /// undo manager of the environment.
+    fn undoable(self) -> Box<UndoableState<T, GS>> {
+        UndoableState::new(WidgetState::new(Box::new(self)))
+    }
+
+    /// Call the callback with the old and the new value, each time the value of this state changes.
+    /// The callback is called after the state of the widgets has been synchronized.
+    fn observe<C>(self, callback: C) -> Box<ObservedState<T, GS>>
+        where C: Fn(&T, &T, &mut Environment<GS>, &mut GS) + Clone + 'static {
+        ObservedState::new(WidgetState::new(Box::new(self)), callback)
+    }
+
+    /// Derive a read-only state from this state and another.
+    fn map2<U, R, S, M>(self, other: S, map: M) -> Box<dyn State<R, GS>>
+        where U: StateContract + 'static, R: StateContract + 'static, S: Into<TState<U, GS>>, M: Fn(&T, &U) -> R + Clone + 'static {
+        let zipped = self.zip(other);
+        let (fst, snd) = zipped.get_latest_value();
+        let start = map(fst, snd);
+
+        MappedState::new(zipped, move |(fst, snd): &(T, U)| map(fst, snd), start)
+    }
+
+    /// Derive a read-only state from this state and two others.
+    fn map3<U, V, R, S1, S2, M>(self, snd: S1, trd: S2, map: M) -> Box<dyn State<R, GS>>
+        where U: StateContract + 'static, V: StateContract + 'static, R: StateContract + 'static,
+              S1: Into<TState<U, GS>>, S2: Into<TState<V, GS>>, M: Fn(&T, &U, &V) -> R + Clone + 'static {
+        let zipped = ZipState::new(self.zip(snd), trd);
+        let ((fst, snd), trd) = zipped.get_latest_value();
+        let start = map(fst, snd, trd);
+
+        MappedState::new(zipped, move |((fst, snd), trd): &((T, U), V)| map(fst, snd, trd), start)
     }
 }
 
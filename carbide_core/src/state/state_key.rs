@@ -5,6 +5,8 @@ use crate::prelude::EnvironmentFontSize;
 #[derive(Hash, Eq, PartialEq, Clone, Debug)]
 pub enum StateKey {
     String(String),
+    /// The key of a local state that is persisted across runs of the application.
+    Persistent(String),
     Color(EnvironmentColor),
     FontSize(EnvironmentFontSize),
 }
\ No newline at end of file
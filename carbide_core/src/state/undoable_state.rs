@@ -0,0 +1,144 @@
+use uuid::Uuid;
+
+use crate::{from_bin, to_bin};
+use crate::prelude::Environment;
+use crate::prelude::GlobalState;
+use crate::state::{StateContract, TState};
+use crate::state::state::State;
+use crate::state::state_key::StateKey;
+use crate::state::widget_state::WidgetState;
+
+/// A state recording the changes made to the state it wraps in the undo manager of the
+/// environment. A change is recorded when the state is released after a mutable reference to
+/// its value has been handed out, and the value differs from the value before.
+#[derive(Clone)]
+pub struct UndoableState<T, GS> where T: StateContract, GS: GlobalState {
+    /// The key used to identify the changes of this state in the undo manager. Clones of the
+    /// state share the key.
+    id: StateKey,
+    state: TState<T, GS>,
+    /// The serialized value from before the first mutable reference since the last release.
+    before: Option<Vec<u8>>,
+    /// True if a value has been restored by an undo or redo, and not yet written to the state.
+    restored: bool,
+}
+
+impl<T: StateContract, GS: GlobalState> UndoableState<T, GS> {
+    pub fn new<I: Into<TState<T, GS>>>(state: I) -> Box<UndoableState<T, GS>> {
+        Box::new(UndoableState {
+            id: StateKey::String(Uuid::new_v4().to_string()),
+            state: state.into(),
+            before: None,
+            restored: false,
+        })
+    }
+
+    /// Apply the value staged by an undo or redo, if any.
+    fn apply_pending(&mut self, env: &Environment<GS>) {
+        if let Some(value) = env.undo_manager().pending_value(&self.id) {
+            *self.state.get_latest_value_mut() = from_bin(value).unwrap();
+            self.restored = true;
+            self.before = None;
+        }
+    }
+
+    fn write_restored(&mut self, env: &mut Environment<GS>, global_state: &mut GS) {
+        if self.restored {
+            let restored = self.state.get_latest_value().clone();
+            *self.state.get_value_mut(env, global_state) = restored;
+            self.restored = false;
+        }
+    }
+}
+
+impl<T: StateContract, GS: GlobalState> State<T, GS> for UndoableState<T, GS> {
+    fn get_value_mut<'a>(&'a mut self, env: &'a mut Environment<GS>, global_state: &'a mut GS) -> &'a mut T {
+        self.write_restored(env, global_state);
+
+        let value = self.state.get_value_mut(env, global_state);
+
+        if self.before.is_none() {
+            self.before = Some(to_bin(&*value).unwrap());
+        }
+
+        value
+    }
+
+    fn get_value(&mut self, env: &Environment<GS>, global_state: &GS) -> &T {
+        self.apply_pending(env);
+
+        if self.restored {
+            return self.state.get_latest_value();
+        }
+
+        self.state.get_value(env, global_state)
+    }
+
+    fn get_latest_value(&self) -> &T {
+        self.state.get_latest_value()
+    }
+
+    fn get_latest_value_mut(&mut self) -> &mut T {
+        if self.before.is_none() && !self.restored {
+            self.before = Some(to_bin(self.state.get_latest_value()).unwrap());
+        }
+
+        self.state.get_latest_value_mut()
+    }
+
+    fn get_key(&self) -> Option<&StateKey> {
+        None
+    }
+
+    fn update_dependent_states(&mut self, env: &Environment<GS>) {
+        env.update_local_state(&mut self.state);
+        self.apply_pending(env);
+    }
+
+    fn insert_dependent_states(&self, env: &mut Environment<GS>) {
+        env.insert_local_state(&self.state);
+    }
+
+    fn release_state(&mut self, env: &mut Environment<GS>, global_state: &mut GS) {
+        self.write_restored(env, global_state);
+
+        if let Some(before) = self.before.take() {
+            let after = to_bin(self.state.get_value(env, global_state)).unwrap();
+
+            if before != after {
+                env.undo_manager_mut().register_change(self.id.clone(), before, after);
+            }
+        }
+
+        self.state.release_state(env, global_state);
+    }
+}
+
+impl<T: StateContract + 'static, GS: GlobalState> Into<TState<T, GS>> for Box<UndoableState<T, GS>> {
+    fn into(self) -> TState<T, GS> {
+        WidgetState::new(self)
+    }
+}
+
+#[test]
+fn undo_restores_the_value_before_the_change() {
+    use crate::state::state::CommonState;
+
+    let mut env = Environment::<()>::new(vec![], [100.0, 100.0], 1.0);
+    let mut global_state = ();
+
+    let mut state = UndoableState::new(CommonState::new(&"a".to_string()));
+
+    *state.get_value_mut(&mut env, &mut global_state) = "b".to_string();
+    state.release_state(&mut env, &mut global_state);
+
+    assert!(env.undo_manager_mut().undo());
+
+    assert_eq!(state.get_value(&env, &global_state), &"a".to_string());
+    state.release_state(&mut env, &mut global_state);
+    env.undo_manager_mut().clear_pending();
+
+    assert_eq!(state.get_value(&env, &global_state), &"a".to_string());
+    assert!(!env.undo_manager().can_undo());
+    assert!(env.undo_manager().can_redo());
+}
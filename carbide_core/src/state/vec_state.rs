@@ -11,6 +11,10 @@ use crate::state::state::State;
 use crate::state::state_key::StateKey;
 use crate::state::widget_state::WidgetState;
 
+/// A state of the element at an index in a vec. If the index is out of bounds, for example if
+/// the vec has shrunk while the index state is stale, the latest value is used as a fallback and
+/// changes to it are not written to the vec. Use `KeyedVecState` to refer to elements by their
+/// id instead of their position.
 #[derive(Clone)]
 pub struct VecState<T, GS> where T: Serialize + Clone + Debug, GS: GlobalState {
     id: Option<StateKey>,
@@ -46,14 +50,24 @@ impl<T: Serialize + Clone + Debug, GS: GlobalState> VecState<T, GS> {
 impl<T: Serialize + Clone + Debug + DeserializeOwned, GS: GlobalState> State<T, GS> for VecState<T, GS> {
     fn get_value_mut<'a>(&'a mut self, env: &'a mut Environment<GS>, global_state: &'a mut GS) -> &'a mut T {
         self.latest_index = *self.index_state.get_value(env, global_state);
-        self.latest_value = self.vec.get_value(env, global_state)[self.latest_index].clone();
-        &mut self.vec.get_value_mut(env, global_state)[self.latest_index]
+
+        match self.vec.get_value_mut(env, global_state).get_mut(self.latest_index) {
+            Some(value) => {
+                self.latest_value = value.clone();
+                value
+            }
+            None => &mut self.latest_value,
+        }
     }
 
     fn get_value(&mut self, env: &Environment<GS>, global_state: &GS) -> &T {
         self.latest_index = *self.index_state.get_value(env, global_state);
-        self.latest_value = self.vec.get_value(env, global_state)[self.latest_index].clone();
-        &self.vec.get_value(env, global_state)[self.latest_index]
+
+        if let Some(value) = self.vec.get_value(env, global_state).get(self.latest_index) {
+            self.latest_value = value.clone();
+        }
+
+        &self.latest_value
     }
 
     fn get_latest_value(&self) -> &T {
@@ -92,4 +106,27 @@ impl<T: StateContract + 'static, GS: GlobalState> Into<TState<T, GS>> for Box<Ve
     fn into(self) -> TState<T, GS> {
         WidgetState::new(self)
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn out_of_bounds_index_uses_latest_value() {
+    use crate::state::state::CommonState;
+
+    let env = Environment::<()>::new(vec![], [100.0, 100.0], 1.0);
+
+    let vec: Box<dyn State<Vec<u32>, ()>> = Box::new(CommonState::new(&vec![1, 2, 3]));
+    let mut index: Box<dyn State<usize, ()>> = Box::new(CommonState::new(&2));
+
+    let mut state = VecState::new(vec, index.clone(), 0);
+
+    assert_eq!(*state.get_value(&env, &()), 3);
+
+    *state.vec.get_latest_value_mut() = vec![1];
+
+    assert_eq!(*state.get_value(&env, &()), 3);
+
+    *index.get_latest_value_mut() = 0;
+    state.index_state = index;
+
+    assert_eq!(*state.get_value(&env, &()), 1);
+}
@@ -68,4 +68,8 @@ impl<T: Serialize + Clone + Debug + DeserializeOwned + Default, GS: GlobalState>
     fn insert_dependent_states(&self, env: &mut Environment<GS>) {
         self.0.insert_dependent_states(env)
     }
+
+    fn release_state(&mut self, env: &mut Environment<GS>, global_state: &mut GS) {
+        self.0.release_state(env, global_state)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,146 @@
+use crate::prelude::Environment;
+use crate::prelude::GlobalState;
+use crate::state::{StateContract, TState};
+use crate::state::state::State;
+use crate::state::state_key::StateKey;
+use crate::state::widget_state::WidgetState;
+
+/// A state combining two states into a tuple. Changes to the tuple are written back to the
+/// states it was combined from.
+#[derive(Clone)]
+pub struct ZipState<T, U, GS> where T: StateContract, U: StateContract, GS: GlobalState {
+    fst: TState<T, GS>,
+    snd: TState<U, GS>,
+    latest_value: (T, U),
+    /// True if a mutable reference to the latest value has been handed out, and the value has
+    /// not yet been written back into the zipped states.
+    changed: bool,
+}
+
+impl<T: StateContract, U: StateContract, GS: GlobalState> ZipState<T, U, GS> {
+    pub fn new<IT, IU>(fst: IT, snd: IU) -> Box<ZipState<T, U, GS>>
+        where
+            IT: Into<TState<T, GS>>,
+            IU: Into<TState<U, GS>>
+    {
+        let fst = fst.into();
+        let snd = snd.into();
+
+        let latest_value = (fst.get_latest_value().clone(), snd.get_latest_value().clone());
+
+        Box::new(ZipState {
+            fst,
+            snd,
+            latest_value,
+            changed: false,
+        })
+    }
+
+    fn write_back(&mut self, env: &mut Environment<GS>, global_state: &mut GS) {
+        if self.changed {
+            *self.fst.get_value_mut(env, global_state) = self.latest_value.0.clone();
+            *self.snd.get_value_mut(env, global_state) = self.latest_value.1.clone();
+            self.changed = false;
+        }
+    }
+}
+
+impl<T: StateContract, U: StateContract, GS: GlobalState> State<(T, U), GS> for ZipState<T, U, GS> {
+    fn get_value_mut<'a>(&'a mut self, env: &'a mut Environment<GS>, global_state: &'a mut GS) -> &'a mut (T, U) {
+        self.write_back(env, global_state);
+
+        self.latest_value = (self.fst.get_value_mut(env, global_state).clone(), self.snd.get_value_mut(env, global_state).clone());
+
+        // We can not know what is done with the value, so we assume it has been changed.
+        self.changed = true;
+
+        &mut self.latest_value
+    }
+
+    fn get_value(&mut self, env: &Environment<GS>, global_state: &GS) -> &(T, U) {
+        if self.changed {
+            // The value is written all the way back when the state is released.
+            *self.fst.get_latest_value_mut() = self.latest_value.0.clone();
+            *self.snd.get_latest_value_mut() = self.latest_value.1.clone();
+            return &self.latest_value;
+        }
+
+        self.latest_value = (self.fst.get_value(env, global_state).clone(), self.snd.get_value(env, global_state).clone());
+        &self.latest_value
+    }
+
+    fn get_latest_value(&self) -> &(T, U) {
+        &self.latest_value
+    }
+
+    fn get_latest_value_mut(&mut self) -> &mut (T, U) {
+        self.changed = true;
+        &mut self.latest_value
+    }
+
+    fn get_key(&self) -> Option<&StateKey> {
+        None
+    }
+
+    fn update_dependent_states(&mut self, env: &Environment<GS>) {
+        env.update_local_state(&mut self.fst);
+        env.update_local_state(&mut self.snd);
+
+        if !self.changed {
+            self.latest_value = (self.fst.get_latest_value().clone(), self.snd.get_latest_value().clone());
+        }
+    }
+
+    fn insert_dependent_states(&self, env: &mut Environment<GS>) {
+        if self.changed {
+            if let Some(fst_key) = self.fst.get_key() {
+                env.insert_local_state_from_key_value(fst_key, &self.latest_value.0);
+            }
+
+            if let Some(snd_key) = self.snd.get_key() {
+                env.insert_local_state_from_key_value(snd_key, &self.latest_value.1);
+            }
+        } else {
+            self.fst.insert_dependent_states(env);
+            self.snd.insert_dependent_states(env);
+        }
+    }
+
+    fn release_state(&mut self, env: &mut Environment<GS>, global_state: &mut GS) {
+        self.write_back(env, global_state);
+        self.fst.release_state(env, global_state);
+        self.snd.release_state(env, global_state);
+    }
+}
+
+impl<T: StateContract + 'static, U: StateContract + 'static, GS: GlobalState> Into<TState<(T, U), GS>> for Box<ZipState<T, U, GS>> {
+    fn into(self) -> TState<(T, U), GS> {
+        WidgetState::new(self)
+    }
+}
+
+#[test]
+fn changes_are_written_back_to_both_states() {
+    use crate::state::state::CommonState;
+
+    let mut env = Environment::<()>::new(vec![], [100.0, 100.0], 1.0);
+    let mut global_state = ();
+
+    let fst: TState<u32, ()> = CommonState::new(&1).into();
+    let snd: TState<String, ()> = CommonState::new(&"a".to_string()).into();
+
+    let mut zip = ZipState::new(fst.clone(), snd.clone());
+
+    {
+        let value = zip.get_value_mut(&mut env, &mut global_state);
+        value.0 = 2;
+        value.1 = "b".to_string();
+    }
+
+    assert_eq!(zip.get_value(&env, &global_state), &(2, "b".to_string()));
+
+    zip.release_state(&mut env, &mut global_state);
+
+    assert_eq!(zip.fst.get_value(&env, &global_state), &2);
+    assert_eq!(zip.snd.get_value(&env, &global_state), &"b".to_string());
+}
@@ -17,6 +17,7 @@ pub struct Text<GS> where GS: GlobalState {
     style: Option<TextStyle>,
     latest_requested_offset: Position,
     latest_requested_size: Dimension,
+    latest_justify: Justify,
     spans: Vec<TextSpan<GS>>,
     latest_max_width: Scalar,
     latest_max_height: Scalar,
@@ -35,6 +36,7 @@ impl<GS: GlobalState> Text<GS> {
             style: None,
             latest_requested_offset: Default::default(),
             latest_requested_size: Dimension::new(-1.0, -1.0),
+            latest_justify: Justify::Left,
             spans,
             latest_max_width: 0.0,
             latest_max_height: 0.0,
@@ -102,8 +104,9 @@ impl<GS: GlobalState> Text<GS> {
         // Layout as if the layout is at x:0, y:0
 
         // Todo: If text is NoWrap, this is not needed.
-        if self.latest_requested_size.width != requested_size.width {
+        if self.latest_requested_size.width != requested_size.width || self.latest_justify != self.justify {
             self.latest_requested_size = requested_size;
+            self.latest_justify = self.justify;
             match self.wrap {
                 Wrap::Character => {
                     self.calculate_size_with_character_break(requested_size, env);
@@ -240,6 +243,7 @@ impl<GS: GlobalState> Text<GS> {
             }
         }
 
+        self.justify_lines(max_width);
         self.calculate_line_heights(requested_size, env);
 
         self.latest_requested_offset = Position::new(0.0, 0.0);
@@ -310,11 +314,65 @@ impl<GS: GlobalState> Text<GS> {
             }
         }
 
+        self.justify_lines(max_width);
         self.calculate_line_heights(requested_size, env);
         self.latest_requested_offset = Position::new(0.0, 0.0);
         self.latest_max_width = max_width as f64;
     }
 
+    /// Move the lines horizontally within the width of the longest line, according to the
+    /// justification. This is done while the y of each glyph is still the index of its line.
+    fn justify_lines(&mut self, max_width: Scalar) {
+        let factor = match self.justify {
+            Justify::Left => return,
+            Justify::Center => 0.5,
+            Justify::Right => 1.0,
+        };
+
+        // The width of each line, ignoring the whitespace at its ends.
+        let mut line_widths: Vec<Scalar> = vec![];
+
+        for span in &self.spans {
+            if let TextSpan::Text { text, glyphs, widths, .. } = span {
+                for ((glyph, width), char) in glyphs.iter().zip(widths).zip(text.chars()) {
+                    let line = glyph.position().y as usize;
+
+                    if line_widths.len() <= line {
+                        line_widths.resize(line + 1, 0.0);
+                    }
+
+                    if !char.is_whitespace() {
+                        line_widths[line] = line_widths[line].max(glyph.position().x + width);
+                    }
+                }
+            }
+        }
+
+        let offset = |line: usize| (max_width - line_widths.get(line).cloned().unwrap_or(0.0)).max(0.0) * factor;
+
+        for span in &mut self.spans {
+            if let TextSpan::Text { glyphs, style, .. } = span {
+                for glyph in glyphs {
+                    let position = glyph.position();
+                    *glyph.position_mut() = Position::new(position.x + offset(position.y as usize), position.y);
+                }
+
+                if let Some(style) = style {
+                    match &mut style.text_decoration {
+                        TextDecoration::None => {}
+                        TextDecoration::Overline(l) |
+                        TextDecoration::Underline(l) |
+                        TextDecoration::StrikeThrough(l) => {
+                            for rect in l {
+                                rect.position.x += offset(rect.position.y as usize) / self.scale_factor;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
     fn calculate_line_heights(&mut self, requested_size: Dimension, env: &Environment<GS>) {
         let mut line_descends = vec![0.0];
         let mut line_ascends = vec![];
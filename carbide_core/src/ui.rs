@@ -1,15 +1,19 @@
 use std;
 use std::fmt::Debug;
+use std::io;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicUsize;
 
 use instant::Instant;
+use uuid::Uuid;
 
 use crate::{color, cursor};
 use crate::color::Color;
 use crate::event::event::Event;
 use crate::event::input::Input;
-use crate::event_handler::{EventHandler, KeyboardEvent, WidgetEvent, WindowEvent};
-use crate::focus::{Focusable, Refocus};
+use crate::event_handler::{EventHandler, KeyboardEvent, MouseEvent, MouseFromTouch, WidgetEvent, WindowEvent};
+use crate::focus::{Focus, Focusable, Refocus};
 use crate::input::{Key, ModifierKey};
 use crate::position::Dimensions;
 use crate::prelude::Environment;
@@ -18,6 +22,7 @@ use crate::prelude::EnvironmentFontSize;
 use crate::prelude::EnvironmentVariable;
 use crate::render::cprimitives::CPrimitives;
 use crate::state::global_state::GlobalState;
+use crate::widget::common_widget::CommonWidget;
 use crate::widget::primitive::Widget;
 use crate::widget::Rectangle;
 
@@ -42,8 +47,12 @@ pub struct Ui<S> where S: GlobalState {
 
     pub widgets: Box<dyn Widget<S>>,
     event_handler: EventHandler,
+    /// Synthesizes mouse events from the touches not consumed by the widgets.
+    mouse_from_touch: MouseFromTouch,
     pub environment: Environment<S>,
     any_focus: bool,
+    /// The file the persistent local states are read from and saved to.
+    persistent_state_path: Option<PathBuf>,
 }
 
 
@@ -152,8 +161,44 @@ impl<S: GlobalState> Ui<S> {
             maybe_background_color: None,
             mouse_cursor: cursor::MouseCursor::Arrow,
             event_handler: EventHandler::new(),
+            mouse_from_touch: MouseFromTouch::default(),
             environment,
             any_focus: false,
+            persistent_state_path: None,
+        }
+    }
+
+    /// A new, empty **Ui**, with the persistent local states restored from the file at the path.
+    /// Returns an error if the file exists but could not be read.
+    pub fn new_with_persistent_state<P: AsRef<Path>>(window_pixel_dimensions: Dimensions, scale_factor: f64, path: P) -> io::Result<Self> {
+        let mut ui = Ui::new(window_pixel_dimensions, scale_factor);
+        ui.set_persistent_state_path(path)?;
+        Ok(ui)
+    }
+
+    /// Set the file to persist the persistent local states to, and restore the states from it if
+    /// it exists. The file is used for saving even if restoring from it failed, in which case
+    /// the error is returned.
+    pub fn set_persistent_state_path<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        let path = path.as_ref().to_path_buf();
+
+        let restored = if path.exists() {
+            self.environment.read_persistent_state(&path)
+        } else {
+            Ok(())
+        };
+
+        self.persistent_state_path = Some(path);
+
+        restored
+    }
+
+    /// Save the persistent local states to the file given by `set_persistent_state_path`. This
+    /// does nothing if no file has been set.
+    pub fn save_persistent_state(&self) -> io::Result<()> {
+        match &self.persistent_state_path {
+            Some(path) => self.environment.write_persistent_state(path),
+            None => Ok(()),
         }
     }
 
@@ -187,6 +232,8 @@ impl<S: GlobalState> Ui<S> {
                     WindowEvent::Focus => (),//_needs_redraw = true,
                     WindowEvent::UnFocus => (),
                     WindowEvent::Redraw => (),//_needs_redraw = true,
+                    WindowEvent::Tick => (),
+                    WindowEvent::StatesRestored => (),
                 }
             }
         }
@@ -198,25 +245,78 @@ impl<S: GlobalState> Ui<S> {
 
     pub fn delegate_events(&mut self, global_state: &mut S) -> bool {
         let now = Instant::now();
+
+        if self.environment.take_tick_request() {
+            self.event_handler.tick();
+        }
+
+        // The layout may have changed when drawing.
+        self.update_hovered(global_state);
+
         let events = self.event_handler.get_events();
 
         for event in events {
+            // All the changes to undoable states made as a result of a single event are undone
+            // together.
+            self.environment.undo_manager_mut().begin_transaction(None);
+
+            // The cursor is set by the widget under the mouse while the move is handled.
+            if let WidgetEvent::Mouse(MouseEvent::Move { .. }) = event {
+                self.environment.set_cursor(cursor::MouseCursor::Arrow);
+            }
+
             match event {
                 WidgetEvent::Mouse(mouse_event) => {
-                    let consumed = false;
-                    self.widgets.process_mouse_event(mouse_event, &consumed, &mut self.environment, global_state);
+                    dispatch_mouse_event(&mut self.widgets, mouse_event, &mut self.environment, global_state);
                 }
                 WidgetEvent::Keyboard(keyboard_event) => {
-                    self.widgets.process_keyboard_event(keyboard_event, &mut self.environment, global_state);
+                    // A key press executing a registered command, like undo or redo, is not
+                    // given to the widgets.
+                    let executed = match keyboard_event {
+                        KeyboardEvent::Press(key, modifier) => self.environment.execute_shortcut(*key, *modifier, global_state),
+                        _ => false,
+                    };
+
+                    if !executed {
+                        let mut path = vec![];
+                        focus_path(self.widgets.deref(), &mut path);
+
+                        self.environment.begin_dispatch_along_path(path);
+                        self.widgets.process_keyboard_event(keyboard_event, &mut self.environment, global_state);
+                        let stopped = self.environment.end_dispatch_along_path();
+
+                        if !stopped {
+                            self.widgets.process_keyboard_event(keyboard_event, &mut self.environment, global_state);
+                        }
+                    }
                 }
                 WidgetEvent::Window(_) => {
                     self.widgets.process_other_event(event, &mut self.environment, global_state);
                 }
-                WidgetEvent::Touch(_) => {
+                WidgetEvent::Touch(touch_event) => {
+                    // The touch is given to all the widgets. Stopping its propagation only keeps
+                    // mouse events from being synthesized from it.
+                    self.environment.reset_propagation();
                     self.widgets.process_other_event(event, &mut self.environment, global_state);
+                    let consumed = self.environment.is_propagation_stopped();
+
+                    for mouse_event in self.mouse_from_touch.mouse_events(touch_event, consumed) {
+                        dispatch_mouse_event(&mut self.widgets, &mouse_event, &mut self.environment, global_state);
+                    }
                 }
             }
 
+            // Apply the values restored by an undo or a redo to all the undoable states right
+            // away, and not only to the states used while handling the event.
+            if self.environment.undo_manager().has_pending() {
+                self.widgets.process_other_event(&WidgetEvent::Window(WindowEvent::StatesRestored), &mut self.environment, global_state);
+            }
+
+            self.environment.undo_manager_mut().end_transaction();
+            self.environment.undo_manager_mut().clear_pending();
+
+            self.mouse_cursor = self.environment.cursor();
+
             if let Some(request) = self.environment.focus_request.clone() {
                 match request {
                     Refocus::FocusRequest => {
@@ -264,11 +364,21 @@ impl<S: GlobalState> Ui<S> {
             // as the thing below. This will not work if the thing below the overlay layers, position is
             // dependent on some state that has not been synchronized. For a use case look at the pop up
             // button in controls.
-            self.widgets.calculate_size(self.environment.get_corrected_dimensions(), &mut self.environment);
-            self.widgets.position_children();
+            if self.environment.is_layout_invalidated() {
+                self.environment.begin_layout(&*self.widgets);
+                self.widgets.calculate_size_cached(self.environment.get_corrected_dimensions(), &mut self.environment);
+                self.widgets.position_children(&mut self.environment);
+                self.environment.validate_layout();
+            }
+
+            self.update_hovered(global_state);
 
             self.widgets.sync_state(&mut self.environment, global_state);
 
+            for callback in self.environment.take_queued_callbacks() {
+                callback(&mut self.environment, global_state);
+            }
+
 
             self.environment.clear();
         }
@@ -285,6 +395,31 @@ impl<S: GlobalState> Ui<S> {
         return true;
     }
 
+    /// Find the hovered widgets again if the layout changed since they were last found, and send
+    /// enter and exit events to the widgets that moved under or away from a still mouse.
+    fn update_hovered(&mut self, global_state: &mut S) {
+        let position = match self.environment.take_outdated_hover_position() {
+            Some(position) => position,
+            None => return,
+        };
+
+        let mut hovered = vec![];
+        self.widgets.hit_test(position, &mut hovered);
+        self.environment.set_hovered(hovered);
+
+        // With an empty event path only the widgets the mouse entered or exited are visited.
+        let event = MouseEvent::Move { from: position, to: position, delta_xy: [0.0, 0.0], modifiers: ModifierKey::NO_MODIFIER };
+        self.environment.begin_dispatch_along_path(vec![]);
+        self.widgets.process_mouse_event(&event, &mut self.environment, global_state);
+        self.environment.end_dispatch_along_path();
+    }
+
+    /// Make all the widgets be sized again before they are drawn next. Call this after changing
+    /// the global state outside of the event handling of the widgets.
+    pub fn invalidate_layout(&mut self) {
+        self.environment.invalidate_layout();
+    }
+
     /// Draw the `Ui` in it's current state.
     ///
     /// NOTE: If you don't need to redraw your carbide GUI every frame, it is recommended to use the
@@ -306,4 +441,54 @@ impl<S: GlobalState> Ui<S> {
     pub fn mouse_cursor(&self) -> cursor::MouseCursor {
         self.mouse_cursor
     }
-}
\ No newline at end of file
+}
+
+/// Dispatch the mouse event along the path to the hovered widget, and broadcast it to all the
+/// widgets unless its propagation was stopped.
+fn dispatch_mouse_event<S: GlobalState>(widgets: &mut Box<dyn Widget<S>>, mouse_event: &MouseEvent, env: &mut Environment<S>, global_state: &mut S) {
+    // Find the hovered widgets using the layout from before the event, which is the layout the
+    // user sees.
+    let mut hovered = vec![];
+    widgets.hit_test(mouse_event.get_current_mouse_position(), &mut hovered);
+    env.set_hovered(hovered.clone());
+    env.set_mouse_position(mouse_event.get_current_mouse_position());
+
+    env.begin_dispatch_along_path(hovered);
+    widgets.process_mouse_event(mouse_event, env, global_state);
+    let stopped = env.end_dispatch_along_path();
+
+    if !stopped {
+        widgets.process_mouse_event(mouse_event, env, global_state);
+    }
+}
+
+/// Push the id of the focused widget and the ids of its ancestors to the path, innermost first.
+/// Returns true if a focused widget was found.
+fn focus_path<S: GlobalState>(widget: &dyn Widget<S>, path: &mut Vec<Uuid>) -> bool {
+    let found = widget.get_focus() == Focus::Focused
+        || widget.get_children().any(|child| focus_path(child, path));
+
+    if found {
+        path.push(widget.get_id());
+    }
+
+    found
+}
+
+#[test]
+fn unreadable_persistent_state_is_reported() {
+    let path = std::env::temp_dir().join(format!("carbide_persistent_state_{}", Uuid::new_v4()));
+    std::fs::write(&path, [0xff, 0xff, 0xff]).unwrap();
+
+    let mut ui = Ui::<()>::new([100.0, 100.0], 1.0);
+    let restored = ui.set_persistent_state_path(&path);
+
+    // The file is still used for saving the states.
+    let saved = ui.save_persistent_state();
+    let readable = Ui::<()>::new([100.0, 100.0], 1.0).set_persistent_state_path(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(restored.is_err());
+    assert!(saved.is_ok());
+    assert!(readable.is_ok());
+}
@@ -8,6 +8,8 @@ use uuid::Uuid;
 
 pub use carbide_core::layout::CrossAxisAlignment;
 pub use carbide_core::layout::Layout;
+pub use carbide_core::layout::LayoutDirection;
+pub use carbide_core::layout::MainAxisAlignment;
 pub use carbide_core::layout::layouter::Layouter;
 pub use carbide_core::state::state_ext::StateExt;
 pub use carbide_core::window::TWindow;
@@ -20,6 +22,7 @@ pub use crate::flags::Flags;
 pub use crate::focus::Focus;
 pub use crate::focus::Focusable;
 pub use crate::focus::Refocus;
+pub use crate::gesture::{LongPressGesture, PanGesture, PanValue, PinchGesture, PinchValue, RotateGesture, RotateValue, TapGesture};
 pub use crate::layout::basic_layouter::BasicLayouter;
 pub use crate::layout::layout::SingleChildLayout;
 pub use crate::position::Dimensions;
@@ -32,17 +35,29 @@ pub use crate::text::FontStyle;
 pub use crate::text::FontWeight;
 
 pub use self::common_widget::CommonWidget;
+pub use self::primitive::aspect_ratio::AspectRatio;
+pub use self::primitive::background::Background;
 pub use self::primitive::canvas::canvas::Canvas;
 pub use self::primitive::canvas::context::Context;
 pub use self::primitive::canvas::context::ContextAction;
 pub use self::primitive::environment_updating::EnvUpdating;
+pub use self::primitive::flex::*;
+pub use self::primitive::flexible_frame::FlexibleFrame;
 pub use self::primitive::foreach::ForEach;
 pub use self::primitive::frame::*;
+pub use self::primitive::geometry_reader::GeometryReader;
+pub use self::primitive::grid::*;
 pub use self::primitive::h_stack::*;
 pub use self::primitive::if_else::IfElse;
 pub use self::primitive::image::{self, Image};
+pub use self::primitive::keyboard_shortcut::KeyboardShortcut;
+pub use self::primitive::lazy_stack::LazyStack;
 pub use self::primitive::offset::Offset;
+pub use self::primitive::on_change::OnChange;
+pub use self::primitive::on_gesture::OnGesture;
+pub use self::primitive::on_hover::OnHover;
 pub use self::primitive::overlaid_layer::OverlaidLayer;
+pub use self::primitive::overlay::Overlay;
 pub use self::primitive::padding::Padding;
 pub use self::primitive::scroll::Scroll;
 pub use self::primitive::shape::capsule::Capsule;
@@ -52,10 +67,12 @@ pub use self::primitive::shape::rectangle::{self, Rectangle};
 pub use self::primitive::shape::rounded_rectangle::RoundedRectangle;
 pub use self::primitive::shared_state::SharedState;
 pub use self::primitive::spacer::Spacer;
+pub use self::primitive::split_view::SplitView;
 pub use self::primitive::text::{self, Text};
 pub use self::primitive::v_stack::*;
 pub use self::primitive::Widget;
 pub use self::primitive::widget::WidgetExt;
+pub use self::primitive::wrap_stack::WrapStack;
 pub use self::primitive::z_stack::*;
 pub use self::render::ChildRender;
 pub use self::types::corner_radii::CornerRadii;
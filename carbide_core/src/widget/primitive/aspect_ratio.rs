@@ -0,0 +1,118 @@
+use crate::prelude::*;
+use crate::widget::ChildRender;
+use crate::widget::types::scale_mode::ScaleMode;
+
+/// A widget sizing its child to the largest size with the given ratio between the width and the
+/// height that fits within the requested size, or the smallest covering it when filling.
+#[derive(Debug, Clone, Widget)]
+pub struct AspectRatio<GS> where GS: GlobalState {
+    id: Uuid,
+    child: Box<dyn Widget<GS>>,
+    position: Point,
+    dimension: Dimensions,
+    ratio: Scalar,
+    scale_mode: ScaleMode,
+}
+
+impl<GS: GlobalState> AspectRatio<GS> {
+    pub fn new(ratio: Scalar, scale_mode: ScaleMode, child: Box<dyn Widget<GS>>) -> Box<Self> {
+        Box::new(AspectRatio {
+            id: Uuid::new_v4(),
+            child,
+            position: [0.0, 0.0],
+            dimension: [0.0, 0.0],
+            ratio,
+            scale_mode,
+        })
+    }
+}
+
+impl<GS: GlobalState> WidgetExt<GS> for AspectRatio<GS> {}
+
+impl<GS: GlobalState> Layout<GS> for AspectRatio<GS> {
+    fn flexibility(&self) -> u32 {
+        self.child.flexibility()
+    }
+
+    fn calculate_size(&mut self, requested_size: Dimensions, env: &mut Environment<GS>) -> Dimensions {
+        let size = self.scale_mode.scale([self.ratio, 1.0], requested_size);
+
+        self.child.calculate_size_cached(size, env);
+
+        self.dimension = size;
+        self.dimension
+    }
+
+    fn position_children(&mut self, env: &mut Environment<GS>) {
+        let positioning = BasicLayouter::Center.position(env);
+        let position = self.position;
+        let dimension = self.dimension;
+
+        positioning(position, dimension, &mut self.child);
+
+        self.child.position_children(env);
+    }
+}
+
+impl<GS: GlobalState> CommonWidget<GS> for AspectRatio<GS> {
+    fn get_id(&self) -> Uuid {
+        self.id
+    }
+
+    fn set_id(&mut self, id: Uuid) {
+        self.id = id;
+    }
+
+    fn get_flag(&self) -> Flags {
+        Flags::EMPTY
+    }
+
+    fn get_children(&self) -> WidgetIter<GS> {
+        if self.child.get_flag() == Flags::PROXY {
+            self.child.get_children()
+        } else {
+            WidgetIter::single(self.child.deref())
+        }
+    }
+
+    fn get_children_mut(&mut self) -> WidgetIterMut<GS> {
+        if self.child.get_flag() == Flags::PROXY {
+            self.child.get_children_mut()
+        } else {
+            WidgetIterMut::single(self.child.deref_mut())
+        }
+    }
+
+    fn get_proxied_children(&mut self) -> WidgetIterMut<GS> {
+        WidgetIterMut::single(self.child.deref_mut())
+    }
+
+    fn get_proxied_children_rev(&mut self) -> WidgetIterMut<GS> {
+        WidgetIterMut::single(self.child.deref_mut())
+    }
+
+    fn get_position(&self) -> Point {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Dimensions) {
+        self.position = position;
+    }
+
+    fn get_dimension(&self) -> Dimensions {
+        self.dimension
+    }
+
+    fn set_dimension(&mut self, dimensions: Dimensions) {
+        self.dimension = dimensions
+    }
+}
+
+impl<GS: GlobalState> ChildRender for AspectRatio<GS> {}
+
+#[test]
+fn sizes_keep_the_ratio() {
+    assert_eq!(ScaleMode::Fit.scale([2.0, 1.0], [300.0, 100.0]), [200.0, 100.0]);
+    assert_eq!(ScaleMode::Fill.scale([2.0, 1.0], [300.0, 100.0]), [300.0, 150.0]);
+    assert_eq!(ScaleMode::Stretch.scale([2.0, 1.0], [300.0, 100.0]), [300.0, 100.0]);
+}
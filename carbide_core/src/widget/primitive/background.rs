@@ -0,0 +1,117 @@
+use crate::prelude::*;
+use crate::widget::ChildRender;
+
+/// A widget drawing a widget behind its child. The size is decided by the child alone, and the
+/// background is given the size of the child.
+#[derive(Debug, Clone, Widget)]
+pub struct Background<GS> where GS: GlobalState {
+    id: Uuid,
+    child: Box<dyn Widget<GS>>,
+    background: Box<dyn Widget<GS>>,
+    position: Point,
+    dimension: Dimensions,
+}
+
+impl<GS: GlobalState> Background<GS> {
+    pub fn new(child: Box<dyn Widget<GS>>, background: Box<dyn Widget<GS>>) -> Box<Self> {
+        Box::new(Background {
+            id: Uuid::new_v4(),
+            child,
+            background,
+            position: [0.0, 0.0],
+            dimension: [0.0, 0.0],
+        })
+    }
+}
+
+impl<GS: GlobalState> WidgetExt<GS> for Background<GS> {}
+
+impl<GS: GlobalState> Layout<GS> for Background<GS> {
+    fn flexibility(&self) -> u32 {
+        self.child.flexibility()
+    }
+
+    fn calculate_size(&mut self, requested_size: Dimensions, env: &mut Environment<GS>) -> Dimensions {
+        self.dimension = self.child.calculate_size_cached(requested_size, env);
+
+        self.background.calculate_size_cached(self.dimension, env);
+
+        self.dimension
+    }
+
+    fn position_children(&mut self, env: &mut Environment<GS>) {
+        let positioning = BasicLayouter::Center.position(env);
+        let position = self.position;
+        let dimension = self.dimension;
+
+        positioning(position, dimension, &mut self.background);
+        positioning(position, dimension, &mut self.child);
+
+        self.background.position_children(env);
+        self.child.position_children(env);
+    }
+}
+
+impl<GS: GlobalState> CommonWidget<GS> for Background<GS> {
+    fn get_id(&self) -> Uuid {
+        self.id
+    }
+
+    fn set_id(&mut self, id: Uuid) {
+        self.id = id;
+    }
+
+    fn get_flag(&self) -> Flags {
+        Flags::EMPTY
+    }
+
+    fn get_children(&self) -> WidgetIter<GS> {
+        vec![self.background.deref(), self.child.deref()]
+            .into_iter()
+            .rfold(WidgetIter::Empty, |acc, x| {
+                if x.get_flag() == Flags::PROXY {
+                    WidgetIter::Multi(Box::new(x.get_children()), Box::new(acc))
+                } else {
+                    WidgetIter::Single(x, Box::new(acc))
+                }
+            })
+    }
+
+    fn get_children_mut(&mut self) -> WidgetIterMut<GS> {
+        vec![self.background.deref_mut(), self.child.deref_mut()]
+            .into_iter()
+            .rfold(WidgetIterMut::Empty, |acc, x| {
+                if x.get_flag() == Flags::PROXY {
+                    WidgetIterMut::Multi(Box::new(x.get_children_mut()), Box::new(acc))
+                } else {
+                    WidgetIterMut::Single(x, Box::new(acc))
+                }
+            })
+    }
+
+    fn get_proxied_children(&mut self) -> WidgetIterMut<GS> {
+        WidgetIterMut::Single(self.background.deref_mut(), Box::new(WidgetIterMut::single(self.child.deref_mut())))
+    }
+
+    fn get_proxied_children_rev(&mut self) -> WidgetIterMut<GS> {
+        WidgetIterMut::Single(self.child.deref_mut(), Box::new(WidgetIterMut::single(self.background.deref_mut())))
+    }
+
+    fn get_position(&self) -> Point {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Dimensions) {
+        self.position = position;
+    }
+
+    fn get_dimension(&self) -> Dimensions {
+        self.dimension
+    }
+
+    fn set_dimension(&mut self, dimensions: Dimensions) {
+        self.dimension = dimensions
+    }
+}
+
+impl<GS: GlobalState> ChildRender for Background<GS> {}
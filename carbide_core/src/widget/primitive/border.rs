@@ -23,21 +23,21 @@ impl<GS: GlobalState> Layout<GS> for Border<GS> {
         let border_width = self.border_width as f64;
         let dimensions = [requested_size[0] - border_width - border_width, requested_size[1] - border_width - border_width];
 
-        let child_dimensions = self.child.calculate_size(dimensions, env);
+        let child_dimensions = self.child.calculate_size_cached(dimensions, env);
 
         self.dimension = [child_dimensions[0] + border_width + border_width, child_dimensions[1] + border_width + border_width];
 
         self.dimension
     }
 
-    fn position_children(&mut self) {
+    fn position_children(&mut self, env: &mut Environment<GS>) {
         let border_width = self.border_width as f64;
-        let positioning = BasicLayouter::Center.position();
+        let positioning = BasicLayouter::Center.position(env);
         let position = [self.position[0] + border_width, self.position[1] + border_width];
         let dimension = [self.dimension[0] - border_width - border_width, self.dimension[1] - border_width - border_width];
 
         positioning(position, dimension, &mut self.child);
-        self.child.position_children();
+        self.child.position_children(env);
     }
 }
 
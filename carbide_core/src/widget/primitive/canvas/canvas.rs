@@ -1,3 +1,7 @@
+use std::fmt;
+use std::fmt::Debug;
+
+use dyn_clone::DynClone;
 use lyon::algorithms::path::Path;
 use lyon::tessellation::{BuffersBuilder, FillOptions, FillTessellator, FillVertex, StrokeOptions, StrokeTessellator, StrokeVertex, VertexBuffers};
 
@@ -17,18 +21,32 @@ pub struct Canvas<GS> where GS: GlobalState {
     dimension: Dimensions,
     #[state] color: ColorState<GS>,
     //prim_store: Vec<Primitive>,
-    context: fn(OldRect, Context<GS>) -> Context<GS>,
+    context: Box<dyn ContextFn<GS>>,
+}
+
+/// The function drawing the content of a canvas. It receives the bounds of the canvas and the
+/// context to draw into.
+pub trait ContextFn<GS: GlobalState>: Fn(OldRect, Context<GS>) -> Context<GS> + DynClone {}
+
+impl<GS: GlobalState, F> ContextFn<GS> for F where F: Fn(OldRect, Context<GS>) -> Context<GS> + DynClone {}
+
+dyn_clone::clone_trait_object!(<GS: GlobalState> ContextFn<GS>);
+
+impl<GS: GlobalState> Debug for dyn ContextFn<GS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ContextFn")
+    }
 }
 
 impl<GS: GlobalState> Canvas<GS> {
-    pub fn initialize(context: fn(OldRect, Context<GS>) -> Context<GS>) -> Box<Self> {
+    pub fn initialize<F: Fn(OldRect, Context<GS>) -> Context<GS> + Clone + 'static>(context: F) -> Box<Self> {
         Box::new(Canvas {
             id: Uuid::new_v4(),
             position: [0.0, 0.0],
             dimension: [100.0, 100.0],
             color: EnvironmentColor::Accent.into(),
             //prim_store: vec![],
-            context,
+            context: Box::new(context),
         })
     }
 
@@ -171,5 +189,5 @@ impl<GS: GlobalState> Layout<GS> for Canvas<GS> {
         requested_size
     }
 
-    fn position_children(&mut self) {}
+    fn position_children(&mut self, env: &mut Environment<GS>) {}
 }
\ No newline at end of file
@@ -1,7 +1,9 @@
+use crate::event::event::Event;
 use crate::prelude::*;
 use crate::render::primitive_kind::PrimitiveKind;
 
 #[derive(Debug, Clone, Widget)]
+#[event(hit_test)]
 pub struct Clip<GS> where GS: GlobalState {
     id: Uuid,
     child: Box<dyn Widget<GS>>,
@@ -11,25 +13,32 @@ pub struct Clip<GS> where GS: GlobalState {
 
 impl<GS: GlobalState> WidgetExt<GS> for Clip<GS> {}
 
+impl<GS: GlobalState> Clip<GS> {
+    /// The parts of the child outside the clip are not drawn, and can not be hovered.
+    fn hit_test(&self, point: Point, path: &mut Vec<Uuid>) -> bool {
+        self.is_inside(point) && self.hit_test_default(point, path)
+    }
+}
+
 impl<GS: GlobalState> Layout<GS> for Clip<GS> {
     fn flexibility(&self) -> u32 {
         self.child.flexibility()
     }
 
     fn calculate_size(&mut self, requested_size: Dimensions, env: &mut Environment<GS>) -> Dimensions {
-        self.child.calculate_size(requested_size, env);
+        self.child.calculate_size_cached(requested_size, env);
         self.dimension = requested_size;
         requested_size
     }
 
-    fn position_children(&mut self) {
-        let positioning = BasicLayouter::Center.position();
+    fn position_children(&mut self, env: &mut Environment<GS>) {
+        let positioning = BasicLayouter::Center.position(env);
         let position = self.position;
         let dimension = self.dimension;
 
         positioning(position, dimension, &mut self.child);
 
-        self.child.position_children();
+        self.child.position_children(env);
     }
 }
 
@@ -13,6 +13,8 @@ pub struct EnvUpdating<GS> where GS: GlobalState {
     position: Point,
     dimension: Dimensions,
     envs_to_update: Vec<EnvironmentStateContainer<GS>>,
+    /// The layout directions set for the child in the latest layout.
+    layout_directions: Vec<LayoutDirection>,
 }
 
 #[derive(Debug, Clone)]
@@ -23,6 +25,7 @@ pub enum EnvironmentStateContainer<GS> where GS: GlobalState {
     Color { key: EnvironmentColor, value: ColorState<GS> },
     FontSize { key: EnvironmentFontSize, value: U32State<GS> },
     I32 { key: String, value: Box<dyn State<i32, GS>> },
+    LayoutDirection { value: Box<dyn State<LayoutDirection, GS>> },
 }
 
 impl<GS: GlobalState> EnvUpdating<GS> {
@@ -33,6 +36,7 @@ impl<GS: GlobalState> EnvUpdating<GS> {
             position: [0.0, 0.0],
             dimension: [100.0, 100.0],
             envs_to_update: vec![],
+            layout_directions: vec![],
         })
     }
 
@@ -43,6 +47,12 @@ impl<GS: GlobalState> EnvUpdating<GS> {
     fn sync_state(&mut self, env: &mut Environment<GS>, global_state: &GS) {
         self.insert_into_env(env, global_state);
 
+        // The children read the layout direction from the environment when sized, so they are
+        // sized again when it changes.
+        if self.layout_directions() != self.layout_directions {
+            env.invalidate_layout_of_tree(&*self);
+        }
+
         self.default_sync_state(env, global_state);
 
         self.remove_from_env(env);
@@ -56,10 +66,10 @@ impl<GS: GlobalState> EnvUpdating<GS> {
         self.remove_from_env(env);
     }
 
-    fn process_mouse_event(&mut self, event: &MouseEvent, consumed: &bool, env: &mut Environment<GS>, global_state: &mut GS) {
+    fn process_mouse_event(&mut self, event: &MouseEvent, env: &mut Environment<GS>, global_state: &mut GS) {
         self.insert_into_env(env, global_state);
 
-        self.process_mouse_event_default(event, consumed, env, global_state);
+        self.process_mouse_event_default(event, env, global_state);
 
         self.remove_from_env(env);
     }
@@ -111,9 +121,35 @@ impl<GS: GlobalState> EnvUpdating<GS> {
 
                     env.push(EnvironmentVariable::I32 { key: key.clone(), value: to_update })
                 }
+                EnvironmentStateContainer::LayoutDirection { value } => {
+                    let to_update = *value.get_value(env, global_state);
+
+                    env.push(EnvironmentVariable::LayoutDirection { value: to_update })
+                }
             }
         }
     }
+
+    fn layout_directions(&self) -> Vec<LayoutDirection> {
+        self.envs_to_update.iter()
+            .filter_map(|env_to_update| match env_to_update {
+                EnvironmentStateContainer::LayoutDirection { value } => Some(*value.get_latest_value()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Insert the values affecting the layout, using the latest values of the states because the
+    /// global state is not available while laying out.
+    fn insert_layout_into_env(&mut self, env: &mut Environment<GS>) -> usize {
+        self.layout_directions = self.layout_directions();
+
+        for layout_direction in &self.layout_directions {
+            env.push(EnvironmentVariable::LayoutDirection { value: *layout_direction });
+        }
+
+        self.layout_directions.len()
+    }
 }
 
 
@@ -123,17 +159,24 @@ impl<GS: GlobalState> Layout<GS> for EnvUpdating<GS> {
     }
 
     fn calculate_size(&mut self, requested_size: Dimensions, env: &mut Environment<GS>) -> Dimensions {
-        self.dimension = self.child.calculate_size(requested_size, env);
+        let inserted = self.insert_layout_into_env(env);
+
+        self.dimension = self.child.calculate_size_cached(requested_size, env);
+
+        for _ in 0..inserted {
+            env.pop();
+        }
+
         self.dimension
     }
 
-    fn position_children(&mut self) {
-        let positioning = BasicLayouter::Center.position();
+    fn position_children(&mut self, env: &mut Environment<GS>) {
+        let positioning = BasicLayouter::Center.position(env);
         let position = self.position;
         let dimension = self.dimension;
 
         positioning(position, dimension, &mut self.child);
-        self.child.position_children();
+        self.child.position_children(env);
     }
 }
 
@@ -0,0 +1,492 @@
+use std::ops::Range;
+
+use crate::layout::{CrossAxisAlignment, MainAxisAlignment};
+use crate::prelude::*;
+use crate::widget::ChildRender;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FlexDirection {
+    Row, Column
+}
+
+impl FlexDirection {
+    /// The index of the main axis in points and dimensions.
+    fn main_axis(&self) -> usize {
+        match self {
+            FlexDirection::Row => 0,
+            FlexDirection::Column => 1,
+        }
+    }
+}
+
+/// The size of a flex item along the main axis, before the free space is distributed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FlexBasis {
+    /// The size the child chooses when offered the full size of the container.
+    Auto,
+    Points(Scalar),
+    /// A percentage of the size of the container along the main axis.
+    Percent(Scalar),
+}
+
+/// A child of a `Flex` container along with how it is sized. By default an item does not grow,
+/// shrinks with a factor of 1 and has an automatic basis. Spacers with an automatic basis are
+/// given a basis of 0 and grow with a factor of at least 1.
+#[derive(Debug, Clone)]
+pub struct FlexItem<GS> where GS: GlobalState {
+    child: Box<dyn Widget<GS>>,
+    properties: FlexProperties,
+}
+
+#[derive(Debug, Clone)]
+struct FlexProperties {
+    grow: Scalar,
+    shrink: Scalar,
+    basis: FlexBasis,
+    align_self: Option<CrossAxisAlignment>,
+}
+
+impl<GS: GlobalState> FlexItem<GS> {
+    pub fn new(child: Box<dyn Widget<GS>>) -> FlexItem<GS> {
+        FlexItem {
+            child,
+            properties: FlexProperties {
+                grow: 0.0,
+                shrink: 1.0,
+                basis: FlexBasis::Auto,
+                align_self: None,
+            },
+        }
+    }
+
+    /// The share of the free space of the line given to this item.
+    pub fn grow(mut self, grow: Scalar) -> FlexItem<GS> {
+        self.properties.grow = grow;
+        self
+    }
+
+    /// How much this item shrinks, relative to its basis, when the line overflows.
+    pub fn shrink(mut self, shrink: Scalar) -> FlexItem<GS> {
+        self.properties.shrink = shrink;
+        self
+    }
+
+    pub fn basis(mut self, basis: FlexBasis) -> FlexItem<GS> {
+        self.properties.basis = basis;
+        self
+    }
+
+    /// Override the cross axis alignment of the container for this item.
+    pub fn align_self(mut self, alignment: CrossAxisAlignment) -> FlexItem<GS> {
+        self.properties.align_self = Some(alignment);
+        self
+    }
+}
+
+impl<GS: GlobalState> From<Box<dyn Widget<GS>>> for FlexItem<GS> {
+    fn from(child: Box<dyn Widget<GS>>) -> Self {
+        FlexItem::new(child)
+    }
+}
+
+/// A container laying out its children along a main axis, distributing the free space between
+/// them based on their grow and shrink factors. When wrapping is enabled the children that do
+/// not fit are moved onto new lines along the cross axis.
+#[derive(Debug, Clone, Widget)]
+pub struct Flex<GS> where GS: GlobalState {
+    id: Uuid,
+    items: Vec<FlexItem<GS>>,
+    position: Point,
+    dimension: Dimensions,
+    direction: FlexDirection,
+    wrap: bool,
+    spacing: Scalar,
+    line_spacing: Scalar,
+    main_axis_alignment: MainAxisAlignment,
+    cross_axis_alignment: CrossAxisAlignment,
+    /// The offset of each child from the position of the container, calculated when sizing.
+    offsets: Vec<Point>,
+}
+
+impl<GS: GlobalState> WidgetExt<GS> for Flex<GS> {}
+
+impl<GS: GlobalState> Flex<GS> {
+    pub fn row(items: Vec<FlexItem<GS>>) -> Box<Self> {
+        Flex::new(FlexDirection::Row, items)
+    }
+
+    pub fn column(items: Vec<FlexItem<GS>>) -> Box<Self> {
+        Flex::new(FlexDirection::Column, items)
+    }
+
+    pub fn new(direction: FlexDirection, items: Vec<FlexItem<GS>>) -> Box<Self> {
+        Box::new(Flex {
+            id: Uuid::new_v4(),
+            items,
+            position: [0.0, 0.0],
+            dimension: [100.0, 100.0],
+            direction,
+            wrap: false,
+            spacing: 10.0,
+            line_spacing: 10.0,
+            main_axis_alignment: MainAxisAlignment::Start,
+            cross_axis_alignment: CrossAxisAlignment::Center,
+            offsets: vec![],
+        })
+    }
+
+    /// Move the children that do not fit on the current line onto a new line.
+    pub fn wrap(mut self) -> Box<Self> {
+        self.wrap = true;
+        Box::new(self)
+    }
+
+    /// The space between the children of a line.
+    pub fn spacing(mut self, spacing: f64) -> Box<Self> {
+        self.spacing = spacing;
+        Box::new(self)
+    }
+
+    /// The space between the lines, when wrapping.
+    pub fn line_spacing(mut self, spacing: f64) -> Box<Self> {
+        self.line_spacing = spacing;
+        Box::new(self)
+    }
+
+    pub fn main_axis_alignment(mut self, alignment: MainAxisAlignment) -> Box<Self> {
+        self.main_axis_alignment = alignment;
+        Box::new(self)
+    }
+
+    pub fn cross_axis_alignment(mut self, alignment: CrossAxisAlignment) -> Box<Self> {
+        self.cross_axis_alignment = alignment;
+        Box::new(self)
+    }
+}
+
+/// The children of the items along with their properties. The children of proxy widgets, like
+/// `ForEach`, share the properties of their item.
+fn flex_children<GS: GlobalState>(items: &mut Vec<FlexItem<GS>>) -> Vec<(FlexProperties, &mut dyn Widget<GS>)> {
+    let mut children = vec![];
+
+    for item in items {
+        let mut properties = item.properties.clone();
+
+        if item.child.get_flag() == Flags::PROXY {
+            for child in item.child.get_children_mut() {
+                children.push((properties.clone(), child));
+            }
+        } else {
+            if item.child.get_flag() == Flags::SPACER && properties.basis == FlexBasis::Auto {
+                properties.basis = FlexBasis::Points(0.0);
+                properties.grow = properties.grow.max(1.0);
+            }
+
+            children.push((properties, item.child.deref_mut()));
+        }
+    }
+
+    children
+}
+
+/// Split the items into lines, such that the bases of each line fit within the available size.
+/// A line always contains at least one item.
+pub(crate) fn break_lines(bases: &[Scalar], available: Scalar, spacing: Scalar, wrap: bool) -> Vec<Range<usize>> {
+    let mut lines = vec![];
+    let mut start = 0;
+    let mut line_length = 0.0;
+
+    for (index, basis) in bases.iter().enumerate() {
+        if index == start {
+            line_length = *basis;
+        } else if wrap && line_length + spacing + basis > available {
+            lines.push(start..index);
+            start = index;
+            line_length = *basis;
+        } else {
+            line_length += spacing + basis;
+        }
+    }
+
+    if start < bases.len() {
+        lines.push(start..bases.len());
+    }
+
+    lines
+}
+
+/// Distribute the free space of a line. Items that are not frozen grow by their share of the
+/// positive free space, or shrink by their share of the negative free space scaled by their basis.
+fn distribute(bases: &[Scalar], grow: &[Scalar], shrink: &[Scalar], frozen: &[Option<Scalar>], free: Scalar) -> Vec<Scalar> {
+    let unfrozen = || (0..bases.len()).filter(|index| frozen[*index].is_none());
+
+    let total_grow: Scalar = unfrozen().map(|index| grow[index]).sum();
+    let total_shrink: Scalar = unfrozen().map(|index| shrink[index] * bases[index]).sum();
+
+    (0..bases.len()).map(|index| {
+        if let Some(size) = frozen[index] {
+            size
+        } else if free > 0.0 && total_grow > 0.0 {
+            bases[index] + free * grow[index] / total_grow
+        } else if free < 0.0 && total_shrink > 0.0 {
+            (bases[index] + free * shrink[index] * bases[index] / total_shrink).max(0.0)
+        } else {
+            bases[index]
+        }
+    }).collect()
+}
+
+impl<GS: GlobalState> Layout<GS> for Flex<GS> {
+    fn flexibility(&self) -> u32 {
+        1
+    }
+
+    fn calculate_size(&mut self, requested_size: Dimensions, env: &mut Environment<GS>) -> Dimensions {
+        let main = self.direction.main_axis();
+        let cross = 1 - main;
+        let spacing = self.spacing;
+
+        let mut children = flex_children(&mut self.items);
+
+        let bases: Vec<Scalar> = children.iter_mut().map(|(properties, child)| {
+            match properties.basis {
+                FlexBasis::Auto => child.calculate_size_cached(requested_size, env)[main],
+                FlexBasis::Points(points) => points,
+                FlexBasis::Percent(percent) => requested_size[main] * percent / 100.0,
+            }
+        }).collect();
+
+        let lines = break_lines(&bases, requested_size[main], spacing, self.wrap);
+
+        let mut sizes = vec![[0.0, 0.0]; children.len()];
+
+        for line in &lines {
+            let line_bases = &bases[line.clone()];
+            let grow: Vec<Scalar> = children[line.clone()].iter().map(|(properties, _)| properties.grow).collect();
+            let shrink: Vec<Scalar> = children[line.clone()].iter().map(|(properties, _)| properties.shrink).collect();
+            let gaps = spacing * (line.len() - 1) as Scalar;
+
+            // Children choosing a size different from the size they are offered are frozen at
+            // their chosen size, and the free space is distributed again between the rest.
+            let mut frozen: Vec<Option<Scalar>> = vec![None; line.len()];
+
+            loop {
+                let used: Scalar = (0..line.len()).map(|index| frozen[index].unwrap_or(line_bases[index])).sum();
+                let free = requested_size[main] - gaps - used;
+                let targets = distribute(line_bases, &grow, &shrink, &frozen, free);
+
+                let mut changed = false;
+
+                for (index, target) in targets.iter().enumerate() {
+                    if frozen[index].is_some() {
+                        continue;
+                    }
+
+                    let mut size_for_child = requested_size;
+                    size_for_child[main] = *target;
+
+                    let chosen_size = children[line.start + index].1.calculate_size_cached(size_for_child, env);
+                    sizes[line.start + index] = chosen_size;
+
+                    if (chosen_size[main] - target).abs() > 0.5 {
+                        frozen[index] = Some(chosen_size[main]);
+                        changed = true;
+                    }
+                }
+
+                if !changed || frozen.iter().all(|size| size.is_some()) {
+                    break;
+                }
+            }
+        }
+
+        let alignments: Vec<CrossAxisAlignment> = children.iter()
+            .map(|(properties, _)| properties.align_self.clone().unwrap_or(self.cross_axis_alignment.clone()))
+            .collect();
+
+        drop(children);
+
+        let line_lengths: Vec<Scalar> = lines.iter()
+            .map(|line| sizes[line.clone()].iter().map(|size| size[main]).sum::<Scalar>() + spacing * (line.len() - 1) as Scalar)
+            .collect();
+        let line_crosses: Vec<Scalar> = lines.iter()
+            .map(|line| sizes[line.clone()].iter().map(|size| size[cross]).fold(0.0, Scalar::max))
+            .collect();
+
+        let longest_line = line_lengths.iter().cloned().fold(0.0, Scalar::max);
+        let main_size = if self.main_axis_alignment == MainAxisAlignment::Start {
+            longest_line
+        } else {
+            longest_line.max(requested_size[main])
+        };
+
+        let mut offsets = vec![[0.0, 0.0]; sizes.len()];
+        let mut cross_offset = 0.0;
+
+        for ((line, line_length), line_cross) in lines.iter().zip(line_lengths).zip(line_crosses.iter()) {
+            let free = (main_size - line_length).max(0.0);
+            let count = line.len() as Scalar;
+
+            let (mut main_offset, extra_spacing) = match self.main_axis_alignment {
+                MainAxisAlignment::Start => (0.0, 0.0),
+                MainAxisAlignment::Center => (free / 2.0, 0.0),
+                MainAxisAlignment::End => (free, 0.0),
+                MainAxisAlignment::SpaceBetween if count > 1.0 => (0.0, free / (count - 1.0)),
+                MainAxisAlignment::SpaceBetween => (0.0, 0.0),
+                MainAxisAlignment::SpaceAround => (free / count / 2.0, free / count),
+                MainAxisAlignment::SpaceEvenly => (free / (count + 1.0), free / (count + 1.0)),
+            };
+
+            for index in line.clone() {
+                let cross_in_line = match alignments[index] {
+                    CrossAxisAlignment::Start => 0.0,
+                    CrossAxisAlignment::Center => (line_cross - sizes[index][cross]) / 2.0,
+                    CrossAxisAlignment::End => line_cross - sizes[index][cross],
+                };
+
+                offsets[index][main] = main_offset;
+                offsets[index][cross] = cross_offset + cross_in_line;
+
+                main_offset += sizes[index][main] + spacing + extra_spacing;
+            }
+
+            cross_offset += line_cross + self.line_spacing;
+        }
+
+        let cross_size = line_crosses.iter().sum::<Scalar>() + self.line_spacing * (lines.len().max(1) - 1) as Scalar;
+
+        self.offsets = offsets;
+        self.dimension[main] = main_size;
+        self.dimension[cross] = cross_size;
+
+        self.dimension
+    }
+
+    fn position_children(&mut self, env: &mut Environment<GS>) {
+        let position = self.position;
+        let width = self.dimension[0];
+        let layout_direction = env.get_layout_direction();
+
+        for ((_, child), offset) in flex_children(&mut self.items).into_iter().zip(self.offsets.iter()) {
+            // The offsets are from the leading edge, which is the right edge in a right to left
+            // layout.
+            let x = match layout_direction {
+                LayoutDirection::LeftToRight => position[0] + offset[0],
+                LayoutDirection::RightToLeft => position[0] + width - offset[0] - child.get_width(),
+            };
+
+            child.set_position([x, position[1] + offset[1]]);
+            child.position_children(env);
+        }
+    }
+}
+
+impl<GS: GlobalState> CommonWidget<GS> for Flex<GS> {
+    fn get_id(&self) -> Uuid {
+        self.id
+    }
+
+    fn set_id(&mut self, id: Uuid) {
+        self.id = id;
+    }
+
+    fn get_flag(&self) -> Flags {
+        Flags::EMPTY
+    }
+
+    fn get_children(&self) -> WidgetIter<GS> {
+        self.items
+            .iter()
+            .map(|x| x.child.deref())
+            .rfold(WidgetIter::Empty, |acc, x| {
+                if x.get_flag() == Flags::PROXY {
+                    WidgetIter::Multi(Box::new(x.get_children()), Box::new(acc))
+                } else {
+                    WidgetIter::Single(x, Box::new(acc))
+                }
+            })
+    }
+
+    fn get_children_mut(&mut self) -> WidgetIterMut<GS> {
+        self.items
+            .iter_mut()
+            .map(|x| x.child.deref_mut())
+            .rfold(WidgetIterMut::Empty, |acc, x| {
+                if x.get_flag() == Flags::PROXY {
+                    WidgetIterMut::Multi(Box::new(x.get_children_mut()), Box::new(acc))
+                } else {
+                    WidgetIterMut::Single(x, Box::new(acc))
+                }
+            })
+    }
+
+    fn get_proxied_children(&mut self) -> WidgetIterMut<GS> {
+        self.items.iter_mut()
+            .map(|x| x.child.deref_mut())
+            .rfold(WidgetIterMut::Empty, |acc, x| {
+                WidgetIterMut::Single(x, Box::new(acc))
+            })
+    }
+
+    fn get_proxied_children_rev(&mut self) -> WidgetIterMut<GS> {
+        self.items.iter_mut()
+            .map(|x| x.child.deref_mut())
+            .fold(WidgetIterMut::Empty, |acc, x| {
+                WidgetIterMut::Single(x, Box::new(acc))
+            })
+    }
+
+    fn get_position(&self) -> Point {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Dimensions) {
+        self.position = position;
+    }
+
+    fn get_dimension(&self) -> Dimensions {
+        self.dimension
+    }
+
+    fn set_dimension(&mut self, dimensions: Dimensions) {
+        self.dimension = dimensions
+    }
+}
+
+impl<GS: GlobalState> ChildRender for Flex<GS> {}
+
+#[test]
+fn free_space_is_distributed_by_grow_and_shrink() {
+    let bases = [60.0, 0.0, 20.0];
+    let grow = [0.0, 1.0, 3.0];
+    let shrink = [1.0, 1.0, 1.0];
+
+    assert_eq!(distribute(&bases, &grow, &shrink, &[None, None, None], 40.0), vec![60.0, 10.0, 50.0]);
+    assert_eq!(distribute(&bases, &grow, &shrink, &[None, None, None], -40.0), vec![30.0, 0.0, 10.0]);
+    assert_eq!(distribute(&bases, &grow, &shrink, &[None, Some(15.0), None], 40.0), vec![60.0, 15.0, 60.0]);
+
+    assert_eq!(break_lines(&[40.0, 40.0, 40.0], 100.0, 10.0, true), vec![0..2, 2..3]);
+    assert_eq!(break_lines(&[40.0, 40.0, 40.0], 100.0, 10.0, false), vec![0..3]);
+}
+
+#[test]
+fn children_wrap_onto_a_new_line_when_the_width_is_exceeded() {
+    let mut env = Environment::<()>::new(vec![], [100.0, 100.0], 1.0);
+    let children: Vec<Box<dyn Widget<()>>> = vec![
+        Rectangle::initialize(vec![]).frame(40.0, 10.0),
+        Rectangle::initialize(vec![]).frame(40.0, 10.0),
+        Rectangle::initialize(vec![]).frame(40.0, 10.0),
+    ];
+
+    let mut flex = Flex::row(children.into_iter().map(FlexItem::from).collect())
+        .wrap()
+        .spacing(10.0)
+        .line_spacing(10.0);
+
+    assert_eq!(flex.calculate_size([100.0, 100.0], &mut env), [90.0, 30.0]);
+    flex.set_position([0.0, 0.0]);
+    flex.position_children(&mut env);
+
+    let positions: Vec<Point> = flex.get_children().map(|child| child.get_position()).collect();
+    assert_eq!(positions, vec![[0.0, 0.0], [50.0, 0.0], [0.0, 20.0]]);
+}
@@ -0,0 +1,191 @@
+use crate::prelude::*;
+use crate::widget::ChildRender;
+
+/// A frame constraining the size of its child between a minimum and a maximum size along each
+/// axis. A frame with both a minimum and a maximum takes the requested size clamped to the bounds,
+/// which together with a maximum of `INFINITY` makes it fill the available space. A frame with
+/// only one of the bounds takes the size of its child clamped to the bound. The ideal size is
+/// requested from the child instead of an infinite requested size.
+#[derive(Debug, Clone, Widget)]
+pub struct FlexibleFrame<GS> where GS: GlobalState {
+    id: Uuid,
+    child: Box<dyn Widget<GS>>,
+    position: Point,
+    dimension: Dimensions,
+    min: [Option<Scalar>; 2],
+    ideal: [Option<Scalar>; 2],
+    max: [Option<Scalar>; 2],
+}
+
+impl<GS: GlobalState> FlexibleFrame<GS> {
+    pub fn new(child: Box<dyn Widget<GS>>) -> Box<Self> {
+        Box::new(FlexibleFrame {
+            id: Uuid::new_v4(),
+            child,
+            position: [0.0, 0.0],
+            dimension: [0.0, 0.0],
+            min: [None, None],
+            ideal: [None, None],
+            max: [None, None],
+        })
+    }
+
+    pub fn min_width(mut self, width: Scalar) -> Box<Self> {
+        self.min[0] = Some(width);
+        Box::new(self)
+    }
+
+    pub fn ideal_width(mut self, width: Scalar) -> Box<Self> {
+        self.ideal[0] = Some(width);
+        Box::new(self)
+    }
+
+    pub fn max_width(mut self, width: Scalar) -> Box<Self> {
+        self.max[0] = Some(width);
+        Box::new(self)
+    }
+
+    pub fn min_height(mut self, height: Scalar) -> Box<Self> {
+        self.min[1] = Some(height);
+        Box::new(self)
+    }
+
+    pub fn ideal_height(mut self, height: Scalar) -> Box<Self> {
+        self.ideal[1] = Some(height);
+        Box::new(self)
+    }
+
+    pub fn max_height(mut self, height: Scalar) -> Box<Self> {
+        self.max[1] = Some(height);
+        Box::new(self)
+    }
+}
+
+impl<GS: GlobalState> WidgetExt<GS> for FlexibleFrame<GS> {}
+
+fn clamp(value: Scalar, min: Option<Scalar>, max: Option<Scalar>) -> Scalar {
+    // The minimum wins if it is larger than the maximum.
+    value.min(max.unwrap_or(Scalar::INFINITY)).max(min.unwrap_or(Scalar::NEG_INFINITY))
+}
+
+/// The size requested from the child along an axis.
+fn proposed_size(requested: Scalar, min: Option<Scalar>, ideal: Option<Scalar>, max: Option<Scalar>) -> Scalar {
+    let requested = match ideal {
+        Some(ideal) if requested.is_infinite() => ideal,
+        _ => requested,
+    };
+
+    clamp(requested, min, max)
+}
+
+/// The size of the frame along an axis, given the size requested from the child and the size
+/// chosen by the child.
+fn resolved_size(proposed: Scalar, chosen: Scalar, min: Option<Scalar>, max: Option<Scalar>) -> Scalar {
+    match (min, max) {
+        (Some(_), Some(_)) => proposed,
+        _ => clamp(chosen, min, max),
+    }
+}
+
+impl<GS: GlobalState> Layout<GS> for FlexibleFrame<GS> {
+    fn flexibility(&self) -> u32 {
+        self.child.flexibility()
+    }
+
+    fn calculate_size(&mut self, requested_size: Dimensions, env: &mut Environment<GS>) -> Dimensions {
+        let proposed = [
+            proposed_size(requested_size[0], self.min[0], self.ideal[0], self.max[0]),
+            proposed_size(requested_size[1], self.min[1], self.ideal[1], self.max[1]),
+        ];
+
+        let chosen = self.child.calculate_size_cached(proposed, env);
+
+        self.dimension = [
+            resolved_size(proposed[0], chosen[0], self.min[0], self.max[0]),
+            resolved_size(proposed[1], chosen[1], self.min[1], self.max[1]),
+        ];
+
+        self.dimension
+    }
+
+    fn position_children(&mut self, env: &mut Environment<GS>) {
+        let positioning = BasicLayouter::Center.position(env);
+        let position = self.position;
+        let dimension = self.dimension;
+
+        positioning(position, dimension, &mut self.child);
+
+        self.child.position_children(env);
+    }
+}
+
+impl<GS: GlobalState> CommonWidget<GS> for FlexibleFrame<GS> {
+    fn get_id(&self) -> Uuid {
+        self.id
+    }
+
+    fn set_id(&mut self, id: Uuid) {
+        self.id = id;
+    }
+
+    fn get_flag(&self) -> Flags {
+        Flags::EMPTY
+    }
+
+    fn get_children(&self) -> WidgetIter<GS> {
+        if self.child.get_flag() == Flags::PROXY {
+            self.child.get_children()
+        } else {
+            WidgetIter::single(self.child.deref())
+        }
+    }
+
+    fn get_children_mut(&mut self) -> WidgetIterMut<GS> {
+        if self.child.get_flag() == Flags::PROXY {
+            self.child.get_children_mut()
+        } else {
+            WidgetIterMut::single(self.child.deref_mut())
+        }
+    }
+
+    fn get_proxied_children(&mut self) -> WidgetIterMut<GS> {
+        WidgetIterMut::single(self.child.deref_mut())
+    }
+
+    fn get_proxied_children_rev(&mut self) -> WidgetIterMut<GS> {
+        WidgetIterMut::single(self.child.deref_mut())
+    }
+
+    fn get_position(&self) -> Point {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Dimensions) {
+        self.position = position;
+    }
+
+    fn get_dimension(&self) -> Dimensions {
+        self.dimension
+    }
+
+    fn set_dimension(&mut self, dimensions: Dimensions) {
+        self.dimension = dimensions
+    }
+}
+
+impl<GS: GlobalState> ChildRender for FlexibleFrame<GS> {}
+
+#[test]
+fn sizes_are_kept_within_bounds() {
+    use crate::widget::INFINITY;
+
+    // At least 200 wide, at most the available width.
+    assert_eq!(resolved_size(proposed_size(500.0, Some(200.0), None, Some(INFINITY)), 120.0, Some(200.0), Some(INFINITY)), 500.0);
+    assert_eq!(resolved_size(proposed_size(150.0, Some(200.0), None, Some(INFINITY)), 120.0, Some(200.0), Some(INFINITY)), 200.0);
+
+    // At most 600 wide, otherwise the size of the child.
+    assert_eq!(proposed_size(1000.0, None, None, Some(600.0)), 600.0);
+    assert_eq!(resolved_size(600.0, 450.0, None, Some(600.0)), 450.0);
+
+    assert_eq!(proposed_size(INFINITY, None, Some(300.0), None), 300.0);
+}
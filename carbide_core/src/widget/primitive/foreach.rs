@@ -1,6 +1,6 @@
 #![allow(unsafe_code)]
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 use std::hash::Hash;
 
@@ -8,9 +8,11 @@ use fxhash::{FxBuildHasher, FxHashMap};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
+use crate::event::event::is_on_dispatch_route;
 use crate::event_handler::{KeyboardEvent, MouseEvent};
 use crate::prelude::*;
 use crate::state::state::CommonState;
+use crate::state::state_ext::StateExt;
 use crate::widget::render::ChildRender;
 
 pub trait ForEachDelegate: Clone + PartialEq + Eq + Hash + Debug + Serialize + DeserializeOwned + Default {}
@@ -37,13 +39,29 @@ pub struct ForEach<GS, T> where GS: GlobalState, T: ForEachDelegate + 'static {
 
 impl<GS: GlobalState, T: ForEachDelegate + 'static> WidgetExt<GS> for ForEach<GS, T> {}
 
+/// Clone the delegate, giving the clone and its descendants new ids such that the widgets
+/// created for different ids can be told apart, for example when finding the hovered widget.
+pub(crate) fn clone_delegate<GS: GlobalState>(delegate: &Box<dyn Widget<GS>>) -> Box<dyn Widget<GS>> {
+    let mut clone = Clone::clone(delegate);
+    assign_new_ids(clone.deref_mut());
+    clone
+}
+
+fn assign_new_ids<GS: GlobalState>(widget: &mut dyn Widget<GS>) {
+    widget.set_id(Uuid::new_v4());
+
+    for child in widget.get_proxied_children() {
+        assign_new_ids(child);
+    }
+}
+
 impl<GS: GlobalState, T: ForEachDelegate + 'static> ForEach<GS, T> {
     pub fn new<K: Into<TState<Vec<T>, GS>>>(ids: K, delegate: Box<dyn Widget<GS>>) -> Box<Self> {
         let ids = ids.into();
         let mut map = HashMap::with_hasher(FxBuildHasher::default());
 
         for i in ids.get_latest_value() {
-            map.insert(i.clone(), Clone::clone(&delegate));
+            map.insert(i.clone(), clone_delegate(&delegate));
         }
 
         Box::new(Self {
@@ -59,6 +77,21 @@ impl<GS: GlobalState, T: ForEachDelegate + 'static> ForEach<GS, T> {
         })
     }
 
+    /// Show the delegate for each element of the model, identified by `id_of`. The delegate is
+    /// given a state of its element that keeps referring to the same element when elements
+    /// before it are inserted or removed.
+    pub fn keyed<E, V, M, D>(model: V, id_of: M, delegate: D) -> Box<Self>
+        where E: StateContract + 'static, V: Into<TState<Vec<E>, GS>>, M: Fn(&E) -> T + Clone + 'static, D: FnOnce(TState<E, GS>) -> Box<dyn Widget<GS>> {
+        let model = model.into();
+        let id_state = CommonState::new_local_with_key(&T::default());
+
+        let element = KeyedVecState::new(model.clone(), id_state.clone(), id_of.clone());
+        let ids = model.mapped(move |elements: &Vec<E>| elements.iter().map(|element| id_of(element)).collect::<Vec<T>>());
+
+        ForEach::new(ids, delegate(element.into()))
+            .id_state(Box::new(id_state))
+    }
+
     pub fn id_state(mut self, state: Box<dyn State<T, GS>>) -> Box<Self> {
         self.id_state = state;
         Box::new(self)
@@ -74,6 +107,17 @@ impl<GS: GlobalState, T: ForEachDelegate + 'static> ForEach<GS, T> {
         Box::new(self)
     }
 
+    /// Remove the children of ids that are no longer in the model, such that their states are
+    /// reset if the id is added again.
+    fn remove_unused_children(&mut self) {
+        let ids = self.ids.get_latest_value();
+
+        if self.children_map.len() > ids.len() {
+            let ids: HashSet<&T> = ids.iter().collect();
+            self.children_map.retain(|id, _| ids.contains(id));
+        }
+    }
+
     fn sync_state(&mut self, env: &mut Environment<GS>, global_state: &GS) {
         self.update_all_widget_state(env, global_state);
 
@@ -86,7 +130,9 @@ impl<GS: GlobalState, T: ForEachDelegate + 'static> ForEach<GS, T> {
         let index_key = self.index_state.get_key().unwrap().clone();
 
         for (i, child) in self.get_proxied_children().enumerate() {
-            env.insert_local_state_from_key_value(&id_key, &ids.get_value(env, global_state)[i]);
+            if let Some(id) = ids.get_value(env, global_state).get(i) {
+                env.insert_local_state_from_key_value(&id_key, id);
+            }
             env.insert_local_state_from_key_value(&index_key, &(i + initial_offset));
 
             child.sync_state(env, global_state)
@@ -95,7 +141,7 @@ impl<GS: GlobalState, T: ForEachDelegate + 'static> ForEach<GS, T> {
         self.update_local_widget_state(env);
     }
 
-    fn process_mouse_event(&mut self, event: &MouseEvent, consumed: &bool, env: &mut Environment<GS>, global_state: &mut GS) {
+    fn process_mouse_event(&mut self, event: &MouseEvent, env: &mut Environment<GS>, global_state: &mut GS) {
         self.update_all_widget_state(env, global_state);
 
         self.insert_local_state(env);
@@ -105,13 +151,17 @@ impl<GS: GlobalState, T: ForEachDelegate + 'static> ForEach<GS, T> {
         let initial_offset = *self.index_offset.get_latest_value();
         let id_key = self.id_state.get_key().unwrap().clone();
         let index_key = self.index_state.get_key().unwrap().clone();
+        let along_path = env.is_dispatching_along_path();
 
         for (i, child) in self.get_proxied_children().enumerate() {
-            env.insert_local_state_from_key_value(&id_key, &ids.get_value(env, global_state)[i]);
+            if along_path && !is_on_dispatch_route(child, env) { continue }
+
+            if let Some(id) = ids.get_value(env, global_state).get(i) {
+                env.insert_local_state_from_key_value(&id_key, id);
+            }
             env.insert_local_state_from_key_value(&index_key, &(i + initial_offset));
 
-            child.process_mouse_event(event, &consumed, env, global_state);
-            if *consumed { return () }
+            child.process_mouse_event(event, env, global_state);
         }
 
         self.update_local_widget_state(env)
@@ -127,9 +177,14 @@ impl<GS: GlobalState, T: ForEachDelegate + 'static> ForEach<GS, T> {
         let initial_offset = *self.index_offset.get_latest_value();
         let id_key = self.id_state.get_key().unwrap().clone();
         let index_key = self.index_state.get_key().unwrap().clone();
+        let along_path = env.is_dispatching_along_path();
 
         for (i, child) in self.get_proxied_children().enumerate() {
-            env.insert_local_state_from_key_value(&id_key, &ids.get_value(env, global_state)[i]);
+            if along_path && !is_on_dispatch_route(child, env) { continue }
+
+            if let Some(id) = ids.get_value(env, global_state).get(i) {
+                env.insert_local_state_from_key_value(&id_key, id);
+            }
             env.insert_local_state_from_key_value(&index_key, &(i + initial_offset));
 
             child.process_keyboard_event(event, env, global_state);
@@ -174,7 +229,7 @@ impl<GS: GlobalState, T: ForEachDelegate> CommonWidget<GS> for ForEach<GS, T> {
         for id in self.ids.get_latest_value().iter().rev() {
             let contains = self.children_map.contains_key(id).clone();
             if !contains {
-                self.children_map.insert(id.clone(), Clone::clone(&self.delegate));
+                self.children_map.insert(id.clone(), clone_delegate(&self.delegate));
             }
         }
 
@@ -195,12 +250,14 @@ impl<GS: GlobalState, T: ForEachDelegate> CommonWidget<GS> for ForEach<GS, T> {
     }
 
     fn get_proxied_children(&mut self) -> WidgetIterMut<GS> {
+        self.remove_unused_children();
+
         let mut w = WidgetIterMut::Empty;
 
         for id in self.ids.get_latest_value().iter().rev() {
             let contains = self.children_map.contains_key(id).clone();
             if !contains {
-                self.children_map.insert(id.clone(), Clone::clone(&self.delegate));
+                self.children_map.insert(id.clone(), clone_delegate(&self.delegate));
             }
         }
 
@@ -226,7 +283,7 @@ impl<GS: GlobalState, T: ForEachDelegate> CommonWidget<GS> for ForEach<GS, T> {
         for id in self.ids.get_latest_value().iter() {
             let contains = self.children_map.contains_key(id).clone();
             if !contains {
-                self.children_map.insert(id.clone(), Clone::clone(&self.delegate));
+                self.children_map.insert(id.clone(), clone_delegate(&self.delegate));
             }
         }
 
@@ -274,7 +331,7 @@ impl<GS: GlobalState, T: ForEachDelegate> Layout<GS> for ForEach<GS, T> {
         unimplemented!()
     }
 
-    fn position_children(&mut self) {
+    fn position_children(&mut self, env: &mut Environment<GS>) {
         unimplemented!()
     }
 }
@@ -3,6 +3,9 @@ use crate::widget::ChildRender;
 
 pub static SCALE: f64 = -1.0;
 
+/// Used as a maximum size to allow a widget to take all of the available space.
+pub static INFINITY: f64 = f64::INFINITY;
+
 #[derive(Debug, Clone, Widget)]
 pub struct Frame<GS> where GS: GlobalState {
     id: Uuid,
@@ -27,7 +30,7 @@ impl<GS: GlobalState> Frame<GS> {
         let expand_height = *height.get_latest_value() == SCALE;
 
         Box::new(Frame {
-            id: Default::default(),
+            id: Uuid::new_v4(),
             child: Box::new(child),
             position: [0.0, 0.0],
             x: 0.0.into(),
@@ -43,7 +46,7 @@ impl<GS: GlobalState> Frame<GS> {
 
     pub fn init_width(width: F64State<GS>, child: Box<dyn Widget<GS>>) -> Box<Frame<GS>> {
         Box::new(Frame {
-            id: Default::default(),
+            id: Uuid::new_v4(),
             child: Box::new(child),
             position: [0.0, 0.0],
             x: 0.0.into(),
@@ -59,7 +62,7 @@ impl<GS: GlobalState> Frame<GS> {
 
     pub fn init_height(height: F64State<GS>, child: Box<dyn Widget<GS>>) -> Box<Frame<GS>> {
         Box::new(Frame {
-            id: Default::default(),
+            id: Uuid::new_v4(),
             child: Box::new(child),
             position: [0.0, 0.0],
             x: 0.0.into(),
@@ -172,12 +175,12 @@ impl<GS: GlobalState> Layout<GS> for Frame<GS> {
 
         let dimensions = self.get_dimension();
 
-        self.child.calculate_size(dimensions, env);
+        self.child.calculate_size_cached(dimensions, env);
 
         self.get_dimension()
     }
 
-    fn position_children(&mut self) {
+    fn position_children(&mut self, env: &mut Environment<GS>) {
         if self.fixed_x {
             self.set_x(*self.x.get_latest_value());
         }
@@ -186,13 +189,13 @@ impl<GS: GlobalState> Layout<GS> for Frame<GS> {
             self.set_y(*self.y.get_latest_value());
         }
 
-        let positioning = BasicLayouter::Center.position();
+        let positioning = BasicLayouter::Center.position(env);
         let position = self.position;
         let dimension = [self.get_width(), self.get_height()];
 
 
         positioning(position, dimension, &mut self.child);
-        self.child.position_children();
+        self.child.position_children(env);
     }
 }
 
@@ -0,0 +1,171 @@
+use crate::prelude::*;
+use crate::widget::ChildRender;
+
+/// A widget taking the size requested from it, and writing the size into a state that its
+/// descendants can read, to lay out their content depending on the available space. The size
+/// is written when the widget is sized, and the position when it is positioned.
+///
+/// The states should be local states shared with the descendants, like a state created by
+/// `CommonState::new_local_with_key`.
+#[derive(Debug, Clone, Widget)]
+pub struct GeometryReader<GS> where GS: GlobalState {
+    id: Uuid,
+    child: Box<dyn Widget<GS>>,
+    position: Point,
+    dimension: Dimensions,
+    #[state] size: TState<Dimensions, GS>,
+    #[state] position_state: TState<Point, GS>,
+}
+
+impl<GS: GlobalState> GeometryReader<GS> {
+    pub fn new<S: Into<TState<Dimensions, GS>>>(size: S, child: Box<dyn Widget<GS>>) -> Box<Self> {
+        Box::new(GeometryReader {
+            id: Uuid::new_v4(),
+            child,
+            position: [0.0, 0.0],
+            dimension: [0.0, 0.0],
+            size: size.into(),
+            position_state: CommonState::new(&[0.0, 0.0]).into(),
+        })
+    }
+
+    /// Also write the position of the widget into the state.
+    pub fn position_state<S: Into<TState<Point, GS>>>(mut self, state: S) -> Box<Self> {
+        self.position_state = state.into();
+        Box::new(self)
+    }
+}
+
+/// Update the local states of the widget and its descendants from the environment.
+fn update_local_states<GS: GlobalState>(widget: &mut dyn Widget<GS>, env: &Environment<GS>) {
+    widget.update_local_widget_state(env);
+
+    for child in widget.get_proxied_children() {
+        update_local_states(child, env);
+    }
+}
+
+impl<GS: GlobalState> WidgetExt<GS> for GeometryReader<GS> {}
+
+impl<GS: GlobalState> Layout<GS> for GeometryReader<GS> {
+    fn flexibility(&self) -> u32 {
+        0
+    }
+
+    fn calculate_size(&mut self, requested_size: Dimensions, env: &mut Environment<GS>) -> Dimensions {
+        if *self.size.get_latest_value() != requested_size {
+            *self.size.get_latest_value_mut() = requested_size;
+
+            // Make the new size visible to the descendants before they are sized, instead of
+            // when the state is synchronized after the layout.
+            env.insert_local_state(&self.size);
+            update_local_states(self.child.deref_mut(), env);
+        }
+
+        self.child.calculate_size_cached(requested_size, env);
+
+        self.dimension = requested_size;
+        self.dimension
+    }
+
+    fn position_children(&mut self, env: &mut Environment<GS>) {
+        if *self.position_state.get_latest_value() != self.position {
+            *self.position_state.get_latest_value_mut() = self.position;
+
+            env.insert_local_state(&self.position_state);
+            update_local_states(self.child.deref_mut(), env);
+        }
+
+        let positioning = BasicLayouter::TopLeading.position(env);
+        let position = self.position;
+        let dimension = self.dimension;
+
+        positioning(position, dimension, &mut self.child);
+
+        self.child.position_children(env);
+    }
+}
+
+impl<GS: GlobalState> CommonWidget<GS> for GeometryReader<GS> {
+    fn get_id(&self) -> Uuid {
+        self.id
+    }
+
+    fn set_id(&mut self, id: Uuid) {
+        self.id = id;
+    }
+
+    fn get_flag(&self) -> Flags {
+        Flags::EMPTY
+    }
+
+    fn get_children(&self) -> WidgetIter<GS> {
+        if self.child.get_flag() == Flags::PROXY {
+            self.child.get_children()
+        } else {
+            WidgetIter::single(self.child.deref())
+        }
+    }
+
+    fn get_children_mut(&mut self) -> WidgetIterMut<GS> {
+        if self.child.get_flag() == Flags::PROXY {
+            self.child.get_children_mut()
+        } else {
+            WidgetIterMut::single(self.child.deref_mut())
+        }
+    }
+
+    fn get_proxied_children(&mut self) -> WidgetIterMut<GS> {
+        WidgetIterMut::single(self.child.deref_mut())
+    }
+
+    fn get_proxied_children_rev(&mut self) -> WidgetIterMut<GS> {
+        WidgetIterMut::single(self.child.deref_mut())
+    }
+
+    fn get_position(&self) -> Point {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Dimensions) {
+        self.position = position;
+    }
+
+    fn get_dimension(&self) -> Dimensions {
+        self.dimension
+    }
+
+    fn set_dimension(&mut self, dimensions: Dimensions) {
+        self.dimension = dimensions
+    }
+}
+
+impl<GS: GlobalState> ChildRender for GeometryReader<GS> {}
+
+#[test]
+fn reported_geometry_matches_the_laid_out_frame() {
+    use crate::headless::HeadlessUi;
+
+    let mut ui = HeadlessUi::new([200.0, 100.0], 1.0, ());
+
+    let size = CommonState::new_local_with_key(&[0.0, 0.0]);
+    let position = CommonState::new_local_with_key(&[0.0, 0.0]);
+
+    let root: Box<dyn Widget<()>> = GeometryReader::new(size.clone(), Rectangle::initialize(vec![]))
+        .position_state(position.clone())
+        .frame(120.0, 80.0);
+    *ui.widgets_mut() = root;
+    ui.draw();
+
+    let reader = ui.widgets().get_children().next().unwrap();
+    assert_eq!(reader.get_position(), [40.0, 10.0]);
+    assert_eq!(reader.get_dimension(), [120.0, 80.0]);
+
+    let mut size = size;
+    let mut position = position;
+    ui.environment().update_local_state(&mut size);
+    ui.environment().update_local_state(&mut position);
+
+    assert_eq!(*size.get_latest_value(), [120.0, 80.0]);
+    assert_eq!(*position.get_latest_value(), [40.0, 10.0]);
+}
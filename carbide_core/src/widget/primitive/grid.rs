@@ -0,0 +1,429 @@
+use crate::prelude::*;
+use crate::widget::ChildRender;
+
+/// The sizing of a row or a column of a `Grid`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GridTrack {
+    Fixed(Scalar),
+    /// A share of the space left after sizing the fixed and auto tracks, relative to the other
+    /// fractional tracks. Similar to the `fr` unit in css.
+    Fraction(Scalar),
+    /// The size of the largest child in the track.
+    Auto,
+}
+
+/// A child of a `Grid` along with the cell it is placed in.
+#[derive(Debug, Clone)]
+pub struct GridItem<GS> where GS: GlobalState {
+    child: Box<dyn Widget<GS>>,
+    row: usize,
+    column: usize,
+    row_span: usize,
+    column_span: usize,
+    alignment: BasicLayouter,
+}
+
+impl<GS: GlobalState> GridItem<GS> {
+    pub fn new(child: Box<dyn Widget<GS>>, row: usize, column: usize) -> GridItem<GS> {
+        GridItem {
+            child,
+            row,
+            column,
+            row_span: 1,
+            column_span: 1,
+            alignment: BasicLayouter::Center,
+        }
+    }
+
+    pub fn row_span(mut self, span: usize) -> GridItem<GS> {
+        self.row_span = span.max(1);
+        self
+    }
+
+    pub fn column_span(mut self, span: usize) -> GridItem<GS> {
+        self.column_span = span.max(1);
+        self
+    }
+
+    /// The alignment of the child within its cell.
+    pub fn alignment(mut self, alignment: BasicLayouter) -> GridItem<GS> {
+        self.alignment = alignment;
+        self
+    }
+}
+
+/// A container laying out its children in rows and columns. Children placed outside of the
+/// specified tracks get auto sized tracks. When no rows are specified, all rows are auto sized.
+#[derive(Debug, Clone, Widget)]
+pub struct Grid<GS> where GS: GlobalState {
+    id: Uuid,
+    items: Vec<GridItem<GS>>,
+    position: Point,
+    dimension: Dimensions,
+    columns: Vec<GridTrack>,
+    rows: Vec<GridTrack>,
+    column_spacing: Scalar,
+    row_spacing: Scalar,
+    /// The offset, the size and the alignment of the cell of each child, calculated when sizing.
+    cells: Vec<(Point, Dimensions, BasicLayouter)>,
+}
+
+impl<GS: GlobalState> WidgetExt<GS> for Grid<GS> {}
+
+impl<GS: GlobalState> Grid<GS> {
+    pub fn initialize(columns: Vec<GridTrack>, items: Vec<GridItem<GS>>) -> Box<Self> {
+        Box::new(Grid {
+            id: Uuid::new_v4(),
+            items,
+            position: [0.0, 0.0],
+            dimension: [100.0, 100.0],
+            columns,
+            rows: vec![],
+            column_spacing: 10.0,
+            row_spacing: 10.0,
+            cells: vec![],
+        })
+    }
+
+    pub fn rows(mut self, rows: Vec<GridTrack>) -> Box<Self> {
+        self.rows = rows;
+        Box::new(self)
+    }
+
+    /// Set both the row and the column spacing.
+    pub fn spacing(mut self, spacing: f64) -> Box<Self> {
+        self.column_spacing = spacing;
+        self.row_spacing = spacing;
+        Box::new(self)
+    }
+
+    pub fn column_spacing(mut self, spacing: f64) -> Box<Self> {
+        self.column_spacing = spacing;
+        Box::new(self)
+    }
+
+    pub fn row_spacing(mut self, spacing: f64) -> Box<Self> {
+        self.row_spacing = spacing;
+        Box::new(self)
+    }
+}
+
+/// The cell of a child of the grid, after expanding the children of proxy widgets.
+struct Placement {
+    row: usize,
+    column: usize,
+    row_span: usize,
+    column_span: usize,
+    alignment: BasicLayouter,
+}
+
+impl<GS: GlobalState> Grid<GS> {
+    /// The cell of each child, in the order of `get_children`. The children of a proxy widget,
+    /// like a `ForEach`, are placed in consecutive columns from the cell of the item, continuing
+    /// on the next rows when the specified columns are filled.
+    fn placements(&self) -> Vec<Placement> {
+        let column_count = self.columns.len();
+        let mut placements = vec![];
+
+        for item in &self.items {
+            if item.child.get_flag() != Flags::PROXY {
+                placements.push(Placement {
+                    row: item.row,
+                    column: item.column,
+                    row_span: item.row_span,
+                    column_span: item.column_span,
+                    alignment: item.alignment.clone(),
+                });
+                continue;
+            }
+
+            for index in 0..item.child.get_children().count() {
+                let column = item.column + index;
+                let (row, column) = if column_count > 0 {
+                    (item.row + column / column_count, column % column_count)
+                } else {
+                    (item.row, column)
+                };
+
+                placements.push(Placement { row, column, row_span: 1, column_span: 1, alignment: item.alignment.clone() });
+            }
+        }
+
+        placements
+    }
+}
+
+/// The specified tracks, extended with auto sized tracks such that every item fits.
+fn tracks_for(tracks: &[GridTrack], ends: impl Iterator<Item=usize>) -> Vec<GridTrack> {
+    let count = ends.fold(tracks.len(), usize::max);
+    let mut tracks = tracks.to_vec();
+    tracks.resize(count, GridTrack::Auto);
+    tracks
+}
+
+/// Calculate the size of each track. The contents are given as the first track, the number of
+/// tracks spanned and the size of the content.
+fn resolve_tracks(tracks: &[GridTrack], contents: &[(usize, usize, Scalar)], available: Scalar, spacing: Scalar) -> Vec<Scalar> {
+    let mut sizes: Vec<Scalar> = tracks.iter().map(|track| match track {
+        GridTrack::Fixed(size) => *size,
+        _ => 0.0,
+    }).collect();
+
+    for (start, _, size) in contents.iter().filter(|(_, span, _)| *span == 1) {
+        if tracks[*start] == GridTrack::Auto {
+            sizes[*start] = sizes[*start].max(*size);
+        }
+    }
+
+    // Content spanning multiple tracks grows the auto tracks it spans equally, if the tracks
+    // are not large enough. Content spanning fractional tracks is fitted by those instead.
+    for (start, span, size) in contents.iter().filter(|(_, span, _)| *span > 1) {
+        let range = *start..*start + *span;
+
+        if tracks[range.clone()].iter().any(|track| matches!(track, GridTrack::Fraction(_))) {
+            continue;
+        }
+
+        let auto_tracks: Vec<usize> = range.clone().filter(|index| tracks[*index] == GridTrack::Auto).collect();
+        let spanned = sizes[range].iter().sum::<Scalar>() + spacing * (*span - 1) as Scalar;
+        let missing = size - spanned;
+
+        if missing > 0.0 && !auto_tracks.is_empty() {
+            for index in &auto_tracks {
+                sizes[*index] += missing / auto_tracks.len() as Scalar;
+            }
+        }
+    }
+
+    let total_fraction: Scalar = tracks.iter().map(|track| match track {
+        GridTrack::Fraction(fraction) => *fraction,
+        _ => 0.0,
+    }).sum();
+
+    if total_fraction > 0.0 {
+        let used = sizes.iter().sum::<Scalar>() + spacing * (tracks.len().max(1) - 1) as Scalar;
+        let remaining = (available - used).max(0.0);
+
+        for (index, track) in tracks.iter().enumerate() {
+            if let GridTrack::Fraction(fraction) = track {
+                sizes[index] = remaining * fraction / total_fraction;
+            }
+        }
+    }
+
+    sizes
+}
+
+/// The offset of each track, followed by the total size of the tracks.
+fn track_offsets(sizes: &[Scalar], spacing: Scalar) -> Vec<Scalar> {
+    let mut offsets = vec![0.0];
+
+    for (index, size) in sizes.iter().enumerate() {
+        let spacing = if index + 1 < sizes.len() { spacing } else { 0.0 };
+        offsets.push(offsets[index] + size + spacing);
+    }
+
+    offsets
+}
+
+/// The size of a span of tracks, including the spacing between them.
+fn span_size(sizes: &[Scalar], start: usize, span: usize, spacing: Scalar) -> Scalar {
+    sizes[start..start + span].iter().sum::<Scalar>() + spacing * (span - 1) as Scalar
+}
+
+impl<GS: GlobalState> Layout<GS> for Grid<GS> {
+    fn flexibility(&self) -> u32 {
+        1
+    }
+
+    fn calculate_size(&mut self, requested_size: Dimensions, env: &mut Environment<GS>) -> Dimensions {
+        let column_spacing = self.column_spacing;
+        let row_spacing = self.row_spacing;
+
+        let placements = self.placements();
+
+        let columns = tracks_for(&self.columns, placements.iter().map(|placement| placement.column + placement.column_span));
+        let rows = tracks_for(&self.rows, placements.iter().map(|placement| placement.row + placement.row_span));
+
+        // Only the children in auto sized columns need to be measured to size the columns.
+        let column_contents: Vec<(usize, usize, Scalar)> = self.get_children_mut()
+            .zip(placements.iter())
+            .filter(|(_, placement)| columns[placement.column..placement.column + placement.column_span].contains(&GridTrack::Auto))
+            .map(|(child, placement)| (placement.column, placement.column_span, child.calculate_size_cached(requested_size, env)[0]))
+            .collect();
+
+        let column_sizes = resolve_tracks(&columns, &column_contents, requested_size[0], column_spacing);
+        let column_offsets = track_offsets(&column_sizes, column_spacing);
+
+        let row_contents: Vec<(usize, usize, Scalar)> = self.get_children_mut()
+            .zip(placements.iter())
+            .map(|(child, placement)| {
+                let width = span_size(&column_sizes, placement.column, placement.column_span, column_spacing);
+                (placement.row, placement.row_span, child.calculate_size_cached([width, requested_size[1]], env)[1])
+            })
+            .collect();
+
+        let row_sizes = resolve_tracks(&rows, &row_contents, requested_size[1], row_spacing);
+        let row_offsets = track_offsets(&row_sizes, row_spacing);
+
+        let mut cells = vec![];
+
+        for (child, placement) in self.get_children_mut().zip(placements.iter()) {
+            let width = span_size(&column_sizes, placement.column, placement.column_span, column_spacing);
+            let height = span_size(&row_sizes, placement.row, placement.row_span, row_spacing);
+
+            child.calculate_size_cached([width, height], env);
+
+            cells.push(([column_offsets[placement.column], row_offsets[placement.row]], [width, height], placement.alignment.clone()));
+        }
+
+        self.cells = cells;
+        self.dimension = [*column_offsets.last().unwrap(), *row_offsets.last().unwrap()];
+
+        self.dimension
+    }
+
+    fn position_children(&mut self, env: &mut Environment<GS>) {
+        let position = self.position;
+        let width = self.dimension[0];
+        let layout_direction = env.get_layout_direction();
+        let cells = self.cells.clone();
+
+        for (child, (offset, dimension, alignment)) in self.get_children_mut().zip(cells) {
+            let positioning = alignment.position(env);
+
+            // In a right to left layout the first column is placed at the right edge.
+            let x = match layout_direction {
+                LayoutDirection::LeftToRight => position[0] + offset[0],
+                LayoutDirection::RightToLeft => position[0] + width - offset[0] - dimension[0],
+            };
+
+            positioning([x, position[1] + offset[1]], dimension, child);
+
+            child.position_children(env);
+        }
+    }
+}
+
+impl<GS: GlobalState> CommonWidget<GS> for Grid<GS> {
+    fn get_id(&self) -> Uuid {
+        self.id
+    }
+
+    fn set_id(&mut self, id: Uuid) {
+        self.id = id;
+    }
+
+    fn get_flag(&self) -> Flags {
+        Flags::EMPTY
+    }
+
+    fn get_children(&self) -> WidgetIter<GS> {
+        self.items
+            .iter()
+            .map(|x| x.child.deref())
+            .rfold(WidgetIter::Empty, |acc, x| {
+                if x.get_flag() == Flags::PROXY {
+                    WidgetIter::Multi(Box::new(x.get_children()), Box::new(acc))
+                } else {
+                    WidgetIter::Single(x, Box::new(acc))
+                }
+            })
+    }
+
+    fn get_children_mut(&mut self) -> WidgetIterMut<GS> {
+        self.items
+            .iter_mut()
+            .map(|x| x.child.deref_mut())
+            .rfold(WidgetIterMut::Empty, |acc, x| {
+                if x.get_flag() == Flags::PROXY {
+                    WidgetIterMut::Multi(Box::new(x.get_children_mut()), Box::new(acc))
+                } else {
+                    WidgetIterMut::Single(x, Box::new(acc))
+                }
+            })
+    }
+
+    fn get_proxied_children(&mut self) -> WidgetIterMut<GS> {
+        self.items.iter_mut()
+            .map(|x| x.child.deref_mut())
+            .rfold(WidgetIterMut::Empty, |acc, x| {
+                WidgetIterMut::Single(x, Box::new(acc))
+            })
+    }
+
+    fn get_proxied_children_rev(&mut self) -> WidgetIterMut<GS> {
+        self.items.iter_mut()
+            .map(|x| x.child.deref_mut())
+            .fold(WidgetIterMut::Empty, |acc, x| {
+                WidgetIterMut::Single(x, Box::new(acc))
+            })
+    }
+
+    fn get_position(&self) -> Point {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Dimensions) {
+        self.position = position;
+    }
+
+    fn get_dimension(&self) -> Dimensions {
+        self.dimension
+    }
+
+    fn set_dimension(&mut self, dimensions: Dimensions) {
+        self.dimension = dimensions
+    }
+}
+
+impl<GS: GlobalState> ChildRender for Grid<GS> {}
+
+#[test]
+fn tracks_are_sized_by_kind() {
+    let tracks = [GridTrack::Fixed(50.0), GridTrack::Auto, GridTrack::Fraction(1.0), GridTrack::Fraction(3.0)];
+    let contents = [(1, 1, 30.0), (0, 2, 100.0)];
+
+    // The auto track grows to fit the content spanning the first two tracks: 100 - 50 - 10.
+    // The remaining 400 - 50 - 40 - 3 * 10 is shared by the fractional tracks.
+    assert_eq!(resolve_tracks(&tracks, &contents, 400.0, 10.0), vec![50.0, 40.0, 70.0, 210.0]);
+
+    assert_eq!(track_offsets(&[50.0, 40.0], 10.0), vec![0.0, 60.0, 100.0]);
+}
+
+#[test]
+fn columns_are_mirrored_in_a_right_to_left_layout() {
+    let mut env = Environment::<()>::new(vec![], [100.0, 100.0], 1.0);
+    env.push(EnvironmentVariable::LayoutDirection { value: LayoutDirection::RightToLeft });
+
+    let mut grid = Grid::initialize(vec![GridTrack::Fixed(30.0), GridTrack::Fixed(50.0)], vec![
+        GridItem::new(Rectangle::initialize(vec![]), 0, 0),
+        GridItem::new(Rectangle::initialize(vec![]), 0, 1),
+    ]).rows(vec![GridTrack::Fixed(20.0)]);
+
+    assert_eq!(grid.calculate_size([100.0, 100.0], &mut env), [90.0, 20.0]);
+    grid.set_position([0.0, 0.0]);
+    grid.position_children(&mut env);
+
+    let xs: Vec<Scalar> = grid.get_children().map(|child| child.get_x()).collect();
+    assert_eq!(xs, vec![60.0, 0.0]);
+}
+
+#[test]
+fn children_of_a_for_each_get_a_cell_each() {
+    use crate::widget::ForEach;
+
+    let mut env = Environment::<()>::new(vec![], [100.0, 100.0], 1.0);
+
+    let mut grid = Grid::initialize(vec![GridTrack::Fixed(20.0), GridTrack::Fixed(20.0), GridTrack::Fixed(20.0)], vec![
+        GridItem::new(ForEach::new(vec![1u32, 2, 3], Rectangle::initialize(vec![]).frame(10.0, 10.0)), 0, 0),
+        GridItem::new(Rectangle::initialize(vec![]).frame(10.0, 10.0), 1, 0),
+    ]).spacing(0.0);
+
+    assert_eq!(grid.calculate_size([100.0, 100.0], &mut env), [60.0, 20.0]);
+    grid.set_position([0.0, 0.0]);
+    grid.position_children(&mut env);
+
+    let positions: Vec<Point> = grid.get_children().map(|child| child.get_position()).collect();
+    assert_eq!(positions, vec![[5.0, 0.0], [25.0, 0.0], [45.0, 0.0], [5.0, 10.0]]);
+}
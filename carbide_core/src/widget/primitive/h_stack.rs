@@ -37,7 +37,6 @@ impl<GS: GlobalState> Layout<GS> for HStack<GS> {
     }
 
     fn calculate_size(&mut self, requested_size: Dimensions, env: &mut Environment<GS>) -> Dimensions {
-
         // The number of children not containing any spacers
         let mut number_of_children_that_needs_sizing = self.get_children().filter(|m| m.get_flag() != Flags::SPACER).count() as f64;
 
@@ -61,7 +60,7 @@ impl<GS: GlobalState> Layout<GS> for HStack<GS> {
 
         for (_, child) in children_flexibilty {
             let size_for_child = [size_for_children[0] / number_of_children_that_needs_sizing, size_for_children[1]];
-            let chosen_size = child.calculate_size(size_for_child, env);
+            let chosen_size = child.calculate_size_cached(size_for_child, env);
 
             if chosen_size[1] > max_height {
                 max_height = chosen_size[1];
@@ -78,7 +77,7 @@ impl<GS: GlobalState> Layout<GS> for HStack<GS> {
         let rest_space = requested_size[0] - total_width - spacing_total;
 
         for spacer in self.get_children_mut().filter(|m| m.get_flag() == Flags::SPACER) {
-            let chosen_size = spacer.calculate_size([rest_space / spacer_count, requested_size[1]], env);
+            let chosen_size = spacer.calculate_size_cached([rest_space / spacer_count, requested_size[1]], env);
 
             if chosen_size[1] > max_height {
                 max_height = chosen_size[1];
@@ -92,12 +91,13 @@ impl<GS: GlobalState> Layout<GS> for HStack<GS> {
         self.dimension
     }
 
-    fn position_children(&mut self) {
+    fn position_children(&mut self, env: &mut Environment<GS>) {
         let cross_axis_alignment = CrossAxisAlignment::Center;
         let mut width_offset = 0.0;
         let position = self.position;
         let dimension = self.dimension;
         let spacing = self.spacing;
+        let layout_direction = env.get_layout_direction();
 
         let spacers: Vec<bool> = self.get_children().map(|n| n.get_flag() == Flags::SPACER).collect();
 
@@ -108,7 +108,11 @@ impl<GS: GlobalState> Layout<GS> for HStack<GS> {
                 CrossAxisAlignment::End => { child.set_y(position[1] + dimension[1] - child.get_height()) }
             }
 
-            child.set_x(position[0] + width_offset);
+            // In a right to left layout the first child is placed at the right edge.
+            match layout_direction {
+                LayoutDirection::LeftToRight => child.set_x(position[0] + width_offset),
+                LayoutDirection::RightToLeft => child.set_x(position[0] + dimension[0] - width_offset - child.get_width()),
+            }
 
             if child.get_flag() != Flags::SPACER && n < spacers.len() - 1 && !spacers[n + 1] {
                 width_offset += spacing;
@@ -116,7 +120,7 @@ impl<GS: GlobalState> Layout<GS> for HStack<GS> {
             width_offset += child.get_width();
 
 
-            child.position_children();
+            child.position_children(env);
         }
     }
 }
@@ -197,3 +201,21 @@ impl<S: GlobalState> CommonWidget<S> for HStack<S> {
 impl<S: GlobalState> ChildRender for HStack<S> {}
 
 
+
+#[test]
+fn first_child_is_placed_at_the_right_edge_in_a_right_to_left_layout() {
+    let mut env = Environment::<()>::new(vec![], [100.0, 100.0], 1.0);
+    env.push(EnvironmentVariable::LayoutDirection { value: LayoutDirection::RightToLeft });
+
+    let mut stack = HStack::initialize(vec![
+        Rectangle::initialize(vec![]).frame(10.0, 10.0),
+        Rectangle::initialize(vec![]).frame(20.0, 10.0),
+    ]);
+
+    stack.calculate_size([100.0, 100.0], &mut env);
+    stack.set_position([0.0, 0.0]);
+    stack.position_children(&mut env);
+
+    let xs: Vec<Scalar> = stack.get_children().map(|child| child.get_x()).collect();
+    assert_eq!(xs, vec![30.0, 0.0]);
+}
@@ -2,6 +2,7 @@ use crate::prelude::*;
 
 /// A basic, non-interactive rectangle shape widget.
 #[derive(Debug, Clone, Widget)]
+#[event(hit_test)]
 pub struct Hidden<GS> where GS: GlobalState {
     id: Uuid,
     child: Box<dyn Widget<GS>>,
@@ -11,24 +12,31 @@ pub struct Hidden<GS> where GS: GlobalState {
 
 impl<GS: GlobalState> WidgetExt<GS> for Hidden<GS> {}
 
+impl<GS: GlobalState> Hidden<GS> {
+    /// A hidden widget is not drawn, so the widgets below it are hovered instead.
+    fn hit_test(&self, _: Point, _: &mut Vec<Uuid>) -> bool {
+        false
+    }
+}
+
 impl<GS: GlobalState> Layout<GS> for Hidden<GS> {
     fn flexibility(&self) -> u32 {
         self.child.flexibility()
     }
 
     fn calculate_size(&mut self, requested_size: Dimensions, env: &mut Environment<GS>) -> Dimensions {
-        self.dimension = self.child.calculate_size(requested_size, env);
+        self.dimension = self.child.calculate_size_cached(requested_size, env);
         self.dimension
     }
 
-    fn position_children(&mut self) {
-        let positioning = BasicLayouter::Center.position();
+    fn position_children(&mut self, env: &mut Environment<GS>) {
+        let positioning = BasicLayouter::Center.position(env);
         let position = self.position;
         let dimension = self.dimension;
 
         positioning(position, dimension, &mut self.child);
 
-        self.child.position_children();
+        self.child.position_children(env);
     }
 }
 
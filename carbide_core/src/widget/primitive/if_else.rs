@@ -46,26 +46,26 @@ impl<GS: GlobalState> Layout<GS> for IfElse<GS> {
 
     fn calculate_size(&mut self, requested_size: Dimensions, env: &mut Environment<GS>) -> Dimensions {
         if *self.predicate.get_latest_value() {
-            self.dimension = self.when_true.calculate_size(requested_size, env);
+            self.dimension = self.when_true.calculate_size_cached(requested_size, env);
         } else {
-            self.dimension = self.when_false.calculate_size(requested_size, env);
+            self.dimension = self.when_false.calculate_size_cached(requested_size, env);
         }
         self.dimension
     }
 
-    fn position_children(&mut self) {
-        let positioning = BasicLayouter::Center.position();
+    fn position_children(&mut self, env: &mut Environment<GS>) {
+        let positioning = BasicLayouter::Center.position(env);
         let position = self.position;
         let dimension = self.dimension;
 
         if *self.predicate.get_latest_value() {
             positioning(position, dimension, &mut self.when_true);
 
-            self.when_true.position_children();
+            self.when_true.position_children(env);
         } else {
             positioning(position, dimension, &mut self.when_false);
 
-            self.when_false.position_children();
+            self.when_false.position_children(env);
         }
     }
 }
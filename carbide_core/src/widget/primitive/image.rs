@@ -38,30 +38,15 @@ impl<GS: GlobalState> Layout<GS> for Image {
         if !self.resizeable {
             self.dimension = [image_information.width as f64, image_information.height as f64];
         } else {
-            let width_factor = requested_size[0] / (image_information.width as f64);
-            let height_factor = requested_size[1] / (image_information.height as f64);
-
-            match self.scale_mode {
-                ScaleMode::Fit => {
-                    let scale_factor = width_factor.min(height_factor);
-
-                    self.dimension = [(image_information.width as f64) * scale_factor, (image_information.height as f64) * scale_factor]
-                }
-                ScaleMode::Fill => {
-                    let scale_factor = width_factor.max(height_factor);
-
-                    self.dimension = [(image_information.width as f64) * scale_factor, (image_information.height as f64) * scale_factor]
-                }
-                ScaleMode::Stretch => {
-                    self.dimension = requested_size
-                }
-            }
+            let size = [image_information.width as f64, image_information.height as f64];
+
+            self.dimension = self.scale_mode.scale(size, requested_size);
         }
 
         self.dimension
     }
 
-    fn position_children(&mut self) {}
+    fn position_children(&mut self, env: &mut Environment<GS>) {}
 }
 
 impl<GS: GlobalState> Render<GS> for Image {
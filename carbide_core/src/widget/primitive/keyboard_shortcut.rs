@@ -0,0 +1,124 @@
+use crate::environment::command_registry::Command;
+use crate::event_handler::KeyboardEvent;
+use crate::prelude::*;
+use crate::widget::ChildRender;
+
+/// A widget executing the handler of a command when the keys bound to the command are pressed,
+/// for as long as the widget is shown. The command is added to the command registry of the
+/// environment when the widget is laid out, such that it can be remapped, disabled and listed
+/// like the other commands, but the handler is kept by the widget. The command is removed from
+/// the registry again once the widget is no longer shown.
+#[derive(Debug, Clone, Widget)]
+#[event(handle_keyboard_event)]
+pub struct KeyboardShortcut<GS> where GS: GlobalState {
+    id: Uuid,
+    child: Box<dyn Widget<GS>>,
+    position: Point,
+    dimension: Dimensions,
+    command: Command<GS>,
+}
+
+impl<GS: GlobalState> KeyboardShortcut<GS> {
+    pub fn new(command: Command<GS>, child: Box<dyn Widget<GS>>) -> Box<Self> {
+        Box::new(KeyboardShortcut {
+            id: Uuid::new_v4(),
+            child,
+            position: [0.0, 0.0],
+            dimension: [0.0, 0.0],
+            command,
+        })
+    }
+
+    fn handle_keyboard_event(&mut self, event: &KeyboardEvent, env: &mut Environment<GS>, global_state: &mut GS) {
+        if let KeyboardEvent::Press(key, modifiers) = event {
+            if !env.commands().is_triggered_by(self.command.id(), *key, *modifiers) {
+                return;
+            }
+
+            if let Some(handler) = self.command.handler() {
+                handler(env, global_state);
+            }
+        }
+    }
+}
+
+impl<GS: GlobalState> WidgetExt<GS> for KeyboardShortcut<GS> {}
+
+impl<GS: GlobalState> Layout<GS> for KeyboardShortcut<GS> {
+    fn flexibility(&self) -> u32 {
+        self.child.flexibility()
+    }
+
+    fn calculate_size(&mut self, requested_size: Dimensions, env: &mut Environment<GS>) -> Dimensions {
+        env.commands_mut().declare(self.id, &self.command);
+
+        self.dimension = self.child.calculate_size_cached(requested_size, env);
+        self.dimension
+    }
+
+    fn position_children(&mut self, env: &mut Environment<GS>) {
+        let positioning = BasicLayouter::Center.position(env);
+        let position = self.position;
+        let dimension = self.dimension;
+
+        positioning(position, dimension, &mut self.child);
+
+        self.child.position_children(env);
+    }
+}
+
+impl<GS: GlobalState> CommonWidget<GS> for KeyboardShortcut<GS> {
+    fn get_id(&self) -> Uuid {
+        self.id
+    }
+
+    fn set_id(&mut self, id: Uuid) {
+        self.id = id;
+    }
+
+    fn get_flag(&self) -> Flags {
+        Flags::EMPTY
+    }
+
+    fn get_children(&self) -> WidgetIter<GS> {
+        if self.child.get_flag() == Flags::PROXY {
+            self.child.get_children()
+        } else {
+            WidgetIter::single(self.child.deref())
+        }
+    }
+
+    fn get_children_mut(&mut self) -> WidgetIterMut<GS> {
+        if self.child.get_flag() == Flags::PROXY {
+            self.child.get_children_mut()
+        } else {
+            WidgetIterMut::single(self.child.deref_mut())
+        }
+    }
+
+    fn get_proxied_children(&mut self) -> WidgetIterMut<GS> {
+        WidgetIterMut::single(self.child.deref_mut())
+    }
+
+    fn get_proxied_children_rev(&mut self) -> WidgetIterMut<GS> {
+        WidgetIterMut::single(self.child.deref_mut())
+    }
+
+    fn get_position(&self) -> Point {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Dimensions) {
+        self.position = position;
+    }
+
+    fn get_dimension(&self) -> Dimensions {
+        self.dimension
+    }
+
+    fn set_dimension(&mut self, dimensions: Dimensions) {
+        self.dimension = dimensions
+    }
+}
+
+impl<GS: GlobalState> ChildRender for KeyboardShortcut<GS> {}
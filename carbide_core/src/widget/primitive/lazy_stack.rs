@@ -0,0 +1,367 @@
+use std::collections::{HashMap, HashSet};
+
+use fxhash::{FxBuildHasher, FxHashMap};
+
+use crate::event::event::is_on_dispatch_route;
+use crate::event_handler::{KeyboardEvent, MouseEvent};
+use crate::prelude::*;
+use crate::state::state::CommonState;
+use crate::widget::primitive::foreach::{clone_delegate, ForEachDelegate};
+use crate::widget::render::ChildRender;
+
+/// A stack only instantiating the delegates of the ids that are visible within the viewport of
+/// the enclosing `Scroll`. Without an enclosing scroll, the delegates fitting within the
+/// requested size are shown.
+///
+/// Instances of the delegate that are scrolled out of view are reused for the ids scrolled into
+/// view, so the delegate should derive its content from the id and index states rather than keep
+/// its own local state. Delegates are sized along the main axis by their last measured size,
+/// falling back to the average of the measured sizes, or the estimated size if none has been
+/// measured yet. The sizes of the ids not yet shown are estimated in the same way.
+#[derive(Debug, Clone, Widget)]
+#[state_sync(sync_state)]
+#[event(process_mouse_event, process_keyboard_event)]
+pub struct LazyStack<GS, T> where GS: GlobalState, T: ForEachDelegate + 'static {
+    id: Uuid,
+    /// The instantiated delegates of the visible ids, in order.
+    children: Vec<(T, Box<dyn Widget<GS>>)>,
+    /// Instances of the delegate that are no longer visible.
+    recycled: Vec<Box<dyn Widget<GS>>>,
+    delegate: Box<dyn Widget<GS>>,
+    #[state] ids: TState<Vec<T>, GS>,
+    position: Point,
+    dimension: Dimensions,
+    /// The index of the main axis, 0 for horizontal stacks and 1 for vertical stacks.
+    main_axis: usize,
+    spacing: Scalar,
+    estimated_size: Scalar,
+    /// The latest size along the main axis of each id that has been visible.
+    measured_sizes: FxHashMap<T, Scalar>,
+    /// The index of the first visible id.
+    first_visible: usize,
+    /// The offset along the main axis of each visible child.
+    offsets: Vec<Scalar>,
+    id_state: Box<dyn State<T, GS>>,
+    index_state: Box<dyn State<usize, GS>>,
+}
+
+impl<GS: GlobalState, T: ForEachDelegate + 'static> WidgetExt<GS> for LazyStack<GS, T> {}
+
+impl<GS: GlobalState, T: ForEachDelegate + 'static> LazyStack<GS, T> {
+    /// A lazy stack laying out its children vertically.
+    pub fn vertical<K: Into<TState<Vec<T>, GS>>>(ids: K, delegate: Box<dyn Widget<GS>>) -> Box<Self> {
+        LazyStack::new(1, ids, delegate)
+    }
+
+    /// A lazy stack laying out its children horizontally.
+    pub fn horizontal<K: Into<TState<Vec<T>, GS>>>(ids: K, delegate: Box<dyn Widget<GS>>) -> Box<Self> {
+        LazyStack::new(0, ids, delegate)
+    }
+
+    fn new<K: Into<TState<Vec<T>, GS>>>(main_axis: usize, ids: K, delegate: Box<dyn Widget<GS>>) -> Box<Self> {
+        Box::new(LazyStack {
+            id: Uuid::new_v4(),
+            children: vec![],
+            recycled: vec![],
+            delegate,
+            ids: ids.into(),
+            position: [0.0, 0.0],
+            dimension: [100.0, 100.0],
+            main_axis,
+            spacing: 10.0,
+            estimated_size: 30.0,
+            measured_sizes: HashMap::with_hasher(FxBuildHasher::default()),
+            first_visible: 0,
+            offsets: vec![],
+            id_state: Box::new(CommonState::new_local_with_key(&T::default())),
+            index_state: Box::new(CommonState::new_local_with_key(&0)),
+        })
+    }
+
+    pub fn spacing(mut self, spacing: f64) -> Box<Self> {
+        self.spacing = spacing;
+        Box::new(self)
+    }
+
+    /// The size along the main axis used for the ids that have not been measured yet.
+    pub fn estimated_size(mut self, size: f64) -> Box<Self> {
+        self.estimated_size = size;
+        Box::new(self)
+    }
+
+    pub fn id_state(mut self, state: Box<dyn State<T, GS>>) -> Box<Self> {
+        self.id_state = state;
+        Box::new(self)
+    }
+
+    pub fn index_state(mut self, state: Box<dyn State<usize, GS>>) -> Box<Self> {
+        self.index_state = state;
+        Box::new(self)
+    }
+
+    fn sync_state(&mut self, env: &mut Environment<GS>, global_state: &GS) {
+        self.update_all_widget_state(env, global_state);
+
+        self.insert_local_state(env);
+
+        let first_visible = self.first_visible;
+        let id_key = self.id_state.get_key().unwrap().clone();
+        let index_key = self.index_state.get_key().unwrap().clone();
+
+        for (i, (id, child)) in self.children.iter_mut().enumerate() {
+            env.insert_local_state_from_key_value(&id_key, id);
+            env.insert_local_state_from_key_value(&index_key, &(i + first_visible));
+
+            child.sync_state(env, global_state)
+        }
+
+        self.update_local_widget_state(env);
+    }
+
+    fn process_mouse_event(&mut self, event: &MouseEvent, env: &mut Environment<GS>, global_state: &mut GS) {
+        self.update_all_widget_state(env, global_state);
+
+        self.insert_local_state(env);
+
+        let first_visible = self.first_visible;
+        let id_key = self.id_state.get_key().unwrap().clone();
+        let index_key = self.index_state.get_key().unwrap().clone();
+        let along_path = env.is_dispatching_along_path();
+
+        for (i, (id, child)) in self.children.iter_mut().enumerate() {
+            if along_path && !is_on_dispatch_route(child.deref(), env) { continue }
+
+            env.insert_local_state_from_key_value(&id_key, id);
+            env.insert_local_state_from_key_value(&index_key, &(i + first_visible));
+
+            child.process_mouse_event(event, env, global_state);
+        }
+
+        self.update_local_widget_state(env)
+    }
+
+    fn process_keyboard_event(&mut self, event: &KeyboardEvent, env: &mut Environment<GS>, global_state: &mut GS) {
+        self.update_all_widget_state(env, global_state);
+
+        self.insert_local_state(env);
+
+        let first_visible = self.first_visible;
+        let id_key = self.id_state.get_key().unwrap().clone();
+        let index_key = self.index_state.get_key().unwrap().clone();
+        let along_path = env.is_dispatching_along_path();
+
+        for (i, (id, child)) in self.children.iter_mut().enumerate() {
+            if along_path && !is_on_dispatch_route(child.deref(), env) { continue }
+
+            env.insert_local_state_from_key_value(&id_key, id);
+            env.insert_local_state_from_key_value(&index_key, &(i + first_visible));
+
+            child.process_keyboard_event(event, env, global_state);
+        }
+
+        self.update_local_widget_state(env)
+    }
+}
+
+/// The size along the main axis used for ids that have not been measured.
+fn estimate<T: ForEachDelegate>(measured_sizes: &FxHashMap<T, Scalar>, estimated_size: Scalar) -> Scalar {
+    if measured_sizes.is_empty() {
+        estimated_size
+    } else {
+        measured_sizes.values().sum::<Scalar>() / measured_sizes.len() as Scalar
+    }
+}
+
+/// The index of the first id ending after the start of the visible range, and its offset.
+fn first_visible<T: ForEachDelegate>(ids: &[T], sizes: &FxHashMap<T, Scalar>, estimate: Scalar, spacing: Scalar, visible_start: Scalar) -> (usize, Scalar) {
+    let mut offset = 0.0;
+
+    for (index, id) in ids.iter().enumerate() {
+        let size = sizes.get(id).cloned().unwrap_or(estimate);
+
+        if offset + size >= visible_start {
+            return (index, offset);
+        }
+
+        offset += size + spacing;
+    }
+
+    (ids.len(), offset)
+}
+
+impl<GS: GlobalState, T: ForEachDelegate + 'static> Layout<GS> for LazyStack<GS, T> {
+    fn flexibility(&self) -> u32 {
+        1
+    }
+
+    fn calculate_size(&mut self, requested_size: Dimensions, env: &mut Environment<GS>) -> Dimensions {
+        let main = self.main_axis;
+        let cross = 1 - main;
+        let spacing = self.spacing;
+
+        // The offset of the stack within the content is taken from the previous layout, because
+        // it does not change by scrolling.
+        let (visible_start, visible_end) = match env.viewport() {
+            Some(viewport) => {
+                let offset_in_content = self.position[main] - viewport.content_position[main];
+                let start = viewport.offset[main] - offset_in_content;
+                (start, start + viewport.dimension[main])
+            }
+            None => (0.0, requested_size[main]),
+        };
+
+        let ids = self.ids.get_latest_value();
+
+        // Forget the sizes of removed ids, such that they do not skew the estimate.
+        if self.measured_sizes.len() > ids.len() {
+            let current: HashSet<&T> = ids.iter().collect();
+            self.measured_sizes.retain(|id, _| current.contains(id));
+        }
+
+        let estimate = estimate(&self.measured_sizes, self.estimated_size);
+        let (first, mut offset) = first_visible(ids, &self.measured_sizes, estimate, spacing, visible_start);
+
+        let recycled = &mut self.recycled;
+        let delegate = &self.delegate;
+
+        let mut previous: FxHashMap<T, Box<dyn Widget<GS>>> = self.children.drain(..).collect();
+        let mut offsets = vec![];
+        let mut index = first;
+
+        while index < ids.len() && offset <= visible_end {
+            let id = &ids[index];
+
+            let mut child = match previous.remove(id).or_else(|| recycled.pop()) {
+                Some(child) => child,
+                None => clone_delegate(delegate),
+            };
+
+            let mut size_for_child = requested_size;
+            size_for_child[main] = self.measured_sizes.get(id).cloned().unwrap_or(estimate);
+
+            let chosen_size = child.calculate_size_cached(size_for_child, env);
+            self.measured_sizes.insert(id.clone(), chosen_size[main]);
+
+            offsets.push(offset);
+            offset += chosen_size[main] + spacing;
+
+            self.children.push((id.clone(), child));
+            index += 1;
+        }
+
+        // Keep at most as many instances for reuse as are currently visible.
+        self.recycled.extend(previous.into_iter().map(|(_, child)| child));
+        self.recycled.truncate(self.children.len());
+
+        let remaining: Scalar = ids[index..].iter()
+            .map(|id| self.measured_sizes.get(id).cloned().unwrap_or(estimate) + spacing)
+            .sum();
+
+        self.first_visible = first;
+        self.offsets = offsets;
+
+        self.dimension[main] = (offset + remaining - spacing).max(0.0);
+        self.dimension[cross] = requested_size[cross];
+
+        self.dimension
+    }
+
+    fn position_children(&mut self, env: &mut Environment<GS>) {
+        let main = self.main_axis;
+        let position = self.position;
+
+        for ((_, child), offset) in self.children.iter_mut().zip(self.offsets.iter()) {
+            let mut child_position = position;
+            child_position[main] += offset;
+
+            child.set_position(child_position);
+            child.position_children(env);
+        }
+    }
+}
+
+impl<GS: GlobalState, T: ForEachDelegate> CommonWidget<GS> for LazyStack<GS, T> {
+    fn get_id(&self) -> Uuid {
+        self.id
+    }
+
+    fn set_id(&mut self, id: Uuid) {
+        self.id = id;
+    }
+
+    fn get_flag(&self) -> Flags {
+        Flags::EMPTY
+    }
+
+    fn get_children(&self) -> WidgetIter<GS> {
+        self.children
+            .iter()
+            .map(|(_, x)| x.deref())
+            .rfold(WidgetIter::Empty, |acc, x| {
+                if x.get_flag() == Flags::PROXY {
+                    WidgetIter::Multi(Box::new(x.get_children()), Box::new(acc))
+                } else {
+                    WidgetIter::Single(x, Box::new(acc))
+                }
+            })
+    }
+
+    fn get_children_mut(&mut self) -> WidgetIterMut<GS> {
+        self.children
+            .iter_mut()
+            .map(|(_, x)| x.deref_mut())
+            .rfold(WidgetIterMut::Empty, |acc, x| {
+                if x.get_flag() == Flags::PROXY {
+                    WidgetIterMut::Multi(Box::new(x.get_children_mut()), Box::new(acc))
+                } else {
+                    WidgetIterMut::Single(x, Box::new(acc))
+                }
+            })
+    }
+
+    fn get_proxied_children(&mut self) -> WidgetIterMut<GS> {
+        self.children.iter_mut()
+            .map(|(_, x)| x.deref_mut())
+            .rfold(WidgetIterMut::Empty, |acc, x| {
+                WidgetIterMut::Single(x, Box::new(acc))
+            })
+    }
+
+    fn get_proxied_children_rev(&mut self) -> WidgetIterMut<GS> {
+        self.children.iter_mut()
+            .map(|(_, x)| x.deref_mut())
+            .fold(WidgetIterMut::Empty, |acc, x| {
+                WidgetIterMut::Single(x, Box::new(acc))
+            })
+    }
+
+    fn get_position(&self) -> Point {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Dimensions) {
+        self.position = position;
+    }
+
+    fn get_dimension(&self) -> Dimensions {
+        self.dimension
+    }
+
+    fn set_dimension(&mut self, dimensions: Dimensions) {
+        self.dimension = dimensions
+    }
+}
+
+impl<GS: GlobalState, T: ForEachDelegate> ChildRender for LazyStack<GS, T> {}
+
+#[test]
+fn first_visible_uses_measured_and_estimated_sizes() {
+    let ids: Vec<u32> = (0..100_000).collect();
+    let mut sizes = HashMap::with_hasher(FxBuildHasher::default());
+    sizes.insert(0, 50.0);
+
+    assert_eq!(first_visible(&ids, &sizes, 20.0, 0.0, 0.0), (0, 0.0));
+    assert_eq!(first_visible(&ids, &sizes, 20.0, 0.0, 60.0), (1, 50.0));
+    assert_eq!(first_visible(&ids, &sizes, 20.0, 10.0, 1000.0), (32, 60.0 + 31.0 * 30.0));
+    assert_eq!(estimate(&sizes, 20.0), 50.0);
+}
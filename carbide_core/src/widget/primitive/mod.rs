@@ -13,13 +13,23 @@ pub mod text;
 pub mod widget;
 pub mod v_stack;
 pub mod frame;
+pub mod aspect_ratio;
+pub mod background;
+pub mod overlay;
+pub mod flexible_frame;
+pub mod geometry_reader;
 pub mod h_stack;
+pub mod flex;
+pub mod grid;
 pub mod z_stack;
 pub mod padding;
 pub mod spacer;
 pub mod foreach;
+pub mod lazy_stack;
+pub mod wrap_stack;
 pub mod overlaid_layer;
 pub mod scroll;
+pub mod split_view;
 pub mod clip;
 pub mod hidden;
 pub mod canvas;
@@ -28,6 +38,10 @@ pub mod border;
 pub mod if_else;
 pub mod environment_updating;
 pub mod shared_state;
+pub mod on_change;
+pub mod on_gesture;
+pub mod on_hover;
+pub mod keyboard_shortcut;
 
 /// Find the bounding rect for the given series of points.
 pub fn bounding_box_for_points<I>(mut points: I) -> OldRect
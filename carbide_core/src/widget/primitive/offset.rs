@@ -19,12 +19,12 @@ impl<GS: GlobalState> Layout<GS> for Offset<GS> {
     }
 
     fn calculate_size(&mut self, requested_size: Dimensions, env: &mut Environment<GS>) -> Dimensions {
-        self.dimension = self.child.calculate_size(requested_size, env);
+        self.dimension = self.child.calculate_size_cached(requested_size, env);
         self.dimension
     }
 
-    fn position_children(&mut self) {
-        let positioning = BasicLayouter::Center.position();
+    fn position_children(&mut self, env: &mut Environment<GS>) {
+        let positioning = BasicLayouter::Center.position(env);
         let position = self.position;
         let dimension = self.dimension;
 
@@ -37,7 +37,7 @@ impl<GS: GlobalState> Layout<GS> for Offset<GS> {
 
         self.child.set_position(child_position);
 
-        self.child.position_children();
+        self.child.position_children(env);
     }
 }
 
@@ -0,0 +1,105 @@
+use crate::prelude::*;
+use crate::widget::ChildRender;
+
+/// A widget calling a callback when the value of a state changes. The widget keeps the observed
+/// state synchronized, and is otherwise transparent to its child.
+#[derive(Debug, Clone, Widget)]
+pub struct OnChange<GS, T> where GS: GlobalState, T: StateContract + 'static {
+    id: Uuid,
+    child: Box<dyn Widget<GS>>,
+    position: Point,
+    dimension: Dimensions,
+    #[state] state: TState<T, GS>,
+}
+
+impl<GS: GlobalState, T: StateContract + 'static> OnChange<GS, T> {
+    pub fn new<C>(state: TState<T, GS>, callback: C, child: Box<dyn Widget<GS>>) -> Box<Self>
+        where C: Fn(&T, &T, &mut Environment<GS>, &mut GS) + Clone + 'static {
+        Box::new(OnChange {
+            id: Uuid::new_v4(),
+            child,
+            position: [0.0, 0.0],
+            dimension: [0.0, 0.0],
+            state: ObservedState::new(state, callback).into(),
+        })
+    }
+}
+
+impl<GS: GlobalState, T: StateContract + 'static> WidgetExt<GS> for OnChange<GS, T> {}
+
+impl<GS: GlobalState, T: StateContract + 'static> Layout<GS> for OnChange<GS, T> {
+    fn flexibility(&self) -> u32 {
+        self.child.flexibility()
+    }
+
+    fn calculate_size(&mut self, requested_size: Dimensions, env: &mut Environment<GS>) -> Dimensions {
+        self.dimension = self.child.calculate_size_cached(requested_size, env);
+        self.dimension
+    }
+
+    fn position_children(&mut self, env: &mut Environment<GS>) {
+        let positioning = BasicLayouter::Center.position(env);
+        let position = self.position;
+        let dimension = self.dimension;
+
+        positioning(position, dimension, &mut self.child);
+
+        self.child.position_children(env);
+    }
+}
+
+impl<GS: GlobalState, T: StateContract + 'static> CommonWidget<GS> for OnChange<GS, T> {
+    fn get_id(&self) -> Uuid {
+        self.id
+    }
+
+    fn set_id(&mut self, id: Uuid) {
+        self.id = id;
+    }
+
+    fn get_flag(&self) -> Flags {
+        Flags::EMPTY
+    }
+
+    fn get_children(&self) -> WidgetIter<GS> {
+        if self.child.get_flag() == Flags::PROXY {
+            self.child.get_children()
+        } else {
+            WidgetIter::single(self.child.deref())
+        }
+    }
+
+    fn get_children_mut(&mut self) -> WidgetIterMut<GS> {
+        if self.child.get_flag() == Flags::PROXY {
+            self.child.get_children_mut()
+        } else {
+            WidgetIterMut::single(self.child.deref_mut())
+        }
+    }
+
+    fn get_proxied_children(&mut self) -> WidgetIterMut<GS> {
+        WidgetIterMut::single(self.child.deref_mut())
+    }
+
+    fn get_proxied_children_rev(&mut self) -> WidgetIterMut<GS> {
+        WidgetIterMut::single(self.child.deref_mut())
+    }
+
+    fn get_position(&self) -> Point {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Dimensions) {
+        self.position = position;
+    }
+
+    fn get_dimension(&self) -> Dimensions {
+        self.dimension
+    }
+
+    fn set_dimension(&mut self, dimensions: Dimensions) {
+        self.dimension = dimensions
+    }
+}
+
+impl<GS: GlobalState, T: StateContract + 'static> ChildRender for OnChange<GS, T> {}
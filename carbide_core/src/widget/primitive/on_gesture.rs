@@ -0,0 +1,123 @@
+use crate::event_handler::{MouseEvent, WidgetEvent};
+use crate::gesture::Gesture;
+use crate::prelude::*;
+use crate::widget::ChildRender;
+
+/// A widget feeding the events it receives to a gesture recognizer. The mouse events are inside
+/// the widget when its child, or one of the descendants of the child, is the topmost widget under
+/// the mouse. The touch events are inside when the finger is within the bounds of the widget.
+#[derive(Debug, Clone, Widget)]
+#[event(handle_mouse_event, handle_other_event)]
+pub struct OnGesture<GS> where GS: GlobalState {
+    id: Uuid,
+    child: Box<dyn Widget<GS>>,
+    position: Point,
+    dimension: Dimensions,
+    gesture: Box<dyn Gesture<GS>>,
+}
+
+impl<GS: GlobalState> OnGesture<GS> {
+    pub fn new(gesture: Box<dyn Gesture<GS>>, child: Box<dyn Widget<GS>>) -> Box<Self> {
+        Box::new(OnGesture {
+            id: Uuid::new_v4(),
+            child,
+            position: [0.0, 0.0],
+            dimension: [0.0, 0.0],
+            gesture,
+        })
+    }
+
+    fn handle_mouse_event(&mut self, event: &MouseEvent, env: &mut Environment<GS>, global_state: &mut GS) {
+        let inside = env.is_hovered(self.id);
+        self.gesture.handle_mouse_event(event, inside, env, global_state);
+    }
+
+    fn handle_other_event(&mut self, event: &WidgetEvent, env: &mut Environment<GS>, global_state: &mut GS) {
+        match event {
+            WidgetEvent::Touch(touch_event) => {
+                let inside = self.is_inside(touch_event.get_location());
+                self.gesture.handle_touch_event(touch_event, inside, env, global_state);
+            }
+            _ => self.gesture.handle_other_event(event, env, global_state),
+        }
+    }
+}
+
+impl<GS: GlobalState> WidgetExt<GS> for OnGesture<GS> {}
+
+impl<GS: GlobalState> Layout<GS> for OnGesture<GS> {
+    fn flexibility(&self) -> u32 {
+        self.child.flexibility()
+    }
+
+    fn calculate_size(&mut self, requested_size: Dimensions, env: &mut Environment<GS>) -> Dimensions {
+        self.dimension = self.child.calculate_size_cached(requested_size, env);
+        self.dimension
+    }
+
+    fn position_children(&mut self, env: &mut Environment<GS>) {
+        let positioning = BasicLayouter::Center.position(env);
+        let position = self.position;
+        let dimension = self.dimension;
+
+        positioning(position, dimension, &mut self.child);
+
+        self.child.position_children(env);
+    }
+}
+
+impl<GS: GlobalState> CommonWidget<GS> for OnGesture<GS> {
+    fn get_id(&self) -> Uuid {
+        self.id
+    }
+
+    fn set_id(&mut self, id: Uuid) {
+        self.id = id;
+    }
+
+    fn get_flag(&self) -> Flags {
+        Flags::EMPTY
+    }
+
+    fn get_children(&self) -> WidgetIter<GS> {
+        if self.child.get_flag() == Flags::PROXY {
+            self.child.get_children()
+        } else {
+            WidgetIter::single(self.child.deref())
+        }
+    }
+
+    fn get_children_mut(&mut self) -> WidgetIterMut<GS> {
+        if self.child.get_flag() == Flags::PROXY {
+            self.child.get_children_mut()
+        } else {
+            WidgetIterMut::single(self.child.deref_mut())
+        }
+    }
+
+    fn get_proxied_children(&mut self) -> WidgetIterMut<GS> {
+        WidgetIterMut::single(self.child.deref_mut())
+    }
+
+    fn get_proxied_children_rev(&mut self) -> WidgetIterMut<GS> {
+        WidgetIterMut::single(self.child.deref_mut())
+    }
+
+    fn get_position(&self) -> Point {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Dimensions) {
+        self.position = position;
+    }
+
+    fn get_dimension(&self) -> Dimensions {
+        self.dimension
+    }
+
+    fn set_dimension(&mut self, dimensions: Dimensions) {
+        self.dimension = dimensions
+    }
+}
+
+impl<GS: GlobalState> ChildRender for OnGesture<GS> {}
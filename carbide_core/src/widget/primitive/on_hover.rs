@@ -0,0 +1,220 @@
+use crate::event_handler::MouseEvent;
+use crate::prelude::*;
+use crate::widget::ChildRender;
+
+/// A widget writing whether the mouse is over its child into a state. The child is hovered when
+/// it, or one of its descendants, is the topmost widget under the mouse.
+#[derive(Debug, Clone, Widget)]
+#[event(handle_mouse_event)]
+pub struct OnHover<GS> where GS: GlobalState {
+    id: Uuid,
+    child: Box<dyn Widget<GS>>,
+    position: Point,
+    dimension: Dimensions,
+    #[state] hovered: BoolState<GS>,
+}
+
+impl<GS: GlobalState> OnHover<GS> {
+    pub fn new(hovered: BoolState<GS>, child: Box<dyn Widget<GS>>) -> Box<Self> {
+        Box::new(OnHover {
+            id: Uuid::new_v4(),
+            child,
+            position: [0.0, 0.0],
+            dimension: [0.0, 0.0],
+            hovered,
+        })
+    }
+
+    fn handle_mouse_event(&mut self, event: &MouseEvent, env: &mut Environment<GS>, global_state: &mut GS) {
+        match event {
+            MouseEvent::Enter(_) => *self.hovered.get_value_mut(env, global_state) = true,
+            MouseEvent::Exit(_) => *self.hovered.get_value_mut(env, global_state) = false,
+            _ => {}
+        }
+    }
+}
+
+impl<GS: GlobalState> WidgetExt<GS> for OnHover<GS> {}
+
+impl<GS: GlobalState> Layout<GS> for OnHover<GS> {
+    fn flexibility(&self) -> u32 {
+        self.child.flexibility()
+    }
+
+    fn calculate_size(&mut self, requested_size: Dimensions, env: &mut Environment<GS>) -> Dimensions {
+        self.dimension = self.child.calculate_size_cached(requested_size, env);
+        self.dimension
+    }
+
+    fn position_children(&mut self, env: &mut Environment<GS>) {
+        let positioning = BasicLayouter::Center.position(env);
+        let position = self.position;
+        let dimension = self.dimension;
+
+        positioning(position, dimension, &mut self.child);
+
+        self.child.position_children(env);
+    }
+}
+
+impl<GS: GlobalState> CommonWidget<GS> for OnHover<GS> {
+    fn get_id(&self) -> Uuid {
+        self.id
+    }
+
+    fn set_id(&mut self, id: Uuid) {
+        self.id = id;
+    }
+
+    fn get_flag(&self) -> Flags {
+        Flags::EMPTY
+    }
+
+    fn get_children(&self) -> WidgetIter<GS> {
+        if self.child.get_flag() == Flags::PROXY {
+            self.child.get_children()
+        } else {
+            WidgetIter::single(self.child.deref())
+        }
+    }
+
+    fn get_children_mut(&mut self) -> WidgetIterMut<GS> {
+        if self.child.get_flag() == Flags::PROXY {
+            self.child.get_children_mut()
+        } else {
+            WidgetIterMut::single(self.child.deref_mut())
+        }
+    }
+
+    fn get_proxied_children(&mut self) -> WidgetIterMut<GS> {
+        WidgetIterMut::single(self.child.deref_mut())
+    }
+
+    fn get_proxied_children_rev(&mut self) -> WidgetIterMut<GS> {
+        WidgetIterMut::single(self.child.deref_mut())
+    }
+
+    fn get_position(&self) -> Point {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Dimensions) {
+        self.position = position;
+    }
+
+    fn get_dimension(&self) -> Dimensions {
+        self.dimension
+    }
+
+    fn set_dimension(&mut self, dimensions: Dimensions) {
+        self.dimension = dimensions
+    }
+}
+
+impl<GS: GlobalState> ChildRender for OnHover<GS> {}
+
+#[test]
+fn hover_moves_between_siblings() {
+    use crate::headless::HeadlessUi;
+    use crate::state::global_state::GState;
+    use crate::widget::HStack;
+
+    let mut ui = HeadlessUi::new([200.0, 100.0], 1.0, (false, false));
+
+    let first = GState::new(|hovered: &(bool, bool)| &hovered.0, |hovered: &mut (bool, bool)| &mut hovered.0);
+    let second = GState::new(|hovered: &(bool, bool)| &hovered.1, |hovered: &mut (bool, bool)| &mut hovered.1);
+
+    let root: Box<dyn Widget<(bool, bool)>> = HStack::initialize(vec![
+        Rectangle::initialize(vec![]).frame(50.0, 50.0).on_hover(first),
+        Rectangle::initialize(vec![]).frame(50.0, 50.0).on_hover(second),
+    ]).spacing(0.0);
+    *ui.widgets_mut() = root;
+    ui.draw();
+
+    // The stack is 100 wide and centered, so the rectangles span 50 to 100 and 100 to 150.
+    ui.move_mouse_to([75.0, 50.0]);
+    ui.update();
+    assert_eq!(*ui.global_state(), (true, false));
+
+    ui.move_mouse_to([125.0, 50.0]);
+    ui.update();
+    assert_eq!(*ui.global_state(), (false, true));
+
+    ui.move_mouse_to([10.0, 10.0]);
+    ui.update();
+    assert_eq!(*ui.global_state(), (false, false));
+}
+
+#[test]
+fn sibling_is_exited_before_the_next_is_entered() {
+    use crate::event_handler::MouseEvent;
+    use crate::gesture::Gesture;
+    use crate::headless::HeadlessUi;
+    use crate::widget::HStack;
+
+    type Log = Vec<String>;
+
+    #[derive(Debug, Clone)]
+    struct HoverLog(&'static str);
+
+    impl Gesture<Log> for HoverLog {
+        fn handle_mouse_event(&mut self, event: &MouseEvent, _: bool, _: &mut Environment<Log>, log: &mut Log) {
+            match event {
+                MouseEvent::Enter(_) => log.push(format!("enter {}", self.0)),
+                MouseEvent::Exit(_) => log.push(format!("exit {}", self.0)),
+                _ => {}
+            }
+        }
+    }
+
+    let mut ui = HeadlessUi::new([200.0, 100.0], 1.0, vec![]);
+
+    let root: Box<dyn Widget<Log>> = HStack::initialize(vec![
+        Rectangle::initialize(vec![]).frame(50.0, 50.0).gesture(HoverLog("first")),
+        Rectangle::initialize(vec![]).frame(50.0, 50.0).gesture(HoverLog("second")),
+    ]).spacing(0.0);
+    *ui.widgets_mut() = root;
+    ui.draw();
+
+    ui.move_mouse_to([75.0, 50.0]);
+    ui.update();
+    ui.move_mouse_to([125.0, 50.0]);
+    ui.update();
+    ui.move_mouse_to([10.0, 10.0]);
+    ui.update();
+
+    assert_eq!(ui.global_state(), &vec!["enter first", "exit first", "enter second", "exit second"]);
+}
+
+#[test]
+fn widget_moving_away_from_a_still_mouse_is_exited() {
+    use crate::environment::command_registry::Command;
+    use crate::headless::HeadlessUi;
+    use crate::input::{Key, ModifierKey};
+    use crate::state::global_state::GState;
+
+    let mut ui = HeadlessUi::new([200.0, 100.0], 1.0, (false, 0.0));
+
+    let hovered = GState::new(|state: &(bool, f64)| &state.0, |state: &mut (bool, f64)| &mut state.0);
+    let offset = GState::new(|state: &(bool, f64)| &state.1, |state: &mut (bool, f64)| &mut state.1);
+
+    let root: Box<dyn Widget<(bool, f64)>> = Rectangle::initialize(vec![])
+        .frame(50.0, 50.0)
+        .on_hover(hovered)
+        .offset(offset, 0.0);
+    *ui.widgets_mut() = root;
+    ui.environment_mut().commands_mut().register(Command::new("move", "Move")
+        .binding(Key::M, ModifierKey::NO_MODIFIER)
+        .on_execute(|_: &mut Environment<(bool, f64)>, state: &mut (bool, f64)| state.1 = 100.0));
+    ui.draw();
+
+    ui.move_mouse_to([100.0, 50.0]);
+    ui.update();
+    assert!(ui.global_state().0);
+
+    // The rectangle moves away without the mouse moving.
+    ui.press_key(Key::M, ModifierKey::NO_MODIFIER);
+    ui.draw();
+    ui.update();
+    assert!(!ui.global_state().0);
+}
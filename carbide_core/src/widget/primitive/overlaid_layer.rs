@@ -1,10 +1,10 @@
-use crate::event::event::Event;
+use crate::event::event::{Event, is_on_dispatch_route};
 use crate::event_handler::{KeyboardEvent, MouseEvent, WidgetEvent};
 use crate::prelude::*;
 
 #[derive(Debug, Clone, Widget)]
 #[state_sync(sync_state, update_all_widget_state, update_local_widget_state)]
-#[event(process_keyboard_event, process_mouse_event, process_other_event)]
+#[event(process_keyboard_event, process_mouse_event, process_other_event, hit_test)]
 pub struct OverlaidLayer<GS> where GS: GlobalState {
     id: Uuid,
     child: Box<dyn Widget<GS>>,
@@ -29,57 +29,84 @@ impl<GS: GlobalState> OverlaidLayer<GS> {
         }
     }
 
-    fn process_mouse_event(&mut self, event: &MouseEvent, consumed: &bool, env: &mut Environment<GS>, global_state: &mut GS) {
+    fn process_mouse_event(&mut self, event: &MouseEvent, env: &mut Environment<GS>, global_state: &mut GS) {
         self.update_all_widget_state(env, global_state);
 
-        if !*consumed {
-            self.handle_mouse_event(event, consumed, env, global_state);
-        }
+        self.handle_mouse_event_before_children(event, env, global_state);
 
         self.insert_local_state(env);
 
 
+        let along_path = env.is_dispatching_along_path();
+
         if let Some(overlay) = &mut self.overlay {
-            overlay.process_mouse_event(event, &consumed, env, global_state);
-            if *consumed { return (); }
+            if !along_path || is_on_dispatch_route(overlay.deref(), env) {
+                overlay.process_mouse_event(event, env, global_state);
+            }
 
             if !self.steal_events_when_some {
                 for child in self.get_proxied_children() {
-                    child.process_mouse_event(event, &consumed, env, global_state);
-                    if *consumed { return (); }
+                    if along_path && !is_on_dispatch_route(child, env) { continue }
+
+                    child.process_mouse_event(event, env, global_state);
                 }
             }
         } else {
             for child in self.get_proxied_children() {
-                child.process_mouse_event(event, &consumed, env, global_state);
-                if *consumed { return (); }
+                if along_path && !is_on_dispatch_route(child, env) { continue }
+
+                child.process_mouse_event(event, env, global_state);
             }
         }
 
+        self.handle_mouse_event_after_children(event, env, global_state);
+
         self.update_local_widget_state(env)
     }
 
+    /// The overlay is drawn on top of the child, so it is tested first.
+    fn hit_test(&self, point: Point, path: &mut Vec<Uuid>) -> bool {
+        if let Some(overlay) = &self.overlay {
+            if overlay.hit_test(point, path) {
+                path.push(self.id);
+                return true
+            }
+        }
+
+        self.hit_test_default(point, path)
+    }
+
     fn process_keyboard_event(&mut self, event: &KeyboardEvent, env: &mut Environment<GS>, global_state: &mut GS) {
         self.update_all_widget_state(env, global_state);
 
-        self.handle_keyboard_event(event, env, global_state);
+        self.handle_keyboard_event_before_children(event, env, global_state);
 
         self.insert_local_state(env);
 
+        let along_path = env.is_dispatching_along_path();
+
         if let Some(overlay) = &mut self.overlay {
-            overlay.process_keyboard_event(event, env, global_state);
+            if !along_path || is_on_dispatch_route(overlay.deref(), env) {
+                overlay.process_keyboard_event(event, env, global_state);
+            }
 
             if !self.steal_events_when_some {
                 for child in self.get_proxied_children() {
+                    if along_path && !is_on_dispatch_route(child, env) { continue }
+
                     child.process_keyboard_event(event, env, global_state);
                 }
             }
         } else {
             for child in self.get_proxied_children() {
+                if along_path && !is_on_dispatch_route(child, env) { continue }
+
                 child.process_keyboard_event(event, env, global_state);
             }
         }
 
+        self.handle_keyboard_event_after_children(event, env, global_state);
+
         self.update_local_widget_state(env)
     }
 
@@ -139,13 +166,17 @@ impl<GS: GlobalState> OverlaidLayer<GS> {
                 self.current_overlay_id = Some(overlay.get_id());
                 self.overlay = Some(overlay);
             }
-        } else {
+        } else if self.overlay.is_some() {
             self.current_overlay_id = None;
             self.overlay = None;
+            env.invalidate_layout_of(self.id);
         }
 
+        // The overlay is not a child of the layer, so its states are not tracked by the layout.
+        // It is sized again each time it is shown instead.
         if let Some(overlay) = &mut self.overlay {
             overlay.sync_state(env, global_state);
+            env.invalidate_layout_of(self.id);
         }
 
 
@@ -173,24 +204,24 @@ impl<GS: GlobalState> Layout<GS> for OverlaidLayer<GS> {
     }
 
     fn calculate_size(&mut self, requested_size: Dimensions, env: &mut Environment<GS>) -> Dimensions {
-        self.dimension = self.child.calculate_size(requested_size, env);
+        self.dimension = self.child.calculate_size_cached(requested_size, env);
 
         if let Some(overlay) = &mut self.overlay {
-            overlay.calculate_size(requested_size, env);
+            overlay.calculate_size_cached(requested_size, env);
         }
 
         self.dimension
     }
 
-    fn position_children(&mut self) {
-        let positioning = BasicLayouter::Center.position();
+    fn position_children(&mut self, env: &mut Environment<GS>) {
+        let positioning = BasicLayouter::Center.position(env);
         let position = self.position;
         let dimension = self.dimension;
 
         positioning(position, dimension, &mut self.child);
-        self.child.position_children();
+        self.child.position_children(env);
         if let Some(overlay) = &mut self.overlay {
-            overlay.position_children();
+            overlay.position_children(env);
         }
     }
 }
@@ -0,0 +1,121 @@
+use crate::prelude::*;
+use crate::widget::ChildRender;
+
+/// A widget drawing a widget in front of its child. The size is decided by the child alone, and
+/// the overlay is offered the size of the child and aligned within it.
+#[derive(Debug, Clone, Widget)]
+pub struct Overlay<GS> where GS: GlobalState {
+    id: Uuid,
+    child: Box<dyn Widget<GS>>,
+    overlay: Box<dyn Widget<GS>>,
+    position: Point,
+    dimension: Dimensions,
+    alignment: BasicLayouter,
+}
+
+impl<GS: GlobalState> Overlay<GS> {
+    pub fn new(child: Box<dyn Widget<GS>>, overlay: Box<dyn Widget<GS>>, alignment: BasicLayouter) -> Box<Self> {
+        Box::new(Overlay {
+            id: Uuid::new_v4(),
+            child,
+            overlay,
+            position: [0.0, 0.0],
+            dimension: [0.0, 0.0],
+            alignment,
+        })
+    }
+}
+
+impl<GS: GlobalState> WidgetExt<GS> for Overlay<GS> {}
+
+impl<GS: GlobalState> Layout<GS> for Overlay<GS> {
+    fn flexibility(&self) -> u32 {
+        self.child.flexibility()
+    }
+
+    fn calculate_size(&mut self, requested_size: Dimensions, env: &mut Environment<GS>) -> Dimensions {
+        self.dimension = self.child.calculate_size_cached(requested_size, env);
+
+        self.overlay.calculate_size_cached(self.dimension, env);
+
+        self.dimension
+    }
+
+    fn position_children(&mut self, env: &mut Environment<GS>) {
+        let position = self.position;
+        let dimension = self.dimension;
+
+        let child_positioning = BasicLayouter::Center.position(env);
+        let overlay_positioning = self.alignment.position(env);
+
+        child_positioning(position, dimension, &mut self.child);
+        overlay_positioning(position, dimension, &mut self.overlay);
+
+        self.child.position_children(env);
+        self.overlay.position_children(env);
+    }
+}
+
+impl<GS: GlobalState> CommonWidget<GS> for Overlay<GS> {
+    fn get_id(&self) -> Uuid {
+        self.id
+    }
+
+    fn set_id(&mut self, id: Uuid) {
+        self.id = id;
+    }
+
+    fn get_flag(&self) -> Flags {
+        Flags::EMPTY
+    }
+
+    fn get_children(&self) -> WidgetIter<GS> {
+        vec![self.child.deref(), self.overlay.deref()]
+            .into_iter()
+            .rfold(WidgetIter::Empty, |acc, x| {
+                if x.get_flag() == Flags::PROXY {
+                    WidgetIter::Multi(Box::new(x.get_children()), Box::new(acc))
+                } else {
+                    WidgetIter::Single(x, Box::new(acc))
+                }
+            })
+    }
+
+    fn get_children_mut(&mut self) -> WidgetIterMut<GS> {
+        vec![self.child.deref_mut(), self.overlay.deref_mut()]
+            .into_iter()
+            .rfold(WidgetIterMut::Empty, |acc, x| {
+                if x.get_flag() == Flags::PROXY {
+                    WidgetIterMut::Multi(Box::new(x.get_children_mut()), Box::new(acc))
+                } else {
+                    WidgetIterMut::Single(x, Box::new(acc))
+                }
+            })
+    }
+
+    fn get_proxied_children(&mut self) -> WidgetIterMut<GS> {
+        WidgetIterMut::Single(self.child.deref_mut(), Box::new(WidgetIterMut::single(self.overlay.deref_mut())))
+    }
+
+    fn get_proxied_children_rev(&mut self) -> WidgetIterMut<GS> {
+        WidgetIterMut::Single(self.overlay.deref_mut(), Box::new(WidgetIterMut::single(self.child.deref_mut())))
+    }
+
+    fn get_position(&self) -> Point {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Dimensions) {
+        self.position = position;
+    }
+
+    fn get_dimension(&self) -> Dimensions {
+        self.dimension
+    }
+
+    fn set_dimension(&mut self, dimensions: Dimensions) {
+        self.dimension = dimensions
+    }
+}
+
+impl<GS: GlobalState> ChildRender for Overlay<GS> {}
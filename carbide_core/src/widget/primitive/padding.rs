@@ -19,7 +19,7 @@ impl<GS: GlobalState> WidgetExt<GS> for Padding<GS> {}
 impl<S: GlobalState> Padding<S> {
     pub fn init(edge_insets: EdgeInsets, child: Box<dyn Widget<S>>) -> Box<Self> {
         Box::new(Padding {
-            id: Default::default(),
+            id: Uuid::new_v4(),
             child,
             position: [0.0, 0.0],
             dimension: [0.0, 0.0],
@@ -91,21 +91,42 @@ impl<GS: GlobalState> Layout<GS> for Padding<GS> {
     fn calculate_size(&mut self, requested_size: Dimensions, env: &mut Environment<GS>) -> Dimensions {
         let dimensions = [requested_size[0] - self.edge_insets.left - self.edge_insets.right, requested_size[1] - self.edge_insets.top - self.edge_insets.bottom];
 
-        let child_dimensions = self.child.calculate_size(dimensions, env);
+        let child_dimensions = self.child.calculate_size_cached(dimensions, env);
 
         self.dimension = [child_dimensions[0] + self.edge_insets.left + self.edge_insets.right, child_dimensions[1] + self.edge_insets.top + self.edge_insets.bottom];
 
         self.dimension
     }
 
-    fn position_children(&mut self) {
-        let positioning = BasicLayouter::Center.position();
-        let position = [self.position[0] + self.edge_insets.left, self.position[1] + self.edge_insets.top];
+    fn position_children(&mut self, env: &mut Environment<GS>) {
+        let positioning = BasicLayouter::Center.position(env);
+        // The left inset is applied at the right edge in a right to left layout, such that
+        // the leading inset stays at the leading edge.
+        let leading = match env.get_layout_direction() {
+            LayoutDirection::LeftToRight => self.edge_insets.left,
+            LayoutDirection::RightToLeft => self.edge_insets.right,
+        };
+
+        let position = [self.position[0] + leading, self.position[1] + self.edge_insets.top];
         let dimension = [self.dimension[0] - self.edge_insets.left - self.edge_insets.right, self.dimension[1] - self.edge_insets.top - self.edge_insets.bottom];
 
         positioning(position, dimension, &mut self.child);
-        self.child.position_children();
+        self.child.position_children(env);
     }
 }
 
-impl<S: GlobalState> ChildRender for Padding<S> {}
\ No newline at end of file
+impl<S: GlobalState> ChildRender for Padding<S> {}
+#[test]
+fn left_inset_is_applied_at_the_right_edge_in_a_right_to_left_layout() {
+    let mut env = Environment::<()>::new(vec![], [100.0, 100.0], 1.0);
+    let mut padding = Padding::init(EdgeInsets::single(0.0, 0.0, 10.0, 30.0), Rectangle::initialize(vec![]).frame(20.0, 10.0));
+
+    padding.calculate_size([100.0, 100.0], &mut env);
+    padding.set_position([0.0, 0.0]);
+    padding.position_children(&mut env);
+    assert_eq!(padding.child.get_x(), 10.0);
+
+    env.push(EnvironmentVariable::LayoutDirection { value: LayoutDirection::RightToLeft });
+    padding.position_children(&mut env);
+    assert_eq!(padding.child.get_x(), 30.0);
+}
@@ -1,12 +1,19 @@
+use std::time::Duration;
+
+use instant::Instant;
+
 use crate::draw::shape::vertex::Vertex;
-use crate::event_handler::{MouseEvent, WidgetEvent};
+use crate::event::event::Event;
+use crate::event::touch;
+use crate::event_handler::{MouseEvent, TouchEvent, WidgetEvent, WindowEvent};
 use crate::input::MouseButton;
+use crate::layout::viewport::Viewport;
 use crate::prelude::*;
 use crate::widget::types::scroll_direction::ScrollDirection;
 
 /// A basic, non-interactive rectangle shape widget.
 #[derive(Debug, Clone, Widget)]
-#[event(handle_mouse_event, handle_other_event)]
+#[event(handle_mouse_event, handle_other_event, hit_test)]
 #[state_sync(update_all_widget_state)]
 pub struct Scroll<GS> where GS: GlobalState {
     id: Uuid,
@@ -14,6 +21,10 @@ pub struct Scroll<GS> where GS: GlobalState {
     position: Point,
     dimension: Dimensions,
     scroll_offset: [f64; 2],
+    /// The position of the child in the latest layout.
+    content_position: Point,
+    /// The position of the visible area relative to the content in the latest layout.
+    latest_viewport_offset: Point,
     scroll_directions: ScrollDirection,
     scrollbar_horizontal: Box<dyn Widget<GS>>,
     scrollbar_vertical: Box<dyn Widget<GS>>,
@@ -23,8 +34,39 @@ pub struct Scroll<GS> where GS: GlobalState {
     horizontal_scrollbar_hovered: bool,
     scrollbar_horizontal_background: Box<dyn Widget<GS>>,
     scrollbar_vertical_background: Box<dyn Widget<GS>>,
+    layout_direction: LayoutDirection,
+    touch_drag: Option<TouchDrag>,
+    /// The velocity of the content after a touch drag, in points per second, and the time the
+    /// content was last moved by it.
+    momentum: Option<(Point, Instant)>,
+}
+
+/// A finger dragging the content of a scroll view.
+#[derive(Debug, Clone)]
+struct TouchDrag {
+    id: touch::Id,
+    start: Point,
+    last: Point,
+    time: Instant,
+    velocity: Point,
+    /// True once the finger moved far enough to scroll. Until then, the touch might be a tap on
+    /// the content.
+    dragging: bool,
 }
 
+/// The distance a finger needs to move before the content is dragged.
+const TOUCH_SLOP: Scalar = 10.0;
+
+/// The part of the momentum kept after a second.
+const MOMENTUM_DECAY: f64 = 0.1;
+
+/// The speed in points per second below which the content stops.
+const MINIMUM_MOMENTUM_SPEED: f64 = 20.0;
+
+/// If the finger is held still for longer than this before it is lifted, the content is not
+/// flung.
+const MOMENTUM_TIMEOUT: Duration = Duration::from_millis(100);
+
 impl<GS: GlobalState> WidgetExt<GS> for Scroll<GS> {}
 
 impl<S: GlobalState> Scroll<S> {
@@ -75,6 +117,8 @@ impl<S: GlobalState> Scroll<S> {
             position: [0.0, 0.0],
             dimension: [0.0, 0.0],
             scroll_offset: [0.0, 0.0],
+            content_position: [0.0, 0.0],
+            latest_viewport_offset: [0.0, 0.0],
             scroll_directions: ScrollDirection::Both,
             scrollbar_horizontal: Rectangle::initialize(vec![])
                 .fill(EnvironmentColor::Gray)
@@ -88,10 +132,57 @@ impl<S: GlobalState> Scroll<S> {
             horizontal_scrollbar_hovered: false,
             scrollbar_horizontal_background: Rectangle::initialize(vec![]).fill(Color::Rgba(0.0, 0.0, 0.0, 0.5)).frame(100.0, 10.0),
             scrollbar_vertical_background: Rectangle::initialize(vec![]).fill(Color::Rgba(0.0, 0.0, 0.0, 0.5)).frame(10.0, 100.0),
+            layout_direction: LayoutDirection::LeftToRight,
+            touch_drag: None,
+            momentum: None,
         })
     }
 
-    fn handle_mouse_event(&mut self, event: &MouseEvent, _: &bool, _: &mut Environment<S>, _: &mut S) {
+    /// The horizontal offset is the distance scrolled from the leading edge, so in a right to
+    /// left layout it grows when the content moves right. Horizontal deltas are multiplied by
+    /// this to scroll in the same physical direction in both layout directions.
+    fn horizontal_sign(&self) -> f64 {
+        match self.layout_direction {
+            LayoutDirection::LeftToRight => 1.0,
+            LayoutDirection::RightToLeft => -1.0,
+        }
+    }
+
+    /// The horizontal position of the content, given the width of the content.
+    fn content_x(&self, content_width: Scalar) -> Scalar {
+        match self.layout_direction {
+            LayoutDirection::LeftToRight => self.position[0] - self.scroll_offset[0],
+            LayoutDirection::RightToLeft => self.position[0] + self.dimension[0] - content_width + self.scroll_offset[0],
+        }
+    }
+
+    /// The position of the visible area relative to the content. In a right to left layout the
+    /// content is aligned to the trailing edge, so its width from the previous layout is used.
+    fn viewport_offset(&self) -> Point {
+        let x = match self.layout_direction {
+            LayoutDirection::LeftToRight => self.scroll_offset[0],
+            LayoutDirection::RightToLeft => self.child.get_width() - self.dimension[0] - self.scroll_offset[0],
+        };
+
+        [x, -self.scroll_offset[1]]
+    }
+
+    /// The content outside the scroll view is clipped, and can not be hovered.
+    fn hit_test(&self, point: Point, path: &mut Vec<Uuid>) -> bool {
+        self.is_inside(point) && self.hit_test_default(point, path)
+    }
+
+    fn handle_mouse_event(&mut self, event: &MouseEvent, env: &mut Environment<S>, _: &mut S) {
+        let scroll_offset = self.scroll_offset;
+
+        self.handle_scroll_event(event);
+
+        if self.scroll_offset != scroll_offset {
+            env.invalidate_layout_of(self.id);
+        }
+    }
+
+    fn handle_scroll_event(&mut self, event: &MouseEvent) {
         match event {
             MouseEvent::Scroll { x, y, modifiers, .. } => {
                 if !self.is_inside(event.get_current_mouse_position()) { return }
@@ -110,7 +201,7 @@ impl<S: GlobalState> Scroll<S> {
 
                 if self.scroll_directions == ScrollDirection::Both ||
                     self.scroll_directions == ScrollDirection::Horizontal {
-                    let offset_multiplier = 1.0; //self.child.get_width() / self.get_width();
+                    let offset_multiplier = self.horizontal_sign(); //self.child.get_width() / self.get_width();
                     if modifiers.contains(piston_input::keyboard::ModifierKey::SHIFT) {
                         self.scroll_offset[0] += y * offset_multiplier;
                     } else {
@@ -142,7 +233,7 @@ impl<S: GlobalState> Scroll<S> {
                 }
 
                 if self.scrollbar_horizontal_background.is_inside(*point) && !self.scrollbar_horizontal.is_inside(*point) {
-                    let offset_multiplier = self.child.get_width() / self.get_width();
+                    let offset_multiplier = self.horizontal_sign() * self.child.get_width() / self.get_width();
 
                     let middle_of_scrollbar = self.scrollbar_horizontal.get_x() + self.scrollbar_horizontal.get_width() / 2.0;
 
@@ -178,13 +269,14 @@ impl<S: GlobalState> Scroll<S> {
                     }
                 } else {
                     if self.is_inside([to[0], self.get_y()]) {
-                        let offset_multiplier = self.child.get_width() / self.get_width();
+                        let offset_multiplier = self.horizontal_sign() * self.child.get_width() / self.get_width();
                         self.scroll_offset[0] += delta_xy[0] * offset_multiplier;
                         self.keep_x_within_bounds();
                     } else if to[0] < self.get_x() {
-                        self.scroll_offset[0] = 0.0;
+                        self.scroll_offset[0] = -self.horizontal_sign() * f64::INFINITY;
+                        self.keep_x_within_bounds();
                     } else if to[0] > self.get_x() + self.get_width() {
-                        self.scroll_offset[0] = f64::INFINITY;
+                        self.scroll_offset[0] = self.horizontal_sign() * f64::INFINITY;
                         self.keep_x_within_bounds();
                     }
                 }
@@ -193,8 +285,133 @@ impl<S: GlobalState> Scroll<S> {
         }
     }
 
-    fn handle_other_event(&mut self, event: &WidgetEvent, _: &mut Environment<S>, _: &mut S) {
+    /// Move the content by the delta, in the directions the view scrolls.
+    fn scroll_by(&mut self, delta: Point) {
+        if self.scroll_directions == ScrollDirection::Both ||
+            self.scroll_directions == ScrollDirection::Vertical {
+            self.scroll_offset[1] += delta[1];
+            self.keep_y_within_bounds();
+        }
+
+        if self.scroll_directions == ScrollDirection::Both ||
+            self.scroll_directions == ScrollDirection::Horizontal {
+            self.scroll_offset[0] -= delta[0] * self.horizontal_sign();
+            self.keep_x_within_bounds();
+        }
+    }
+
+    /// Drag the content with a finger. Once the finger moved far enough to drag the content, the
+    /// touches are consumed, which keeps the content from getting mouse events for them. Lifting
+    /// the finger while it moves flings the content.
+    fn handle_touch_event(&mut self, event: &TouchEvent, env: &mut Environment<S>) {
+        match *event {
+            TouchEvent::Start(id, location) => {
+                if self.touch_drag.is_none() && self.is_inside(location) {
+                    self.momentum = None;
+                    self.touch_drag = Some(TouchDrag {
+                        id,
+                        start: location,
+                        last: location,
+                        time: Instant::now(),
+                        velocity: [0.0, 0.0],
+                        dragging: false,
+                    });
+                }
+            }
+            TouchEvent::Move(id, location) => {
+                let mut delta = None;
+
+                if let Some(drag) = &mut self.touch_drag {
+                    if drag.id != id { return }
+
+                    let now = Instant::now();
+                    let moved = [location[0] - drag.last[0], location[1] - drag.last[1]];
+                    let seconds = now.duration_since(drag.time).as_secs_f64();
+
+                    if seconds > 0.0 {
+                        drag.velocity = [moved[0] / seconds, moved[1] / seconds];
+                    }
+
+                    drag.last = location;
+                    drag.time = now;
+
+                    let total = [location[0] - drag.start[0], location[1] - drag.start[1]];
+
+                    if total[0].hypot(total[1]) >= TOUCH_SLOP {
+                        drag.dragging = true;
+                    }
+
+                    if drag.dragging {
+                        delta = Some(moved);
+                    }
+                }
+
+                if let Some(delta) = delta {
+                    env.stop_propagation();
+                    self.scroll_by(delta);
+                    env.invalidate_layout_of(self.id);
+                }
+            }
+            TouchEvent::End(id, _) | TouchEvent::Cancel(id, _) => {
+                let drag = match self.touch_drag.take() {
+                    Some(drag) if drag.id == id => drag,
+                    other => {
+                        self.touch_drag = other;
+                        return
+                    }
+                };
+
+                if drag.dragging {
+                    env.stop_propagation();
+
+                    if matches!(event, TouchEvent::End(..)) && drag.time.elapsed() < MOMENTUM_TIMEOUT {
+                        self.momentum = Some((drag.velocity, Instant::now()));
+                        env.request_tick();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Move the flung content by the time passed since it was last moved, and slow it down.
+    fn step_momentum(&mut self, env: &mut Environment<S>) {
+        let (velocity, time) = match self.momentum {
+            Some(momentum) => momentum,
+            None => return,
+        };
+
+        let now = Instant::now();
+        let seconds = now.duration_since(time).as_secs_f64();
+        let scroll_offset = self.scroll_offset;
+
+        self.scroll_by([velocity[0] * seconds, velocity[1] * seconds]);
+
+        if self.scroll_offset != scroll_offset {
+            env.invalidate_layout_of(self.id);
+        }
+
+        let decay = MOMENTUM_DECAY.powf(seconds);
+        let velocity = [velocity[0] * decay, velocity[1] * decay];
+
+        // The content stops when it slowed down, or when it reached the end.
+        let reached_end = seconds > 0.0 && self.scroll_offset == scroll_offset;
+
+        if velocity[0].hypot(velocity[1]) < MINIMUM_MOMENTUM_SPEED || reached_end {
+            self.momentum = None;
+        } else {
+            self.momentum = Some((velocity, now));
+            env.request_tick();
+        }
+    }
+
+    fn handle_other_event(&mut self, event: &WidgetEvent, env: &mut Environment<S>, _: &mut S) {
         match event {
+            WidgetEvent::Touch(touch_event) => {
+                self.handle_touch_event(touch_event, env);
+            }
+            WidgetEvent::Window(WindowEvent::Tick) => {
+                self.step_momentum(env);
+            }
             WidgetEvent::Window(_) => {
                 self.keep_y_within_bounds();
                 self.keep_x_within_bounds();
@@ -210,7 +427,32 @@ impl<GS: GlobalState> Layout<GS> for Scroll<GS> {
     }
 
     fn calculate_size(&mut self, requested_size: Dimensions, env: &mut Environment<GS>) -> Dimensions {
-        self.child.calculate_size(requested_size, env);
+        // Keep the offset within the bounds of the previous size of the child, such that the
+        // viewport given to the child matches where it is positioned as closely as possible.
+        self.keep_y_within_bounds();
+        self.keep_x_within_bounds();
+
+        self.layout_direction = env.get_layout_direction();
+        self.dimension = requested_size;
+
+        // The content depending on the viewport, like lazy stacks, needs to be sized again when
+        // scrolled.
+        let viewport_offset = self.viewport_offset();
+
+        if viewport_offset != self.latest_viewport_offset {
+            self.latest_viewport_offset = viewport_offset;
+            env.invalidate_layout_of_tree(&*self.child);
+        }
+
+        env.push_viewport(Viewport {
+            offset: viewport_offset,
+            dimension: requested_size,
+            content_position: self.content_position,
+        });
+
+        self.child.calculate_size_cached(requested_size, env);
+
+        env.pop_viewport();
 
         self.keep_y_within_bounds();
         self.keep_x_within_bounds();
@@ -231,10 +473,10 @@ impl<GS: GlobalState> Layout<GS> for Scroll<GS> {
             let height = (max_height - min_height) * percent_height.min(1.0) + min_height - horizontal_height;
 
             self.scrollbar_vertical.set_height(height);
-            self.scrollbar_vertical.calculate_size(requested_size, env);
+            self.scrollbar_vertical.calculate_size_cached(requested_size, env);
 
             self.scrollbar_vertical_background.set_height(requested_size[1]);
-            self.scrollbar_vertical_background.calculate_size(requested_size, env);
+            self.scrollbar_vertical_background.calculate_size_cached(requested_size, env);
         }
 
         if self.scroll_directions == ScrollDirection::Both ||
@@ -252,16 +494,16 @@ impl<GS: GlobalState> Layout<GS> for Scroll<GS> {
             let width = (max_width - min_width) * percent_width.min(1.0) + min_width - vertical_width;
 
             self.scrollbar_horizontal.set_width(width);
-            self.scrollbar_horizontal.calculate_size(requested_size, env);
+            self.scrollbar_horizontal.calculate_size_cached(requested_size, env);
             self.scrollbar_horizontal_background.set_width(requested_size[0]);
-            self.scrollbar_horizontal_background.calculate_size(requested_size, env);
+            self.scrollbar_horizontal_background.calculate_size_cached(requested_size, env);
         }
 
         requested_size
     }
 
-    fn position_children(&mut self) {
-        let positioning = BasicLayouter::TopLeading.position(); // Top for center
+    fn position_children(&mut self, env: &mut Environment<GS>) {
+        let positioning = BasicLayouter::TopLeading.position(env); // Top for center
         let position = self.position;
         let dimension = self.dimension;
 
@@ -269,12 +511,18 @@ impl<GS: GlobalState> Layout<GS> for Scroll<GS> {
 
         let child_position = self.child.get_position();
 
-        self.child.set_position([child_position[0] - self.scroll_offset[0], child_position[1] + self.scroll_offset[1]]);
+        self.child.set_position([self.content_x(self.child.get_width()), child_position[1] + self.scroll_offset[1]]);
+        self.content_position = self.child.get_position();
 
 
-        // Position scrollbars
-        self.scrollbar_vertical.set_position(self.get_position().add([self.dimension[0] - self.scrollbar_vertical.get_width(), 0.0]));
-        self.scrollbar_vertical_background.set_position(self.get_position().add([self.dimension[0] - self.scrollbar_vertical.get_width(), 0.0]));
+        // Position scrollbars. The vertical scrollbar is placed at the trailing edge.
+        let vertical_scrollbar_x = match self.layout_direction {
+            LayoutDirection::LeftToRight => self.dimension[0] - self.scrollbar_vertical.get_width(),
+            LayoutDirection::RightToLeft => 0.0,
+        };
+
+        self.scrollbar_vertical.set_position(self.get_position().add([vertical_scrollbar_x, 0.0]));
+        self.scrollbar_vertical_background.set_position(self.get_position().add([vertical_scrollbar_x, 0.0]));
 
 
         let scroll_vertical_percent = if self.child.get_height() - self.get_height() != 0.0 {
@@ -302,20 +550,31 @@ impl<GS: GlobalState> Layout<GS> for Scroll<GS> {
             0.0
         };
 
+        // The scrollbar starts at the right edge in a right to left layout.
+        let scroll_horizontal_percent = match self.layout_direction {
+            LayoutDirection::LeftToRight => scroll_horizontal_percent,
+            LayoutDirection::RightToLeft => 1.0 - scroll_horizontal_percent,
+        };
+
         let vertical_width = if self.scroll_directions == ScrollDirection::Both && self.child.get_height() > self.get_height() {
             self.scrollbar_vertical.get_width()
         } else {
             0.0
         };
 
-        self.scrollbar_horizontal.set_position(self.scrollbar_horizontal.get_position().add([(self.get_width() - vertical_width - self.scrollbar_horizontal.get_width()) * scroll_horizontal_percent, 0.0]));
+        let horizontal_scrollbar_start = match self.layout_direction {
+            LayoutDirection::LeftToRight => 0.0,
+            LayoutDirection::RightToLeft => vertical_width,
+        };
+
+        self.scrollbar_horizontal.set_position(self.scrollbar_horizontal.get_position().add([horizontal_scrollbar_start + (self.get_width() - vertical_width - self.scrollbar_horizontal.get_width()) * scroll_horizontal_percent, 0.0]));
 
 
-        self.scrollbar_vertical.position_children();
-        self.scrollbar_horizontal.position_children();
-        self.scrollbar_vertical_background.position_children();
-        self.scrollbar_horizontal_background.position_children();
-        self.child.position_children();
+        self.scrollbar_vertical.position_children(env);
+        self.scrollbar_horizontal.position_children(env);
+        self.scrollbar_vertical_background.position_children(env);
+        self.scrollbar_horizontal_background.position_children(env);
+        self.child.position_children(env);
     }
 }
 
@@ -403,6 +662,97 @@ impl<GS: GlobalState> Render<GS> for Scroll<GS> {
     }
 }
 
+#[test]
+fn lazy_content_follows_the_scroll_offset_in_the_same_layout() {
+    use crate::widget::LazyStack;
+
+    let mut env = Environment::<()>::new(vec![], [100.0, 100.0], 1.0);
+
+    let ids: Vec<u32> = (0..100).collect();
+    let row: Box<dyn Widget<()>> = Rectangle::initialize(vec![]).frame(50.0, 20.0);
+    let mut scroll = Scroll::new(LazyStack::vertical(ids, row).spacing(0.0));
+
+    scroll.calculate_size([100.0, 100.0], &mut env);
+    scroll.position_children(&mut env);
+
+    // Rows 10 and onwards are visible when scrolled 210 down, with row 10 starting at 200.
+    scroll.scroll_offset = [0.0, -210.0];
+    scroll.calculate_size([100.0, 100.0], &mut env);
+    scroll.position_children(&mut env);
+
+    let first_row = scroll.child.get_children().next().unwrap();
+    assert_eq!(first_row.get_position(), [0.0, -10.0]);
+}
+
+#[test]
+fn content_starts_at_the_right_edge_in_a_right_to_left_layout() {
+    let mut env = Environment::<()>::new(vec![], [100.0, 100.0], 1.0);
+    env.push(EnvironmentVariable::LayoutDirection { value: LayoutDirection::RightToLeft });
+
+    let mut scroll = Scroll::new(Rectangle::initialize(vec![]).frame(200.0, 50.0))
+        .set_scroll_direction(ScrollDirection::Horizontal);
 
+    scroll.calculate_size([100.0, 100.0], &mut env);
+    scroll.set_position([0.0, 0.0]);
+    scroll.position_children(&mut env);
+    assert_eq!(scroll.child.get_x(), -100.0);
 
+    // Scrolling towards the trailing edge moves the content to the right.
+    scroll.scroll_offset = [10.0, 0.0];
+    scroll.position_children(&mut env);
+    assert_eq!(scroll.child.get_x(), -90.0);
+}
+
+#[test]
+fn finger_drags_the_content_once_moved_past_the_slop() {
+    let mut env = Environment::<()>::new(vec![], [100.0, 100.0], 1.0);
+    let mut scroll = Scroll::new(Rectangle::initialize(vec![]).frame(100.0, 400.0));
+
+    scroll.calculate_size([100.0, 100.0], &mut env);
+    scroll.set_position([0.0, 0.0]);
+    scroll.position_children(&mut env);
+
+    let finger = touch::Id::new(1);
+    let touch = |scroll: &mut Scroll<()>, event: TouchEvent, env: &mut Environment<()>| {
+        env.reset_propagation();
+        scroll.handle_other_event(&WidgetEvent::Touch(event), env, &mut ());
+        env.is_propagation_stopped()
+    };
+
+    // Small movements might be part of a tap on the content, and are not consumed.
+    assert!(!touch(&mut scroll, TouchEvent::Start(finger, [50.0, 50.0]), &mut env));
+    assert!(!touch(&mut scroll, TouchEvent::Move(finger, [50.0, 45.0]), &mut env));
+    assert_eq!(scroll.scroll_offset, [0.0, 0.0]);
+
+    assert!(touch(&mut scroll, TouchEvent::Move(finger, [50.0, 20.0]), &mut env));
+    assert_eq!(scroll.scroll_offset, [0.0, -25.0]);
+
+    // Lifting the finger right after moving it flings the content.
+    assert!(touch(&mut scroll, TouchEvent::End(finger, [50.0, 20.0]), &mut env));
+    assert!(scroll.momentum.is_some());
+    assert!(env.take_tick_request());
+}
 
+#[test]
+fn momentum_decays_until_the_content_stops() {
+    let mut env = Environment::<()>::new(vec![], [100.0, 100.0], 1.0);
+    let mut scroll = Scroll::new(Rectangle::initialize(vec![]).frame(100.0, 400.0));
+
+    scroll.calculate_size([100.0, 100.0], &mut env);
+
+    // After half a second the content moved 50 points, and kept a third of its speed.
+    scroll.momentum = Some(([0.0, -100.0], Instant::now() - Duration::from_millis(500)));
+    scroll.step_momentum(&mut env);
+
+    let (velocity, _) = scroll.momentum.expect("The content is still moving");
+    assert!((scroll.scroll_offset[1] + 50.0).abs() < 0.5);
+    assert!((velocity[1] + 100.0 * MOMENTUM_DECAY.powf(0.5)).abs() < 0.5);
+    assert!(env.take_tick_request());
+
+    // Below the minimum speed the content stops.
+    scroll.momentum = Some((velocity, Instant::now() - Duration::from_secs(1)));
+    scroll.step_momentum(&mut env);
+
+    assert!(scroll.momentum.is_none());
+    assert!(!env.take_tick_request());
+}
@@ -65,7 +65,7 @@ impl<GS: GlobalState> Layout<GS> for Capsule<GS> {
         requested_size
     }
 
-    fn position_children(&mut self) {}
+    fn position_children(&mut self, env: &mut Environment<GS>) {}
 }
 
 impl<S: GlobalState> CommonWidget<S> for Capsule<S> {
@@ -67,7 +67,7 @@ impl<GS: GlobalState> Layout<GS> for Ellipse<GS> {
         requested_size
     }
 
-    fn position_children(&mut self) {}
+    fn position_children(&mut self, env: &mut Environment<GS>) {}
 }
 
 impl<GS: GlobalState> CommonWidget<GS> for Ellipse<GS> {
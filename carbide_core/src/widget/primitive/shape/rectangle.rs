@@ -145,7 +145,7 @@ impl<GS: GlobalState> Layout<GS> for Rectangle<GS> {
         let mut max_child_size = [0.0, 0.0];
 
         for child in &mut self.children {
-            let child_size = child.calculate_size(requested_size, env);
+            let child_size = child.calculate_size_cached(requested_size, env);
 
             if child_size[0] > max_child_size[0] {
                 max_child_size[0] = child_size[0];
@@ -165,14 +165,14 @@ impl<GS: GlobalState> Layout<GS> for Rectangle<GS> {
         self.dimension
     }
 
-    fn position_children(&mut self) {
-        let positioning = BasicLayouter::Center.position();
+    fn position_children(&mut self, env: &mut Environment<GS>) {
+        let positioning = BasicLayouter::Center.position(env);
         let position = self.position;
         let dimension = self.dimension;
 
         for child in &mut self.children {
             positioning(position, dimension, child);
-            child.position_children();
+            child.position_children(env);
         }
     }
 }
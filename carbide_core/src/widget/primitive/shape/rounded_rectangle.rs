@@ -68,7 +68,7 @@ impl<GS: GlobalState> Layout<GS> for RoundedRectangle<GS> {
         requested_size
     }
 
-    fn position_children(&mut self) {}
+    fn position_children(&mut self, env: &mut Environment<GS>) {}
 }
 
 impl<S: GlobalState> CommonWidget<S> for RoundedRectangle<S> {
@@ -34,18 +34,18 @@ impl<T: StateContract, GS: GlobalState> Layout<GS> for SharedState<T, GS> {
     }
 
     fn calculate_size(&mut self, requested_size: Dimensions, env: &mut Environment<GS>) -> Dimensions {
-        self.dimension = self.child.calculate_size(requested_size, env);
+        self.dimension = self.child.calculate_size_cached(requested_size, env);
         self.dimension
     }
 
-    fn position_children(&mut self) {
-        let positioning = BasicLayouter::Center.position();
+    fn position_children(&mut self, env: &mut Environment<GS>) {
+        let positioning = BasicLayouter::Center.position(env);
         let position = self.position;
         let dimension = self.dimension;
 
         positioning(position, dimension, &mut self.child);
 
-        self.child.position_children();
+        self.child.position_children(env);
     }
 }
 
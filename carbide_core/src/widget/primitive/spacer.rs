@@ -44,7 +44,7 @@ impl<GS: GlobalState> Layout<GS> for Spacer {
         self.dimension
     }
 
-    fn position_children(&mut self) {}
+    fn position_children(&mut self, env: &mut Environment<GS>) {}
 }
 
 impl<S: GlobalState> CommonWidget<S> for Spacer {
@@ -0,0 +1,374 @@
+use crate::cursor::MouseCursor;
+use crate::event_handler::MouseEvent;
+use crate::input::MouseButton;
+use crate::prelude::*;
+
+/// A container laying out panes next to each other, separated by dividers that can be dragged
+/// to resize the panes. The position of each divider is stored in a state, as the distance from
+/// the start of the split view to the divider, such that it can be bound and persisted.
+#[derive(Debug, Clone, Widget)]
+#[event(handle_mouse_event)]
+#[state_sync(insert_local_state, update_all_widget_state, update_local_widget_state)]
+pub struct SplitView<GS> where GS: GlobalState {
+    id: Uuid,
+    panes: Vec<Box<dyn Widget<GS>>>,
+    position: Point,
+    dimension: Dimensions,
+    /// The axis the panes are laid out along. 0 for side by side and 1 for above each other.
+    main_axis: usize,
+    dividers: Vec<F64State<GS>>,
+    /// False until the dividers are given positions, either by binding them or by splitting
+    /// the available space evenly when first laid out.
+    dividers_placed: bool,
+    min_sizes: Vec<Scalar>,
+    divider_thickness: Scalar,
+    /// The size of the area around a divider that can be dragged.
+    divider_hit_size: Scalar,
+    divider_lines: Vec<Box<dyn Widget<GS>>>,
+    dragged_divider: Option<usize>,
+}
+
+impl<GS: GlobalState> SplitView<GS> {
+    /// Panes side by side, separated by vertical dividers.
+    pub fn horizontal(panes: Vec<Box<dyn Widget<GS>>>) -> Box<Self> {
+        SplitView::new(panes, 0)
+    }
+
+    /// Panes above each other, separated by horizontal dividers.
+    pub fn vertical(panes: Vec<Box<dyn Widget<GS>>>) -> Box<Self> {
+        SplitView::new(panes, 1)
+    }
+
+    fn new(panes: Vec<Box<dyn Widget<GS>>>, main_axis: usize) -> Box<Self> {
+        let divider_count = panes.len().max(1) - 1;
+
+        Box::new(SplitView {
+            id: Uuid::new_v4(),
+            min_sizes: vec![0.0; panes.len()],
+            panes,
+            position: [0.0, 0.0],
+            dimension: [100.0, 100.0],
+            main_axis,
+            dividers: (0..divider_count).map(|_| CommonState::new(&0.0).into()).collect(),
+            dividers_placed: false,
+            divider_thickness: 1.0,
+            divider_hit_size: 8.0,
+            divider_lines: (0..divider_count).map(|_| Rectangle::initialize(vec![]).fill(EnvironmentColor::OpaqueSeparator) as Box<dyn Widget<GS>>).collect(),
+            dragged_divider: None,
+        })
+    }
+
+    /// Bind the positions of the dividers, one less than the number of panes.
+    pub fn dividers(mut self, dividers: Vec<F64State<GS>>) -> Box<Self> {
+        assert_eq!(dividers.len(), self.dividers.len(), "A split view needs one divider less than the number of panes.");
+
+        self.dividers = dividers;
+        self.dividers_placed = true;
+        Box::new(self)
+    }
+
+    /// The minimum size of each pane along the main axis.
+    pub fn min_sizes(mut self, min_sizes: Vec<Scalar>) -> Box<Self> {
+        self.min_sizes = min_sizes;
+        self.min_sizes.resize(self.panes.len(), 0.0);
+        Box::new(self)
+    }
+
+    pub fn divider_thickness(mut self, thickness: Scalar) -> Box<Self> {
+        self.divider_thickness = thickness;
+        Box::new(self)
+    }
+
+    fn insert_local_state(&self, env: &mut Environment<GS>) {
+        for divider in &self.dividers {
+            env.insert_local_state(divider);
+        }
+    }
+
+    fn update_all_widget_state(&mut self, env: &mut Environment<GS>, global_state: &GS) {
+        for divider in &mut self.dividers {
+            divider.get_value(env, global_state);
+        }
+
+        for line in &mut self.divider_lines {
+            line.sync_state(env, global_state);
+        }
+
+        // The dividers are not marked as states, so their changes are tracked here.
+        let mut marker = ChangeMarker::new();
+        for divider in &self.dividers {
+            marker.add(divider.get_latest_value());
+        }
+        env.track_state_values(self.id, marker.finish());
+    }
+
+    fn update_local_widget_state(&mut self, env: &Environment<GS>) {
+        for divider in &mut self.dividers {
+            env.update_local_state(divider);
+        }
+    }
+
+    /// The divider with a draggable area containing the point.
+    fn divider_at(&self, point: Point) -> Option<usize> {
+        if !self.is_inside(point) {
+            return None
+        }
+
+        let axis = self.main_axis;
+
+        self.dividers.iter().position(|divider| {
+            let center = self.position[axis] + *divider.get_latest_value() + self.divider_thickness / 2.0;
+
+            (point[axis] - center).abs() <= self.divider_hit_size / 2.0
+        })
+    }
+
+    fn resize_cursor(&self) -> MouseCursor {
+        if self.main_axis == 0 {
+            MouseCursor::ResizeHorizontal
+        } else {
+            MouseCursor::ResizeVertical
+        }
+    }
+
+    fn handle_mouse_event(&mut self, event: &MouseEvent, env: &mut Environment<GS>, global_state: &mut GS) {
+        match event {
+            MouseEvent::Move { to, .. } => {
+                if self.divider_at(*to).is_some() {
+                    env.set_cursor(self.resize_cursor());
+                }
+            }
+            MouseEvent::Press(MouseButton::Left, point, _) => {
+                self.dragged_divider = self.divider_at(*point);
+            }
+            MouseEvent::Drag { to, .. } => {
+                if let Some(index) = self.dragged_divider {
+                    env.set_cursor(self.resize_cursor());
+
+                    let mut positions: Vec<Scalar> = self.dividers.iter().map(|divider| *divider.get_latest_value()).collect();
+                    positions[index] = to[self.main_axis] - self.position[self.main_axis] - self.divider_thickness / 2.0;
+
+                    constrain_dividers(&mut positions, &self.min_sizes, self.dimension[self.main_axis], self.divider_thickness);
+
+                    if positions[index] != *self.dividers[index].get_latest_value() {
+                        *self.dividers[index].get_value_mut(env, global_state) = positions[index];
+                    }
+                }
+            }
+            MouseEvent::Release(MouseButton::Left, ..) => {
+                self.dragged_divider = None;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Place the dividers between the minimum sizes of the panes on both sides of them. If the
+/// available size is too small for all the panes, the panes at the start keep their minimum size.
+fn constrain_dividers(positions: &mut [Scalar], min_sizes: &[Scalar], total: Scalar, thickness: Scalar) {
+    let count = positions.len();
+
+    for index in (0..count).rev() {
+        let next_start = if index + 1 < count { positions[index + 1] } else { total };
+        let max = next_start - thickness - min_sizes[index + 1];
+
+        positions[index] = positions[index].min(max);
+    }
+
+    for index in 0..count {
+        let previous_end = if index > 0 { positions[index - 1] + thickness } else { 0.0 };
+        let min = previous_end + min_sizes[index];
+
+        positions[index] = positions[index].max(min);
+    }
+}
+
+/// The start and the size of each pane along the main axis.
+fn pane_extents(positions: &[Scalar], total: Scalar, thickness: Scalar) -> Vec<(Scalar, Scalar)> {
+    (0..positions.len() + 1).map(|index| {
+        let start = if index > 0 { positions[index - 1] + thickness } else { 0.0 };
+        let end = if index < positions.len() { positions[index] } else { total };
+
+        (start, (end - start).max(0.0))
+    }).collect()
+}
+
+impl<GS: GlobalState> Layout<GS> for SplitView<GS> {
+    fn flexibility(&self) -> u32 {
+        0
+    }
+
+    fn calculate_size(&mut self, requested_size: Dimensions, env: &mut Environment<GS>) -> Dimensions {
+        let axis = self.main_axis;
+        let total = requested_size[axis];
+        let thickness = self.divider_thickness;
+
+        if !self.dividers_placed {
+            let pane_size = (total - thickness * self.dividers.len() as Scalar) / self.panes.len() as Scalar;
+
+            for (index, divider) in self.dividers.iter_mut().enumerate() {
+                *divider.get_latest_value_mut() = (index + 1) as Scalar * pane_size + index as Scalar * thickness;
+            }
+
+            self.dividers_placed = true;
+        }
+
+        let mut positions: Vec<Scalar> = self.dividers.iter().map(|divider| *divider.get_latest_value()).collect();
+        constrain_dividers(&mut positions, &self.min_sizes, total, thickness);
+
+        for (divider, position) in self.dividers.iter_mut().zip(positions.iter()) {
+            if divider.get_latest_value() != position {
+                *divider.get_latest_value_mut() = *position;
+            }
+        }
+
+        for (pane, (_, size)) in self.panes.iter_mut().zip(pane_extents(&positions, total, thickness)) {
+            let mut pane_size = requested_size;
+            pane_size[axis] = size;
+
+            pane.calculate_size_cached(pane_size, env);
+        }
+
+        for line in &mut self.divider_lines {
+            let mut line_size = requested_size;
+            line_size[axis] = thickness;
+
+            line.calculate_size_cached(line_size, env);
+        }
+
+        self.dimension = requested_size;
+        self.dimension
+    }
+
+    fn position_children(&mut self, env: &mut Environment<GS>) {
+        let axis = self.main_axis;
+        let position = self.position;
+        let dimension = self.dimension;
+        let thickness = self.divider_thickness;
+        let positions: Vec<Scalar> = self.dividers.iter().map(|divider| *divider.get_latest_value()).collect();
+
+        let positioning = BasicLayouter::TopLeading.position(env);
+
+        for (pane, (start, size)) in self.panes.iter_mut().zip(pane_extents(&positions, dimension[axis], thickness)) {
+            let mut pane_position = position;
+            pane_position[axis] += start;
+
+            let mut pane_dimension = dimension;
+            pane_dimension[axis] = size;
+
+            positioning(pane_position, pane_dimension, pane.deref_mut());
+            pane.position_children(env);
+        }
+
+        for (line, divider) in self.divider_lines.iter_mut().zip(positions.iter()) {
+            let mut line_position = position;
+            line_position[axis] += divider;
+
+            line.set_position(line_position);
+            line.position_children(env);
+        }
+    }
+}
+
+impl<GS: GlobalState> CommonWidget<GS> for SplitView<GS> {
+    fn get_id(&self) -> Uuid {
+        self.id
+    }
+
+    fn set_id(&mut self, id: Uuid) {
+        self.id = id;
+    }
+
+    fn get_flag(&self) -> Flags {
+        Flags::EMPTY
+    }
+
+    fn get_children(&self) -> WidgetIter<GS> {
+        self.panes
+            .iter()
+            .map(|x| x.deref())
+            .rfold(WidgetIter::Empty, |acc, x| {
+                if x.get_flag() == Flags::PROXY {
+                    WidgetIter::Multi(Box::new(x.get_children()), Box::new(acc))
+                } else {
+                    WidgetIter::Single(x, Box::new(acc))
+                }
+            })
+    }
+
+    fn get_children_mut(&mut self) -> WidgetIterMut<GS> {
+        self.panes
+            .iter_mut()
+            .map(|x| x.deref_mut())
+            .rfold(WidgetIterMut::Empty, |acc, x| {
+                if x.get_flag() == Flags::PROXY {
+                    WidgetIterMut::Multi(Box::new(x.get_children_mut()), Box::new(acc))
+                } else {
+                    WidgetIterMut::Single(x, Box::new(acc))
+                }
+            })
+    }
+
+    fn get_proxied_children(&mut self) -> WidgetIterMut<GS> {
+        self.panes.iter_mut()
+            .map(|x| x.deref_mut())
+            .rfold(WidgetIterMut::Empty, |acc, x| {
+                WidgetIterMut::Single(x, Box::new(acc))
+            })
+    }
+
+    fn get_proxied_children_rev(&mut self) -> WidgetIterMut<GS> {
+        self.panes.iter_mut()
+            .map(|x| x.deref_mut())
+            .fold(WidgetIterMut::Empty, |acc, x| {
+                WidgetIterMut::Single(x, Box::new(acc))
+            })
+    }
+
+    fn get_position(&self) -> Point {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Dimensions) {
+        self.position = position;
+    }
+
+    fn get_dimension(&self) -> Dimensions {
+        self.dimension
+    }
+
+    fn set_dimension(&mut self, dimensions: Dimensions) {
+        self.dimension = dimensions
+    }
+}
+
+impl<GS: GlobalState> Render<GS> for SplitView<GS> {
+    fn get_primitives(&mut self, env: &Environment<GS>, global_state: &GS) -> Vec<Primitive> {
+        let mut prims: Vec<Primitive> = self.get_children_mut().flat_map(|f| f.get_primitives(env, global_state)).collect();
+
+        for line in &mut self.divider_lines {
+            prims.extend(line.get_primitives(env, global_state));
+        }
+
+        prims
+    }
+}
+
+impl<GS: GlobalState> WidgetExt<GS> for SplitView<GS> {}
+
+#[test]
+fn dividers_respect_min_sizes() {
+    // Three panes of at least 100 in 500, separated by dividers of 1.
+    let min_sizes = [100.0, 100.0, 100.0];
+
+    let mut positions = [50.0, 480.0];
+    constrain_dividers(&mut positions, &min_sizes, 500.0, 1.0);
+    assert_eq!(positions, [100.0, 399.0]);
+
+    // When the space is too small, the first panes keep their minimum size.
+    let mut positions = [100.0, 250.0];
+    constrain_dividers(&mut positions, &min_sizes, 250.0, 1.0);
+    assert_eq!(positions, [100.0, 201.0]);
+
+    assert_eq!(pane_extents(&[100.0, 201.0], 400.0, 1.0), vec![(0.0, 100.0), (101.0, 100.0), (202.0, 198.0)]);
+}
@@ -26,6 +26,7 @@ pub struct Text<GS> where GS: GlobalState {
     position: Point,
     dimension: Dimensions,
     wrap_mode: Wrap,
+    justify: Justify,
     #[state] pub text: StringState<GS>,
     #[state] font_size: U32State<GS>,
     #[state] color: ColorState<GS>,
@@ -43,6 +44,7 @@ impl<GS: GlobalState> Text<GS> {
             position: [0.0, 0.0],
             dimension: [100.0, 100.0],
             wrap_mode: Wrap::Whitespace,
+            justify: Justify::Left,
             color: EnvironmentColor::Label.into(),
             internal_text: None,
         })
@@ -116,7 +118,8 @@ impl<GS: GlobalState> Text<GS> {
         self.justify(justify::Justify::Center)
     }
 
-    pub fn justify(self, _j: justify::Justify) -> Self {
+    pub fn justify(mut self, j: justify::Justify) -> Self {
+        self.justify = j;
         self
     }
 
@@ -168,6 +171,8 @@ impl<GS: GlobalState> Layout<GS> for Text<GS> {
         }
 
         if let Some(internal) = &mut self.internal_text {
+            internal.justify = self.justify.for_direction(env.get_layout_direction());
+
             let size = internal.calculate_size(Dimension::new(requested_size[0], requested_size[1]), env);
 
             self.dimension = [size.width, size.height]
@@ -178,7 +183,7 @@ impl<GS: GlobalState> Layout<GS> for Text<GS> {
         self.dimension
     }
 
-    fn position_children(&mut self) {
+    fn position_children(&mut self, env: &mut Environment<GS>) {
         let position = Position::new(self.get_x(), self.get_y());
         if let Some(internal) = &mut self.internal_text {
             internal.position(position)
@@ -267,4 +272,36 @@ impl<S: GlobalState> CommonWidget<S> for Text<S> {
     }
 }
 
-impl<GS: GlobalState> WidgetExt<GS> for Text<GS> {}
\ No newline at end of file
+impl<GS: GlobalState> WidgetExt<GS> for Text<GS> {}
+#[test]
+fn left_justified_text_is_right_aligned_in_a_right_to_left_layout() {
+    use crate::headless::HeadlessUi;
+    use crate::text::{FontFamily, FontStyle, FontWeight};
+    use crate::window::TWindow;
+
+    fn line_starts(direction: LayoutDirection) -> Vec<Scalar> {
+        let mut ui = HeadlessUi::new([400.0, 200.0], 1.0, ());
+
+        let mut family = FontFamily::new("NotoSans");
+        family.add_font("fonts/NotoSans/NotoSans-Regular.ttf", FontWeight::Normal, FontStyle::Normal);
+        ui.add_font_family(family);
+
+        let root: Box<dyn Widget<()>> = Text::new("Hello world\nHi").layout_direction(direction);
+        *ui.widgets_mut() = root;
+
+        ui.draw().primitives.into_iter()
+            .filter_map(|primitive| match primitive.kind {
+                PrimitiveKind::Text { text, .. } => text.first().map(|glyph| glyph.position().x),
+                _ => None,
+            })
+            .collect()
+    }
+
+    let left_to_right = line_starts(LayoutDirection::LeftToRight);
+    assert_eq!(left_to_right.len(), 2);
+    assert_eq!(left_to_right[0], left_to_right[1]);
+
+    // The shorter second line ends at the right edge, like the first line.
+    let right_to_left = line_starts(LayoutDirection::RightToLeft);
+    assert!(right_to_left[1] > right_to_left[0]);
+}
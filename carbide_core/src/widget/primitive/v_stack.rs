@@ -65,7 +65,7 @@ impl<GS: GlobalState> Layout<GS> for VStack<GS> {
 
         for (_, child) in children_flexibilty {
             let size_for_child = [size_for_children[0], size_for_children[1] / number_of_children_that_needs_sizing];
-            let chosen_size = child.calculate_size(size_for_child, env);
+            let chosen_size = child.calculate_size_cached(size_for_child, env);
 
             if chosen_size[0] > max_width {
                 max_width = chosen_size[0];
@@ -82,7 +82,7 @@ impl<GS: GlobalState> Layout<GS> for VStack<GS> {
         let rest_space = requested_size[1] - total_height - spacing_total;
 
         for spacer in self.get_children_mut().filter(|m| m.get_flag() == Flags::SPACER) {
-            let chosen_size = spacer.calculate_size([requested_size[0], rest_space / spacer_count], env);
+            let chosen_size = spacer.calculate_size_cached([requested_size[0], rest_space / spacer_count], env);
 
             if chosen_size[0] > max_width {
                 max_width = chosen_size[0];
@@ -96,7 +96,7 @@ impl<GS: GlobalState> Layout<GS> for VStack<GS> {
         self.dimension
     }
 
-    fn position_children(&mut self) {
+    fn position_children(&mut self, env: &mut Environment<GS>) {
         let mut height_offset = 0.0;
         let position = self.position;
         let dimension = self.dimension;
@@ -120,7 +120,7 @@ impl<GS: GlobalState> Layout<GS> for VStack<GS> {
             height_offset += child.get_height();
 
 
-            child.position_children();
+            child.position_children(env);
         }
     }
 }
@@ -4,19 +4,38 @@ use std::ops::{Deref, DerefMut};
 
 use dyn_clone::DynClone;
 
-use crate::event::event::Event;
+use crate::event::event::{Event, EventPropagation};
+use crate::environment::command_registry::Command;
 use crate::event_handler::{KeyboardEvent, MouseEvent, WidgetEvent};
 use crate::focus::{Focus, Focusable, Refocus};
+use crate::gesture::Gesture;
 use crate::prelude::*;
-use crate::widget::{EnvUpdating, Frame, Offset};
+use crate::widget::{AspectRatio, Background, EnvUpdating, FlexibleFrame, Frame, KeyboardShortcut, Offset, OnChange, OnGesture, OnHover, Overlay};
 use crate::widget::primitive::border::Border;
 use crate::widget::primitive::clip::Clip;
 use crate::widget::primitive::environment_updating::EnvironmentStateContainer;
 use crate::widget::primitive::hidden::Hidden;
 use crate::widget::primitive::padding::Padding;
 use crate::widget::types::edge_insets::EdgeInsets;
+use crate::widget::types::scale_mode::ScaleMode;
 
-pub trait Widget<S>: Event<S> + Layout<S> + Render<S> + Focusable<S> + DynClone where S: GlobalState {}
+pub trait Widget<S>: Event<S> + Layout<S> + Render<S> + Focusable<S> + DynClone where S: GlobalState {
+    /// Calculate the size of the widget, reusing the size from the previous layout if the widget
+    /// has not been invalidated and is requested the same size. Widgets should size their
+    /// children using this, such that unchanged parts of the tree are not sized again.
+    fn calculate_size_cached(&mut self, requested_size: Dimensions, env: &mut Environment<S>) -> Dimensions {
+        let id = self.get_id();
+
+        if let Some(size) = env.previous_size(id, requested_size, self.get_dimension()) {
+            return size;
+        }
+
+        let size = self.calculate_size(requested_size, env);
+        env.set_previous_size(id, requested_size, size, self.get_dimension());
+
+        size
+    }
+}
 
 //impl<S, T> Widget<S> for T where T: Event<S> + Layout<S> + Render<S> + DynClone {}
 
@@ -33,9 +52,54 @@ pub trait WidgetExt<GS: GlobalState>: Widget<GS> + Sized + 'static {
         Frame::init_width(width, Box::new(self))
     }
 
+    /// Make the widget at least the given width. Combined with a maximum width the widget takes
+    /// the available width within the bounds.
+    fn min_width(self, width: Scalar) -> Box<FlexibleFrame<GS>> {
+        FlexibleFrame::new(Box::new(self)).min_width(width)
+    }
+
+    /// The width requested from the widget when the available width is infinite.
+    fn ideal_width(self, width: Scalar) -> Box<FlexibleFrame<GS>> {
+        FlexibleFrame::new(Box::new(self)).ideal_width(width)
+    }
+
+    /// Make the widget at most the given width. Use `INFINITY` to allow the widget to take the
+    /// available width.
+    fn max_width(self, width: Scalar) -> Box<FlexibleFrame<GS>> {
+        FlexibleFrame::new(Box::new(self)).max_width(width)
+    }
+
+    fn min_height(self, height: Scalar) -> Box<FlexibleFrame<GS>> {
+        FlexibleFrame::new(Box::new(self)).min_height(height)
+    }
+
+    fn ideal_height(self, height: Scalar) -> Box<FlexibleFrame<GS>> {
+        FlexibleFrame::new(Box::new(self)).ideal_height(height)
+    }
+
+    fn max_height(self, height: Scalar) -> Box<FlexibleFrame<GS>> {
+        FlexibleFrame::new(Box::new(self)).max_height(height)
+    }
+
     fn padding<E: Into<EdgeInsets>>(self, edge_insets: E) -> Box<Padding<GS>> {
         Padding::init(edge_insets.into(), Box::new(self))
     }
+
+    /// Size the widget to the given ratio between its width and its height, fitting within or
+    /// filling the requested size.
+    fn aspect_ratio(self, ratio: Scalar, scale_mode: ScaleMode) -> Box<AspectRatio<GS>> {
+        AspectRatio::new(ratio, scale_mode, Box::new(self))
+    }
+
+    /// Draw the given widget behind this widget, sized to this widget.
+    fn background(self, background: Box<dyn Widget<GS>>) -> Box<Background<GS>> {
+        Background::new(Box::new(self), background)
+    }
+
+    /// Draw the given widget in front of this widget, aligned within this widget.
+    fn overlay(self, overlay: Box<dyn Widget<GS>>, alignment: BasicLayouter) -> Box<Overlay<GS>> {
+        Overlay::new(Box::new(self), overlay, alignment)
+    }
     fn clip(self) -> Box<Clip<GS>> {
         Clip::new(Box::new(self))
     }
@@ -65,6 +129,38 @@ pub trait WidgetExt<GS: GlobalState>: Widget<GS> + Sized + 'static {
 
         e
     }
+
+    /// Lay out the widget and its descendants in the given direction, unless a descendant sets
+    /// another direction.
+    fn layout_direction(self, direction: LayoutDirection) -> Box<EnvUpdating<GS>> {
+        let mut e = EnvUpdating::new(Box::new(self));
+        e.add(EnvironmentStateContainer::LayoutDirection { value: Box::new(CommonState::new(&direction)) });
+
+        e
+    }
+
+    /// Call the callback with the old and the new value, each time the value of the state changes.
+    fn on_change<T, K, C>(self, state: K, callback: C) -> Box<OnChange<GS, T>>
+        where T: StateContract + 'static, K: Into<TState<T, GS>>, C: Fn(&T, &T, &mut Environment<GS>, &mut GS) + Clone + 'static {
+        OnChange::new(state.into(), callback, Box::new(self))
+    }
+
+    /// Set the state to true while the mouse is over the widget, unless the widget is covered by
+    /// another widget.
+    fn on_hover<K: Into<BoolState<GS>>>(self, hovered: K) -> Box<OnHover<GS>> {
+        OnHover::new(hovered.into(), Box::new(self))
+    }
+
+    /// Recognize a gesture on the widget, like `TapGesture::new().count(2).on_end(...)`.
+    fn gesture<G: Gesture<GS> + 'static>(self, gesture: G) -> Box<OnGesture<GS>> {
+        OnGesture::new(Box::new(gesture), Box::new(self))
+    }
+
+    /// Execute the handler of the command when its keys are pressed while the widget is shown,
+    /// like `Command::new("file.save", "Save").binding(Key::S, ModifierKey::CTRL).on_execute(...)`.
+    fn keyboard_shortcut(self, command: Command<GS>) -> Box<KeyboardShortcut<GS>> {
+        KeyboardShortcut::new(command, Box::new(self))
+    }
 }
 
 //This does not currently work with intellisense
@@ -118,8 +214,8 @@ impl<S: GlobalState> CommonWidget<S> for Box<dyn Widget<S>> {
 
 
 impl<S: GlobalState> Event<S> for Box<dyn Widget<S>> {
-    fn handle_mouse_event(&mut self, event: &MouseEvent, consumed: &bool, env: &mut Environment<S>, global_state: &mut S) {
-        self.deref_mut().handle_mouse_event(event, consumed, env, global_state)
+    fn handle_mouse_event(&mut self, event: &MouseEvent, env: &mut Environment<S>, global_state: &mut S) {
+        self.deref_mut().handle_mouse_event(event, env, global_state)
     }
 
     fn handle_keyboard_event(&mut self, event: &KeyboardEvent, env: &mut Environment<S>, global_state: &mut S) {
@@ -130,8 +226,8 @@ impl<S: GlobalState> Event<S> for Box<dyn Widget<S>> {
         self.deref_mut().handle_other_event(event, env, global_state)
     }
 
-    fn process_mouse_event(&mut self, event: &MouseEvent, consumed: &bool, env: &mut Environment<S>, global_state: &mut S) {
-        self.deref_mut().process_mouse_event(event, consumed, env, global_state)
+    fn process_mouse_event(&mut self, event: &MouseEvent, env: &mut Environment<S>, global_state: &mut S) {
+        self.deref_mut().process_mouse_event(event, env, global_state)
     }
 
     fn process_keyboard_event(&mut self, event: &KeyboardEvent, env: &mut Environment<S>, global_state: &mut S) {
@@ -141,6 +237,26 @@ impl<S: GlobalState> Event<S> for Box<dyn Widget<S>> {
     fn process_other_event(&mut self, event: &WidgetEvent, env: &mut Environment<S>, global_state: &mut S) {
         self.deref_mut().process_other_event(event, env, global_state)
     }
+
+    fn capture_mouse_event(&mut self, event: &MouseEvent, env: &mut Environment<S>, global_state: &mut S) -> EventPropagation {
+        self.deref_mut().capture_mouse_event(event, env, global_state)
+    }
+
+    fn bubble_mouse_event(&mut self, event: &MouseEvent, env: &mut Environment<S>, global_state: &mut S) -> EventPropagation {
+        self.deref_mut().bubble_mouse_event(event, env, global_state)
+    }
+
+    fn capture_keyboard_event(&mut self, event: &KeyboardEvent, env: &mut Environment<S>, global_state: &mut S) -> EventPropagation {
+        self.deref_mut().capture_keyboard_event(event, env, global_state)
+    }
+
+    fn bubble_keyboard_event(&mut self, event: &KeyboardEvent, env: &mut Environment<S>, global_state: &mut S) -> EventPropagation {
+        self.deref_mut().bubble_keyboard_event(event, env, global_state)
+    }
+
+    fn hit_test(&self, point: Point, path: &mut Vec<Uuid>) -> bool {
+        self.deref().hit_test(point, path)
+    }
 }
 
 impl<S: GlobalState> StateSync<S> for Box<dyn Widget<S>> {
@@ -170,8 +286,8 @@ impl<GS: GlobalState> Layout<GS> for Box<dyn Widget<GS>> {
         self.deref_mut().calculate_size(requested_size, env)
     }
 
-    fn position_children(&mut self) {
-        self.deref_mut().position_children()
+    fn position_children(&mut self, env: &mut Environment<GS>) {
+        self.deref_mut().position_children(env)
     }
 }
 
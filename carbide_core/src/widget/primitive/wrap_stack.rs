@@ -0,0 +1,261 @@
+use crate::layout::MainAxisAlignment;
+use crate::prelude::*;
+use crate::widget::ChildRender;
+use crate::widget::primitive::flex::break_lines;
+
+/// A stack placing its children in a row, and moving the children that do not fit within the
+/// requested width onto new lines. Useful for lists of tags, chips and toolbar items.
+#[derive(Debug, Clone, Widget)]
+pub struct WrapStack<GS> where GS: GlobalState {
+    id: Uuid,
+    children: Vec<Box<dyn Widget<GS>>>,
+    position: Point,
+    dimension: Dimensions,
+    spacing: Scalar,
+    line_spacing: Scalar,
+    line_alignment: MainAxisAlignment,
+    /// The offset of each child from the position of the stack, calculated when sizing.
+    offsets: Vec<Point>,
+}
+
+impl<GS: GlobalState> WidgetExt<GS> for WrapStack<GS> {}
+
+impl<GS: GlobalState> WrapStack<GS> {
+    pub fn new(children: Vec<Box<dyn Widget<GS>>>) -> Box<Self> {
+        Box::new(WrapStack {
+            id: Uuid::new_v4(),
+            children,
+            position: [0.0, 0.0],
+            dimension: [100.0, 100.0],
+            spacing: 10.0,
+            line_spacing: 10.0,
+            line_alignment: MainAxisAlignment::Start,
+            offsets: vec![],
+        })
+    }
+
+    /// The horizontal space between the children of a line.
+    pub fn spacing(mut self, spacing: f64) -> Box<Self> {
+        self.spacing = spacing;
+        Box::new(self)
+    }
+
+    /// The vertical space between the lines.
+    pub fn line_spacing(mut self, spacing: f64) -> Box<Self> {
+        self.line_spacing = spacing;
+        Box::new(self)
+    }
+
+    /// How each line is aligned within the width of the stack. Unless the lines are aligned at
+    /// the start, the stack takes the full requested width.
+    pub fn line_alignment(mut self, alignment: MainAxisAlignment) -> Box<Self> {
+        self.line_alignment = alignment;
+        Box::new(self)
+    }
+}
+
+impl<GS: GlobalState> Layout<GS> for WrapStack<GS> {
+    fn flexibility(&self) -> u32 {
+        1
+    }
+
+    fn calculate_size(&mut self, requested_size: Dimensions, env: &mut Environment<GS>) -> Dimensions {
+        let sizes: Vec<Dimensions> = self.get_children_mut()
+            .map(|child| child.calculate_size_cached(requested_size, env))
+            .collect();
+
+        let widths: Vec<Scalar> = sizes.iter().map(|size| size[0]).collect();
+        let lines = break_lines(&widths, requested_size[0], self.spacing, true);
+
+        let line_widths: Vec<Scalar> = lines.iter()
+            .map(|line| widths[line.clone()].iter().sum::<Scalar>() + self.spacing * (line.len() - 1) as Scalar)
+            .collect();
+
+        let line_heights: Vec<Scalar> = lines.iter()
+            .map(|line| sizes[line.clone()].iter().map(|size| size[1]).fold(0.0, Scalar::max))
+            .collect();
+
+        let longest_line = line_widths.iter().cloned().fold(0.0, Scalar::max);
+        let width = if self.line_alignment == MainAxisAlignment::Start {
+            longest_line
+        } else {
+            longest_line.max(requested_size[0])
+        };
+
+        let mut offsets = vec![[0.0, 0.0]; sizes.len()];
+        let mut y = 0.0;
+
+        for ((line, line_width), line_height) in lines.iter().zip(line_widths).zip(line_heights.iter()) {
+            let free = (width - line_width).max(0.0);
+            let count = line.len() as Scalar;
+
+            let (mut x, extra_spacing) = match self.line_alignment {
+                MainAxisAlignment::Start => (0.0, 0.0),
+                MainAxisAlignment::Center => (free / 2.0, 0.0),
+                MainAxisAlignment::End => (free, 0.0),
+                MainAxisAlignment::SpaceBetween if count > 1.0 => (0.0, free / (count - 1.0)),
+                MainAxisAlignment::SpaceBetween => (0.0, 0.0),
+                MainAxisAlignment::SpaceAround => (free / count / 2.0, free / count),
+                MainAxisAlignment::SpaceEvenly => (free / (count + 1.0), free / (count + 1.0)),
+            };
+
+            // The children are centered vertically within their line.
+            for index in line.clone() {
+                offsets[index] = [x, y + (line_height - sizes[index][1]) / 2.0];
+                x += sizes[index][0] + self.spacing + extra_spacing;
+            }
+
+            y += line_height + self.line_spacing;
+        }
+
+        let height = line_heights.iter().sum::<Scalar>() + self.line_spacing * (lines.len().max(1) - 1) as Scalar;
+
+        self.offsets = offsets;
+        self.dimension = [width, height];
+
+        self.dimension
+    }
+
+    fn position_children(&mut self, env: &mut Environment<GS>) {
+        let position = self.position;
+        let dimension = self.dimension;
+        let offsets = self.offsets.clone();
+        let layout_direction = env.get_layout_direction();
+
+        for (child, offset) in self.get_children_mut().zip(offsets) {
+            // In a right to left layout the lines start at the right edge.
+            match layout_direction {
+                LayoutDirection::LeftToRight => child.set_x(position[0] + offset[0]),
+                LayoutDirection::RightToLeft => child.set_x(position[0] + dimension[0] - offset[0] - child.get_width()),
+            }
+
+            child.set_y(position[1] + offset[1]);
+            child.position_children(env);
+        }
+    }
+}
+
+impl<GS: GlobalState> CommonWidget<GS> for WrapStack<GS> {
+    fn get_id(&self) -> Uuid {
+        self.id
+    }
+
+    fn set_id(&mut self, id: Uuid) {
+        self.id = id;
+    }
+
+    fn get_flag(&self) -> Flags {
+        Flags::EMPTY
+    }
+
+    fn get_children(&self) -> WidgetIter<GS> {
+        self.children
+            .iter()
+            .map(|x| x.deref())
+            .rfold(WidgetIter::Empty, |acc, x| {
+                if x.get_flag() == Flags::PROXY {
+                    WidgetIter::Multi(Box::new(x.get_children()), Box::new(acc))
+                } else {
+                    WidgetIter::Single(x, Box::new(acc))
+                }
+            })
+    }
+
+    fn get_children_mut(&mut self) -> WidgetIterMut<GS> {
+        self.children
+            .iter_mut()
+            .map(|x| x.deref_mut())
+            .rfold(WidgetIterMut::Empty, |acc, x| {
+                if x.get_flag() == Flags::PROXY {
+                    WidgetIterMut::Multi(Box::new(x.get_children_mut()), Box::new(acc))
+                } else {
+                    WidgetIterMut::Single(x, Box::new(acc))
+                }
+            })
+    }
+
+    fn get_proxied_children(&mut self) -> WidgetIterMut<GS> {
+        self.children.iter_mut()
+            .map(|x| x.deref_mut())
+            .rfold(WidgetIterMut::Empty, |acc, x| {
+                WidgetIterMut::Single(x, Box::new(acc))
+            })
+    }
+
+    fn get_proxied_children_rev(&mut self) -> WidgetIterMut<GS> {
+        self.children.iter_mut()
+            .map(|x| x.deref_mut())
+            .fold(WidgetIterMut::Empty, |acc, x| {
+                WidgetIterMut::Single(x, Box::new(acc))
+            })
+    }
+
+    fn get_position(&self) -> Point {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Dimensions) {
+        self.position = position;
+    }
+
+    fn get_dimension(&self) -> Dimensions {
+        self.dimension
+    }
+
+    fn set_dimension(&mut self, dimensions: Dimensions) {
+        self.dimension = dimensions
+    }
+}
+
+impl<GS: GlobalState> ChildRender for WrapStack<GS> {}
+
+#[test]
+fn spacing_separates_the_children_of_a_line() {
+    let mut env = Environment::<()>::new(vec![], [100.0, 100.0], 1.0);
+    let mut stack = WrapStack::new(vec![
+        Rectangle::initialize(vec![]).frame(40.0, 10.0),
+        Rectangle::initialize(vec![]).frame(40.0, 10.0),
+        Rectangle::initialize(vec![]).frame(40.0, 10.0),
+    ]).spacing(20.0).line_spacing(0.0);
+
+    assert_eq!(stack.calculate_size([100.0, 100.0], &mut env), [100.0, 20.0]);
+    stack.set_position([0.0, 0.0]);
+    stack.position_children(&mut env);
+
+    let positions: Vec<Point> = stack.get_children().map(|child| child.get_position()).collect();
+    assert_eq!(positions, vec![[0.0, 0.0], [60.0, 0.0], [0.0, 10.0]]);
+}
+
+#[test]
+fn line_spacing_separates_the_lines() {
+    let mut env = Environment::<()>::new(vec![], [100.0, 100.0], 1.0);
+    let mut stack = WrapStack::new(vec![
+        Rectangle::initialize(vec![]).frame(40.0, 10.0),
+        Rectangle::initialize(vec![]).frame(40.0, 10.0),
+        Rectangle::initialize(vec![]).frame(40.0, 10.0),
+    ]).spacing(0.0).line_spacing(15.0);
+
+    assert_eq!(stack.calculate_size([100.0, 100.0], &mut env), [80.0, 35.0]);
+    stack.set_position([0.0, 0.0]);
+    stack.position_children(&mut env);
+
+    let positions: Vec<Point> = stack.get_children().map(|child| child.get_position()).collect();
+    assert_eq!(positions, vec![[0.0, 0.0], [40.0, 0.0], [0.0, 25.0]]);
+}
+
+#[test]
+fn lines_are_aligned_by_the_line_alignment() {
+    let mut env = Environment::<()>::new(vec![], [100.0, 100.0], 1.0);
+    let mut stack = WrapStack::new(vec![
+        Rectangle::initialize(vec![]).frame(40.0, 10.0),
+        Rectangle::initialize(vec![]).frame(40.0, 10.0),
+        Rectangle::initialize(vec![]).frame(40.0, 10.0),
+    ]).spacing(10.0).line_spacing(10.0).line_alignment(MainAxisAlignment::End);
+
+    assert_eq!(stack.calculate_size([100.0, 100.0], &mut env), [100.0, 30.0]);
+    stack.set_position([0.0, 0.0]);
+    stack.position_children(&mut env);
+
+    let positions: Vec<Point> = stack.get_children().map(|child| child.get_position()).collect();
+    assert_eq!(positions, vec![[10.0, 0.0], [60.0, 0.0], [60.0, 20.0]]);
+}
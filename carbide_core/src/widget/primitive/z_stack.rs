@@ -46,7 +46,7 @@ impl<GS: GlobalState> Layout<GS> for ZStack<GS> {
         let mut max_height = 0.0;
 
         for (_, child) in children_flexibilty {
-            let chosen_size = child.calculate_size(requested_size, env);
+            let chosen_size = child.calculate_size_cached(requested_size, env);
 
             if chosen_size[0] > max_width {
                 max_width = chosen_size[0];
@@ -61,14 +61,14 @@ impl<GS: GlobalState> Layout<GS> for ZStack<GS> {
         self.dimension
     }
 
-    fn position_children(&mut self) {
-        let positioning = self.alignment.position();
+    fn position_children(&mut self, env: &mut Environment<GS>) {
+        let positioning = self.alignment.position(env);
         let position = self.position;
         let dimension = self.dimension;
 
         for child in self.get_children_mut() {
             positioning(position, dimension, child);
-            child.position_children();
+            child.position_children(env);
         }
     }
 }
@@ -148,3 +148,33 @@ impl<S: GlobalState> CommonWidget<S> for ZStack<S> {
 impl<S: GlobalState> ChildRender for ZStack<S> {}
 
 
+
+#[test]
+fn topmost_child_is_hit() {
+    use crate::event::event::Event;
+
+    let mut env = Environment::<()>::new(vec![], [100.0, 100.0], 1.0);
+    let mut stack = ZStack::initialize(vec![
+        Rectangle::initialize(vec![]),
+        Rectangle::initialize(vec![]).frame(10.0, 10.0),
+    ]);
+
+    stack.calculate_size([100.0, 100.0], &mut env);
+    stack.set_position([0.0, 0.0]);
+    stack.position_children(&mut env);
+
+    let ids: Vec<Uuid> = stack.get_children().map(|child| child.get_id()).collect();
+
+    // The frame is drawn on top, in the center of the stack.
+    let mut path = vec![];
+    assert!(stack.hit_test([50.0, 50.0], &mut path));
+    assert_eq!(&path[1..], &[ids[1], stack.get_id()]);
+
+    let mut path = vec![];
+    assert!(stack.hit_test([5.0, 5.0], &mut path));
+    assert_eq!(path, vec![ids[0], stack.get_id()]);
+
+    let mut path = vec![];
+    assert!(!stack.hit_test([150.0, 5.0], &mut path));
+    assert!(path.is_empty());
+}
@@ -1,3 +1,5 @@
+use crate::layout::LayoutDirection;
+
 /// A type used for referring to typographic alignment of `Text`.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Justify {
@@ -12,3 +14,16 @@ pub enum Justify {
     // /// Extra space is added between words in order to achieve this alignment.
     // TODO: Fill,
 }
+
+
+impl Justify {
+    /// The justification aligning to the same side when laid out in the given direction. Left is
+    /// the start of a line, so in a right to left layout left and right are swapped.
+    pub fn for_direction(&self, direction: LayoutDirection) -> Justify {
+        match (self, direction) {
+            (Justify::Left, LayoutDirection::RightToLeft) => Justify::Right,
+            (Justify::Right, LayoutDirection::RightToLeft) => Justify::Left,
+            (justify, _) => *justify,
+        }
+    }
+}
@@ -1,7 +1,31 @@
+use crate::position::Dimensions;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum ScaleMode {
     Stretch,
     Fit,
     Fill
-}
\ No newline at end of file
+}
+
+impl ScaleMode {
+    /// Scale the size to the requested size. Fit and fill keep the aspect ratio of the size, and
+    /// scale it to fit within or to cover the requested size respectively.
+    pub fn scale(&self, size: Dimensions, requested_size: Dimensions) -> Dimensions {
+        let width_factor = requested_size[0] / size[0];
+        let height_factor = requested_size[1] / size[1];
+
+        match self {
+            ScaleMode::Fit => {
+                let scale_factor = width_factor.min(height_factor);
+
+                [size[0] * scale_factor, size[1] * scale_factor]
+            }
+            ScaleMode::Fill => {
+                let scale_factor = width_factor.max(height_factor);
+
+                [size[0] * scale_factor, size[1] * scale_factor]
+            }
+            ScaleMode::Stretch => requested_size,
+        }
+    }
+}
@@ -134,7 +134,7 @@ pub fn impl_widget(ast: &syn::DeriveInput) -> proc_macro2::TokenStream {
     };
 
     let handle_mouse_event = if let Some(_) = struct_attributes.get("event.handle_mouse_event") {
-        quote! {#struct_ident::handle_mouse_event(self, event, consumed, env, global_state);}
+        quote! {#struct_ident::handle_mouse_event(self, event, env, global_state);}
     } else {
         quote! {}
     };
@@ -151,10 +151,60 @@ pub fn impl_widget(ast: &syn::DeriveInput) -> proc_macro2::TokenStream {
         quote! {}
     };
 
+    let hit_test = if let Some(_) = struct_attributes.get("event.hit_test") {
+        quote! {
+            fn hit_test(&self, point: carbide_core::Point, path: &mut Vec<carbide_core::widget::Id>) -> bool {
+                #struct_ident::hit_test(self, point, path)
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let capture_mouse_event = if let Some(_) = struct_attributes.get("event.capture_mouse_event") {
+        quote! {
+            fn capture_mouse_event(&mut self, event: &carbide_core::event_handler::MouseEvent, env: &mut carbide_core::prelude::Environment<#global_state_use>, global_state: &mut #global_state_use) -> carbide_core::event::event::EventPropagation {
+                #struct_ident::capture_mouse_event(self, event, env, global_state)
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let bubble_mouse_event = if let Some(_) = struct_attributes.get("event.bubble_mouse_event") {
+        quote! {
+            fn bubble_mouse_event(&mut self, event: &carbide_core::event_handler::MouseEvent, env: &mut carbide_core::prelude::Environment<#global_state_use>, global_state: &mut #global_state_use) -> carbide_core::event::event::EventPropagation {
+                #struct_ident::bubble_mouse_event(self, event, env, global_state)
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let capture_keyboard_event = if let Some(_) = struct_attributes.get("event.capture_keyboard_event") {
+        quote! {
+            fn capture_keyboard_event(&mut self, event: &carbide_core::event_handler::KeyboardEvent, env: &mut carbide_core::prelude::Environment<#global_state_use>, global_state: &mut #global_state_use) -> carbide_core::event::event::EventPropagation {
+                #struct_ident::capture_keyboard_event(self, event, env, global_state)
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let bubble_keyboard_event = if let Some(_) = struct_attributes.get("event.bubble_keyboard_event") {
+        quote! {
+            fn bubble_keyboard_event(&mut self, event: &carbide_core::event_handler::KeyboardEvent, env: &mut carbide_core::prelude::Environment<#global_state_use>, global_state: &mut #global_state_use) -> carbide_core::event::event::EventPropagation {
+                #struct_ident::bubble_keyboard_event(self, event, env, global_state)
+            }
+        }
+    } else {
+        quote! {}
+    };
+
     let process_mouse_event = if let Some(_) = struct_attributes.get("event.process_mouse_event") {
-        quote! {#struct_ident::process_mouse_event(self, event, consumed, env, global_state);}
+        quote! {#struct_ident::process_mouse_event(self, event, env, global_state);}
     } else {
-        quote! {self.process_mouse_event_default(event, consumed, env, global_state);}
+        quote! {self.process_mouse_event_default(event, env, global_state);}
     };
 
     let process_keyboard_event = if let Some(_) = struct_attributes.get("event.process_keyboard_event") {
@@ -314,6 +364,24 @@ pub fn impl_widget(ast: &syn::DeriveInput) -> proc_macro2::TokenStream {
         quote! {}
     };
 
+    // Let the states write back changes made while handling the event. This is needed for
+    // states, like the mapped state, that hand out a mutable reference to a copy of their value.
+    let release_states = quote! {
+        #(carbide_core::state::State::release_state(&mut self.#state_idents, env, global_state);)*
+    };
+
+    // Let the environment invalidate the layout of the widget when the values of its states
+    // have changed since they were last updated.
+    let track_state_values = if state_idents.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            let mut marker = carbide_core::state::ChangeMarker::new();
+            #(marker.add(carbide_core::state::State::get_latest_value(&self.#state_idents));)*
+            env.track_state_values(carbide_core::widget::CommonWidget::get_id(self), marker.finish());
+        }
+    };
+
     let wheres = filtered_where_clause(&ast);
 
     quote! {
@@ -356,7 +424,7 @@ pub fn impl_widget(ast: &syn::DeriveInput) -> proc_macro2::TokenStream {
 
         #[automatically_derived]
         impl<#(#generics_without_gs ,)* #global_state> carbide_core::event::event::Event<#global_state_use> for #struct_ident #generics_with_gs #wheres {
-            fn handle_mouse_event(&mut self, event: &carbide_core::event_handler::MouseEvent, consumed: &bool, env: &mut carbide_core::prelude::Environment<#global_state_use>, global_state: &mut #global_state_use) {
+            fn handle_mouse_event(&mut self, event: &carbide_core::event_handler::MouseEvent, env: &mut carbide_core::prelude::Environment<#global_state_use>, global_state: &mut #global_state_use) {
                 #handle_mouse_event
             }
 
@@ -369,17 +437,30 @@ pub fn impl_widget(ast: &syn::DeriveInput) -> proc_macro2::TokenStream {
                 #handle_other_event
             }
 
-            fn process_mouse_event(&mut self, event: &carbide_core::event_handler::MouseEvent, consumed: &bool, env: &mut carbide_core::prelude::Environment<#global_state_use>, global_state: &mut #global_state_use) {
+            fn process_mouse_event(&mut self, event: &carbide_core::event_handler::MouseEvent, env: &mut carbide_core::prelude::Environment<#global_state_use>, global_state: &mut #global_state_use) {
                 #process_mouse_event
+                #release_states
             }
 
             fn process_keyboard_event(&mut self, event: &carbide_core::event_handler::KeyboardEvent, env: &mut carbide_core::prelude::Environment<#global_state_use>, global_state: &mut #global_state_use) {
                 #process_keyboard_event
+                #release_states
             }
 
             fn process_other_event(&mut self, event: &carbide_core::event_handler::WidgetEvent, env: &mut carbide_core::prelude::Environment<#global_state_use>, global_state: &mut #global_state_use) {
                 #process_other_event
+                #release_states
             }
+
+            #capture_mouse_event
+
+            #bubble_mouse_event
+
+            #capture_keyboard_event
+
+            #bubble_keyboard_event
+
+            #hit_test
         }
 
 
@@ -396,6 +477,8 @@ pub fn impl_widget(ast: &syn::DeriveInput) -> proc_macro2::TokenStream {
                 #(self.#state_idents.get_value(env, global_state);)*
 
                 #update_all_widget_state
+
+                #track_state_values
             }
 
             fn update_local_widget_state(&mut self, env: &carbide_core::prelude::Environment<#global_state_use>) {